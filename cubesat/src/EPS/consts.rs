@@ -1,3 +0,0 @@
-// Average solar flux in LEO (Watts per square meter):
-// value taken from NASA's On-Orbit_Thermal_Environments_TFAWS_2014.pdf
-const SOLAR_FLUX_LEO_AVG_W_M2: f64 = 1367.0; 
\ No newline at end of file
@@ -0,0 +1,300 @@
+// Batch runs for array and battery sizing: sweeps and Monte Carlo draws over scenario parameters.
+// Swept parameters form a full grid; every grid point is run `runs` times with the random parameters
+// redrawn, and all runs execute in parallel. Each run is seeded from its index, so a batch is reproducible.
+use std::path::{Path, PathBuf};
+
+use eps::{SatelliteOperationalMode, SheddingAction};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution as _, Normal, Uniform};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::scenario::{Scenario, ScenarioError, check_positive};
+use crate::simulation::simulate;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchConfig {
+    pub scenario: Option<PathBuf>, // Relative to the batch file
+    #[serde(default = "default_runs")]
+    pub runs: usize,               // Per grid point
+    #[serde(default)]
+    pub seed: u64,
+    pub step_s: Option<f64>,       // Defaults to the scenario's
+    #[serde(default)]
+    pub parameters: Vec<ParameterSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParameterSpec {
+    #[serde(flatten)]
+    pub parameter: Parameter,
+    #[serde(flatten)]
+    pub distribution: Distribution,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "parameter", rename_all = "snake_case")]
+pub enum Parameter {
+    PanelEfficiency,            // Every panel
+    PanelDegradationPerYear,
+    MissionAgeYears,
+    BatteryCapacityWh,
+    InitialSoc,
+    LoadPowerW { load: String },
+    OrbitAltitudeKm,
+    BetaAngleDeg,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum Distribution {
+    Values { values: Vec<f64> }, // Swept
+    Uniform { min: f64, max: f64 },
+    Normal { mean: f64, std_dev: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub parameters: Vec<(String, f64)>,
+    pub min_soc_percentage: f64,
+    pub final_soc_percentage: f64,
+    pub time_in_safe_mode_s: f64,
+    pub tier_sheds: Vec<(u8, usize)>, // Times each shedding tier fired, by priority
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Statistics {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+    pub p05: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierStatistics {
+    pub priority: u8,
+    pub fired_fraction: f64, // Of runs where the tier fired at least once
+    pub mean_sheds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchReport {
+    pub runs: Vec<RunSummary>,
+    pub min_soc_percentage: Statistics,
+    pub time_in_safe_mode_s: Statistics,
+    pub safe_mode_fraction: f64, // Of runs that entered SafeMode
+    pub tiers: Vec<TierStatistics>,
+}
+
+impl BatchConfig {
+    pub fn from_file(path: &Path) -> Result<Self, ScenarioError> {
+        let text = std::fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        let mut config: BatchConfig = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => BatchConfig::from_toml(&text)?,
+            Some("json") => BatchConfig::from_json(&text)?,
+            _ => return Err(ScenarioError::UnsupportedFormat),
+        };
+        if let (Some(scenario), Some(directory)) = (&config.scenario, path.parent()) {
+            config.scenario = Some(directory.join(scenario));
+        }
+        Ok(config)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ScenarioError> {
+        let config: BatchConfig = toml::from_str(text).map_err(|error| ScenarioError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        let config: BatchConfig = serde_json::from_str(text).map_err(|error| ScenarioError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // What the format cannot express. Checked up front so a bad entry fails the batch, not a worker.
+    fn validate(&self) -> Result<(), ScenarioError> {
+        if let Some(step_s) = self.step_s {
+            check_positive("step_s", step_s)?;
+        }
+        if self.runs == 0 {
+            return Err(ScenarioError::Parse("`runs` must be at least 1".into()));
+        }
+        self.parameters.iter().try_for_each(|spec| spec.distribution.validate(&spec.parameter.label()))
+    }
+}
+
+impl Distribution {
+    fn validate(&self, label: &str) -> Result<(), ScenarioError> {
+        let valid = match self {
+            Distribution::Values { values } => !values.is_empty() && values.iter().all(|value| value.is_finite()),
+            Distribution::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+            Distribution::Normal { mean, std_dev } => mean.is_finite() && std_dev.is_finite() && *std_dev >= 0.0,
+        };
+        if valid {
+            return Ok(());
+        }
+        Err(ScenarioError::Parse(format!("invalid distribution for `{label}`: {self:?}")))
+    }
+}
+
+impl Parameter {
+    pub fn label(&self) -> String {
+        match self {
+            Parameter::PanelEfficiency => "panel_efficiency".into(),
+            Parameter::PanelDegradationPerYear => "panel_degradation_per_year".into(),
+            Parameter::MissionAgeYears => "mission_age_years".into(),
+            Parameter::BatteryCapacityWh => "battery_capacity_wh".into(),
+            Parameter::InitialSoc => "initial_soc".into(),
+            Parameter::LoadPowerW { load } => format!("load_power_w[{load}]"),
+            Parameter::OrbitAltitudeKm => "orbit_altitude_km".into(),
+            Parameter::BetaAngleDeg => "beta_angle_deg".into(),
+        }
+    }
+
+    /// Sets the parameter in `scenario`.
+    pub fn apply(&self, scenario: &mut Scenario, value: f64) -> Result<(), ScenarioError> {
+        match self {
+            Parameter::PanelEfficiency => scenario.panels.iter_mut().for_each(|panel| panel.efficiency = value),
+            Parameter::PanelDegradationPerYear => scenario.panel_degradation_per_year = value,
+            Parameter::MissionAgeYears => scenario.mission_age_years = value,
+            Parameter::BatteryCapacityWh => scenario.battery.capacity_wh = value,
+            Parameter::InitialSoc => scenario.battery.initial_soc = value,
+            Parameter::LoadPowerW { load } => {
+                let config = scenario.loads.iter_mut().find(|config| config.name == *load);
+                config.ok_or_else(|| ScenarioError::UnknownLoad(load.clone()))?.power_w = value;
+            }
+            Parameter::OrbitAltitudeKm | Parameter::BetaAngleDeg => {
+                let orbit = scenario.orbit.as_mut().ok_or(ScenarioError::MissingOrbit)?;
+                match self {
+                    Parameter::OrbitAltitudeKm => orbit.altitude_km = value,
+                    _ => orbit.beta_angle_deg = value,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Statistics {
+    /// Nearest-rank percentiles. Empty input gives NaN everywhere.
+    pub fn new(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |p: f64| match sorted.len() {
+            0 => f64::NAN,
+            n => sorted[((p * n as f64).ceil() as usize).clamp(1, n) - 1],
+        };
+        Statistics {
+            min: percentile(0.0),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            max: percentile(1.0),
+            p05: percentile(0.05),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+/// Runs every grid point `config.runs` times in parallel and summarises the results.
+pub fn run_batch(scenario: &Scenario, config: &BatchConfig) -> Result<BatchReport, ScenarioError> {
+    config.validate()?;
+    let step_s = config.step_s.unwrap_or(scenario.step_s);
+    let grid = sweep_grid(&config.parameters);
+    let runs = (0..grid.len() * config.runs)
+        .into_par_iter()
+        .map(|index| {
+            let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(index as u64));
+            let mut scenario = scenario.clone();
+            let mut parameters = Vec::with_capacity(config.parameters.len());
+            let mut swept = grid[index / config.runs].iter();
+            for spec in config.parameters.iter() {
+                let value = match &spec.distribution {
+                    Distribution::Values { .. } => *swept.next().unwrap_or(&f64::NAN), // One per swept parameter
+                    Distribution::Uniform { min, max } => Uniform::new_inclusive(*min, *max).sample(&mut rng),
+                    Distribution::Normal { mean, std_dev } => Normal::new(*mean, *std_dev)
+                        .map_err(|error| ScenarioError::Parse(error.to_string()))?
+                        .sample(&mut rng),
+                };
+                spec.parameter.apply(&mut scenario, value)?;
+                parameters.push((spec.parameter.label(), value));
+            }
+            summarise(&scenario, step_s, parameters)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(report(runs))
+}
+
+// Cartesian product of the swept values, in parameter order
+fn sweep_grid(parameters: &[ParameterSpec]) -> Vec<Vec<f64>> {
+    let mut grid = vec![Vec::new()];
+    for spec in parameters {
+        if let Distribution::Values { values } = &spec.distribution {
+            grid = grid
+                .iter()
+                .flat_map(|point| values.iter().map(move |value| [point.as_slice(), &[*value]].concat()))
+                .collect();
+        }
+    }
+    grid
+}
+
+fn summarise(scenario: &Scenario, step_s: f64, parameters: Vec<(String, f64)>) -> Result<RunSummary, ScenarioError> {
+    let mut summary = RunSummary {
+        parameters,
+        min_soc_percentage: f64::INFINITY,
+        final_soc_percentage: f64::NAN,
+        time_in_safe_mode_s: 0.0,
+        tier_sheds: Vec::new(),
+    };
+    simulate(scenario, step_s, |eps, _, record, _| {
+        if summary.tier_sheds.is_empty() {
+            let tiers = eps.get_shedding_policy().get_tiers();
+            summary.tier_sheds = tiers.iter().map(|tier| (tier.get_priority(), 0)).collect();
+        }
+        summary.min_soc_percentage = summary.min_soc_percentage.min(record.soc_percentage);
+        summary.final_soc_percentage = record.soc_percentage;
+        if record.mode == SatelliteOperationalMode::SafeMode {
+            summary.time_in_safe_mode_s += step_s;
+        }
+        for decision in record.shedding_decisions.iter().filter(|decision| decision.action == SheddingAction::Shed) {
+            if let Some((_, count)) = summary.tier_sheds.iter_mut().find(|(priority, _)| *priority == decision.priority) {
+                *count += 1;
+            }
+        }
+    })?;
+    Ok(summary)
+}
+
+fn report(runs: Vec<RunSummary>) -> BatchReport {
+    let column = |value: fn(&RunSummary) -> f64| runs.iter().map(value).collect::<Vec<_>>();
+    let run_count = runs.len().max(1) as f64;
+    let tiers = runs
+        .first()
+        .map(|run| run.tier_sheds.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(index, (priority, _))| {
+            let sheds: Vec<usize> = runs.iter().map(|run| run.tier_sheds[index].1).collect();
+            TierStatistics {
+                priority: *priority,
+                fired_fraction: sheds.iter().filter(|count| **count > 0).count() as f64 / run_count,
+                mean_sheds: sheds.iter().sum::<usize>() as f64 / run_count,
+            }
+        })
+        .collect();
+    BatchReport {
+        min_soc_percentage: Statistics::new(&column(|run| run.min_soc_percentage)),
+        time_in_safe_mode_s: Statistics::new(&column(|run| run.time_in_safe_mode_s)),
+        safe_mode_fraction: runs.iter().filter(|run| run.time_in_safe_mode_s > 0.0).count() as f64 / run_count,
+        tiers,
+        runs,
+    }
+}
+
+fn default_runs() -> usize {
+    1
+}
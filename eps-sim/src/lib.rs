@@ -0,0 +1,11 @@
+// Host-side EPS simulator: builds an `eps::EPS` from a scenario file, runs it and writes a time series.
+// Mission designers change the scenario, not the code.
+pub mod batch;
+pub mod output;
+pub mod scenario;
+pub mod simulation;
+
+pub use batch::{BatchConfig, BatchReport, Distribution, Parameter, RunSummary, run_batch};
+pub use output::OutputFormat;
+pub use scenario::{Scenario, ScenarioError};
+pub use simulation::{LoadSample, RailSample, Sample, run, simulate};
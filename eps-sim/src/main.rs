@@ -0,0 +1,96 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use eps_sim::{BatchConfig, OutputFormat, Scenario, output};
+
+const USAGE: &str = "Usage: eps-sim [batch] <scenario|batch file (.toml|.json)> [--step-s <seconds>] [--format csv|json] [--output <file>]";
+
+struct Options {
+    batch: bool,
+    scenario: String, // Batch file for batch runs
+    step_s: Option<f64>,
+    format: OutputFormat,
+    output: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let batch = args.first().is_some_and(|arg| arg == "batch");
+    let mut options = Options { batch, scenario: String::new(), step_s: None, format: OutputFormat::Csv, output: None };
+    let mut args = args[batch as usize..].iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--step-s" => {
+                let step_s = value()?.parse::<f64>().map_err(|error| format!("invalid step: {error}"))?;
+                if step_s <= 0.0 {
+                    return Err("step must be positive".into());
+                }
+                options.step_s = Some(step_s);
+            }
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown format `{other}`")),
+                }
+            }
+            "--output" => options.output = Some(value()?.clone()),
+            _ if options.scenario.is_empty() && !arg.starts_with("--") => options.scenario = arg.clone(),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    if options.scenario.is_empty() {
+        return Err("missing scenario file".into());
+    }
+    Ok(options)
+}
+
+fn open_output(options: &Options) -> io::Result<Box<dyn Write>> {
+    Ok(match &options.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+fn simulate(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = Scenario::from_file(Path::new(&options.scenario))?;
+    let samples = eps_sim::run(&scenario, options.step_s.unwrap_or(scenario.step_s))?;
+    let mut writer = open_output(options)?;
+    output::write(&samples, options.format, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn run_batch(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = BatchConfig::from_file(Path::new(&options.scenario))?;
+    let scenario = Scenario::from_file(config.scenario.as_deref().ok_or("batch file names no scenario")?)?;
+    config.step_s = options.step_s.or(config.step_s);
+    let report = eps_sim::run_batch(&scenario, &config)?;
+    output::write_batch_summary(&report, &mut io::stderr())?;
+    let mut writer = open_output(options)?;
+    output::write_batch(&report, options.format, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = if options.batch { run_batch(&options) } else { simulate(&options) };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
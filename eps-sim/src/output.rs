@@ -0,0 +1,123 @@
+// Time series writers. CSV has one column per scalar and per load flag; lists are joined with `;`.
+use std::io::{self, Write};
+
+use crate::batch::BatchReport;
+use crate::simulation::Sample;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+}
+
+pub fn write(samples: &[Sample], format: OutputFormat, writer: &mut dyn Write) -> io::Result<()> {
+    match format {
+        OutputFormat::Csv => write_csv(samples, writer),
+        OutputFormat::Json => write_json(samples, writer),
+    }
+}
+
+pub fn write_csv(samples: &[Sample], writer: &mut dyn Write) -> io::Result<()> {
+    let mut header = String::from(
+        "time_s,mode,sunlit,generated_power_w,demanded_power_w,peak_demand_w,conversion_loss_w,net_power_w,battery_energy_in_wh,battery_energy_out_wh,\
+         soc_percentage,battery_voltage_v,battery_current_a,battery_temperature_c,battery_state,active_faults,\
+         mode_transitions,shed_loads,tripped_loads,crossings,deployed_panels,burn_attempts,launch_phase,faults_injected",
+    );
+    // The load and rail sets are fixed for a run, so the first sample names their columns
+    for load in samples.first().map(|sample| sample.loads.as_slice()).unwrap_or_default() {
+        header.push_str(&format!(",{0}_on,{0}_powered,{0}_tripped", load.name));
+    }
+    for rail in samples.first().map(|sample| sample.rails.as_slice()).unwrap_or_default() {
+        header.push_str(&format!(",{0}_output_w,{0}_loss_w", rail.name));
+    }
+    writeln!(writer, "{header}")?;
+
+    for sample in samples {
+        write!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            sample.time_s,
+            sample.mode,
+            sample.sunlit,
+            sample.generated_power_w,
+            sample.demanded_power_w,
+            sample.peak_demand_w,
+            sample.conversion_loss_w,
+            sample.net_power_w,
+            sample.battery_energy_in_wh,
+            sample.battery_energy_out_wh,
+            sample.soc_percentage,
+            sample.battery_voltage_v,
+            sample.battery_current_a,
+            sample.battery_temperature_c,
+            sample.battery_state,
+            sample.active_faults.join(";"),
+            sample.mode_transitions,
+            sample.shed_loads.join(";"),
+            sample.tripped_loads.join(";"),
+            sample.crossings.join(";"),
+            sample.deployed_panels,
+            sample.burn_attempts,
+            sample.launch_phase,
+            sample.faults_injected.join(";"),
+        )?;
+        for load in sample.loads.iter() {
+            write!(writer, ",{},{},{}", load.is_on, load.is_powered, load.is_tripped)?;
+        }
+        for rail in sample.rails.iter() {
+            write!(writer, ",{},{}", rail.output_w, rail.loss_w)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+pub fn write_json(samples: &[Sample], writer: &mut dyn Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, samples)?;
+    writeln!(writer)
+}
+
+/// Batch results: one CSV row per run, or the whole report as JSON.
+pub fn write_batch(report: &BatchReport, format: OutputFormat, writer: &mut dyn Write) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        serde_json::to_writer_pretty(&mut *writer, report)?;
+        return writeln!(writer);
+    }
+    let Some(first) = report.runs.first() else {
+        return Ok(());
+    };
+    let mut header: Vec<String> = first.parameters.iter().map(|(label, _)| label.clone()).collect();
+    header.extend(["min_soc_percentage", "final_soc_percentage", "time_in_safe_mode_s"].map(String::from));
+    header.extend(first.tier_sheds.iter().map(|(priority, _)| format!("tier{priority}_sheds")));
+    writeln!(writer, "{}", header.join(","))?;
+    for run in report.runs.iter() {
+        let mut row: Vec<String> = run.parameters.iter().map(|(_, value)| value.to_string()).collect();
+        row.extend([run.min_soc_percentage, run.final_soc_percentage, run.time_in_safe_mode_s].map(|value| value.to_string()));
+        row.extend(run.tier_sheds.iter().map(|(_, count)| count.to_string()));
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Human-readable statistics of a batch.
+pub fn write_batch_summary(report: &BatchReport, writer: &mut dyn Write) -> io::Result<()> {
+    writeln!(writer, "runs: {}", report.runs.len())?;
+    for (name, statistics) in [("min SoC %", &report.min_soc_percentage), ("SafeMode s", &report.time_in_safe_mode_s)] {
+        writeln!(
+            writer,
+            "{name:<12} min {:.2}  p05 {:.2}  mean {:.2}  p95 {:.2}  max {:.2}",
+            statistics.min, statistics.p05, statistics.mean, statistics.p95, statistics.max
+        )?;
+    }
+    writeln!(writer, "runs entering SafeMode: {:.1} %", 100.0 * report.safe_mode_fraction)?;
+    for tier in report.tiers.iter() {
+        writeln!(
+            writer,
+            "shedding tier {}: fired in {:.1} % of runs, {:.2} times per run",
+            tier.priority,
+            100.0 * tier.fired_fraction,
+            tier.mean_sheds
+        )?;
+    }
+    Ok(())
+}
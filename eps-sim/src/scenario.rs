@@ -0,0 +1,649 @@
+// Scenario files: everything needed to build an EPS and drive it, in TOML or JSON.
+// Loads are named through the shared `LoadRegistry`, so timelines read like the ground's telecommands.
+use std::fmt;
+use std::path::Path;
+
+use eps::{
+    AdaptiveStepper, Attitude, AttitudeMode, Battery, BatteryFault, BatteryThermal, CellTechnology, DeploymentFailure,
+    DeploymentSequencer, EPS, FaultTrigger, InjectedFault, LaunchSequencer, Load, LoadId, LoadProfile, LoadRegistry, Orbit, PowerDistributionUnit, PowerRail, RadiationDegradation,
+    SatelliteOperationalMode, Sensor, SolarPanel, Vector3,
+};
+use eps::consts::{
+    ADAPTIVE_MAX_STEP_S, ADAPTIVE_RESOLUTION_S, BURN_RETRY_INTERVAL_S, BURN_WIRE_DURATION_S, DEFAULT_SEPARATION_SWITCHES,
+    DEPLOYMENT_HOLD_OFF_S, MAX_BURN_ATTEMPTS, RF_SILENCE_S,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default = "default_step_s")]
+    pub step_s: f64,
+    pub duration_s: f64,
+    #[serde(default = "default_mode", with = "ModeDef")]
+    pub initial_mode: SatelliteOperationalMode,
+    #[serde(default = "default_sun_vector")]
+    pub sun_vector: [f64; 3], // Body frame, used without an orbit or attitude
+    pub battery: BatteryConfig,
+    #[serde(default)]
+    pub panels: Vec<PanelConfig>,
+    #[serde(default)]
+    pub panel_degradation_per_year: f64, // Fraction of power lost each year
+    #[serde(default)]
+    pub mission_age_years: f64,          // Degradation already accumulated at the start of the run
+    pub radiation: Option<RadiationConfig>, // Cell radiation damage on every panel, on top of the yearly degradation
+    #[serde(default)]
+    pub loads: Vec<LoadConfig>,
+    pub orbit: Option<OrbitConfig>,
+    pub attitude: Option<AttitudeConfig>,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+    pub adaptive: Option<AdaptiveConfig>, // Sub-steps each output step at SoC, voltage and eclipse crossings
+    pub deployment: Option<DeploymentConfig>, // Releases the panels listed in it, stowed or not
+    pub launch: Option<LaunchConfig>, // Starts in the deployer with the EPS dark
+    #[serde(default)]
+    pub faults: Vec<FaultConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatteryConfig {
+    pub capacity_wh: f64,
+    pub initial_soc: f64, // Fraction of capacity
+    pub voltage_v: f64,
+    pub max_charge_w: f64,
+    pub max_discharge_w: f64,
+    pub temperature_c: Option<f64>, // Enables the thermal model
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PanelConfig {
+    pub id: String,
+    pub area_m2: f64,
+    pub efficiency: f64,
+    pub normal: [f64; 3],
+    #[serde(default = "default_true")]
+    pub deployed: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RadiationConfig {
+    #[serde(with = "TechnologyDef")]
+    pub technology: CellTechnology,
+    pub fluence_rate_e_cm2_year: Option<f64>, // From the orbit altitude when not given
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoadConfig {
+    pub name: String,
+    pub id: Option<u8>, // Required for loads outside the standard registry
+    pub power_w: f64,
+    #[serde(default)]
+    pub critical: bool,
+    #[serde(default)]
+    pub on: bool,
+    #[serde(default)]
+    pub shed_priority: u8,
+    #[serde(default, with = "RailDef")]
+    pub rail: PowerRail,
+    pub profile: Option<ProfileConfig>,
+    pub inrush: Option<InrushConfig>,
+    #[serde(default)]
+    pub schedule: Vec<[f64; 2]>, // On windows as [start_s, end_s], e.g. predicted ground passes
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ProfileConfig {
+    DutyCycle {
+        period_s: f64,
+        on_s: f64,
+        #[serde(default)]
+        offset_s: f64,
+        #[serde(default)]
+        standby_w: f64,
+    },
+    Pulsed {
+        period_s: f64,
+        pulse_s: f64,
+        #[serde(default)]
+        offset_s: f64,
+        pulse_w: f64,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InrushConfig {
+    pub peak_w: f64,
+    pub duration_s: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentConfig {
+    #[serde(default = "default_hold_off_s")]
+    pub hold_off_s: f64, // From the start of the run, taken as the separation
+    #[serde(default = "default_burn_s")]
+    pub burn_s: f64,
+    #[serde(default = "default_retry_interval_s")]
+    pub retry_interval_s: f64,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u8,
+    pub panels: Vec<DeployableConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployableConfig {
+    pub panel: String,
+    pub burn_wire: String, // Load drawing the heater power
+    pub failure: Option<DeploymentFailureConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum DeploymentFailureConfig {
+    Stuck,
+    ReleasesOnAttempt { attempt: u8 },
+    Partial { fraction: f64 },
+    SwitchStuckOpen,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LaunchConfig {
+    pub separation_s: Option<f64>, // Stays in the deployer when not given
+    #[serde(default = "default_separation_switches")]
+    pub separation_switches: usize,
+    #[serde(default)]
+    pub rbf_pin_inserted: bool,
+    #[serde(default = "default_rf_silence_s")]
+    pub rf_silence_s: f64,
+    pub stages: Vec<BootStageConfig>,
+    #[serde(default)]
+    pub transmitters: Vec<String>, // In addition to COM_TX and PayloadTx
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootStageConfig {
+    pub load: String,
+    #[serde(default)]
+    pub delay_s: f64, // After separation
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultConfig {
+    pub trigger: FaultTriggerConfig,
+    pub fault: InjectedFaultConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FaultTriggerConfig {
+    At { time_s: f64 },
+    SocBelow { soc_percentage: f64 },
+    InMode {
+        #[serde(with = "ModeDef")]
+        mode: SatelliteOperationalMode,
+    },
+}
+
+// Scenario batteries have no cell pack, so there is no cell short here
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum InjectedFaultConfig {
+    StringOpen { panel: String },
+    LoadStuckOn { load: String },
+    SensorFrozen {
+        #[serde(with = "SensorDef")]
+        sensor: Sensor,
+    },
+    SensorOffset {
+        #[serde(with = "SensorDef")]
+        sensor: Sensor,
+        offset: f64, // Percent for the SoC, volts for the bus
+    },
+    RegulatorFailure,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveConfig {
+    #[serde(default = "default_adaptive_max_step_s")]
+    pub max_step_s: f64,
+    #[serde(default = "default_adaptive_resolution_s")]
+    pub resolution_s: f64,
+    #[serde(default)]
+    pub soc_thresholds: Vec<f64>,     // Watched in addition to the ones the EPS acts on
+    #[serde(default)]
+    pub voltage_thresholds: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrbitConfig {
+    pub altitude_km: f64,
+    pub inclination_deg: f64,
+    #[serde(default)]
+    pub raan_deg: f64,
+    #[serde(default)]
+    pub beta_angle_deg: f64,
+    #[serde(default)]
+    pub epoch_s: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AttitudeConfig {
+    SunPointing { axis: [f64; 3] },
+    NadirPointing,
+    InertialHold,
+    Tumbling { body_rates_deg_s: [f64; 3] },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimelineEntry {
+    pub time_s: f64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    SetMode {
+        #[serde(with = "ModeDef")]
+        mode: SatelliteOperationalMode,
+    },
+    SwitchLoad { load: String, on: bool },
+    LatchUp { load: String, extra_current_a: f64 },
+    RaiseBatteryFault {
+        #[serde(with = "FaultDef")]
+        fault: BatteryFault,
+    },
+    ClearBatteryFault {
+        #[serde(with = "FaultDef")]
+        fault: BatteryFault,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(remote = "SatelliteOperationalMode")]
+enum ModeDef {
+    NominalSunlit,
+    NominalEclipse,
+    SafeMode,
+    PayloadOperation,
+}
+
+#[derive(Deserialize)]
+#[serde(remote = "PowerRail")]
+enum RailDef {
+    #[serde(rename = "VBAT")]
+    Raw,
+    #[serde(rename = "3V3")]
+    V3_3,
+    #[serde(rename = "5V")]
+    V5,
+    #[serde(rename = "12V")]
+    V12,
+}
+
+#[derive(Deserialize)]
+#[serde(remote = "CellTechnology")]
+enum TechnologyDef {
+    #[serde(rename = "triple_junction_gaas")]
+    TripleJunctionGaAs,
+    #[serde(rename = "silicon")]
+    Silicon,
+}
+
+#[derive(Deserialize)]
+#[serde(remote = "Sensor", rename_all = "snake_case")]
+enum SensorDef {
+    BatterySoc,
+    BusVoltage,
+}
+
+#[derive(Deserialize)]
+#[serde(remote = "BatteryFault")]
+enum FaultDef {
+    OverTemperature,
+    UnderTemperature,
+    OverVoltage,
+    UnderVoltage,
+    OverCurrent,
+    SevereDegradation,
+    CellShort,
+    OpenCircuit,
+    ChargeUnderTemperature,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Parse(String),
+    UnsupportedFormat,
+    UnknownLoad(String),
+    InvalidLoad(String), // Rejected by the registry or the PDU
+    TooManyPanels,
+    UnknownPanel(String),
+    InvalidDeployment(String), // Panel listed twice or too many deployables
+    InvalidLaunch(String),     // Too many boot stages or transmitters
+    TooManyFaults,
+    MissingOrbit, // A parameter needs the scenario to have an orbit
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(error) => write!(f, "cannot read scenario: {error}"),
+            ScenarioError::Parse(message) => write!(f, "invalid scenario: {message}"),
+            ScenarioError::UnsupportedFormat => write!(f, "scenario must be a .toml or .json file"),
+            ScenarioError::UnknownLoad(name) => write!(f, "unknown load `{name}`, give it an `id`"),
+            ScenarioError::InvalidLoad(name) => write!(f, "load `{name}` clashes with another load"),
+            ScenarioError::TooManyPanels => write!(f, "too many solar panels"),
+            ScenarioError::UnknownPanel(id) => write!(f, "unknown solar panel `{id}`"),
+            ScenarioError::InvalidDeployment(id) => write!(f, "cannot deploy panel `{id}`: listed twice or too many deployables"),
+            ScenarioError::InvalidLaunch(name) => write!(f, "cannot add `{name}` to the launch sequence: too many stages or transmitters"),
+            ScenarioError::TooManyFaults => write!(f, "too many scheduled faults"),
+            ScenarioError::MissingOrbit => write!(f, "scenario has no orbit"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Scenario {
+    /// Reads a scenario, picking the format from the file extension.
+    pub fn from_file(path: &Path) -> Result<Self, ScenarioError> {
+        let text = std::fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Scenario::from_toml(&text),
+            Some("json") => Scenario::from_json(&text),
+            _ => Err(ScenarioError::UnsupportedFormat),
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ScenarioError> {
+        toml::from_str::<Scenario>(text).map_err(|error| ScenarioError::Parse(error.to_string()))?.validate()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        serde_json::from_str::<Scenario>(text).map_err(|error| ScenarioError::Parse(error.to_string()))?.validate()
+    }
+
+    // What the format cannot express
+    fn validate(self) -> Result<Self, ScenarioError> {
+        check_positive("step_s", self.step_s)?;
+        check_positive("duration_s", self.duration_s)?;
+        if let Some(adaptive) = &self.adaptive {
+            check_positive("adaptive.max_step_s", adaptive.max_step_s)?;
+            check_positive("adaptive.resolution_s", adaptive.resolution_s)?;
+        }
+        Ok(self)
+    }
+
+    /// The standard registry plus the scenario's own loads.
+    pub fn load_registry(&self) -> Result<LoadRegistry, ScenarioError> {
+        let mut registry = LoadRegistry::standard();
+        for load in self.loads.iter() {
+            match (load.id, registry.get_id(&load.name)) {
+                (None, Some(_)) => {}
+                (Some(id), Some(registered)) if registered == LoadId(id) => {}
+                (Some(id), _) => {
+                    registry.register(LoadId(id), &load.name).map_err(|_| ScenarioError::InvalidLoad(load.name.clone()))?
+                }
+                (None, None) => return Err(ScenarioError::UnknownLoad(load.name.clone())),
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn build_eps(&self, registry: &LoadRegistry) -> Result<EPS, ScenarioError> {
+        let radiation = match &self.radiation {
+            Some(config) => {
+                let fluence_rate_e_cm2_year = match (config.fluence_rate_e_cm2_year, &self.orbit) {
+                    (Some(rate), _) => rate,
+                    (None, Some(orbit)) => RadiationDegradation::get_fluence_rate_for_altitude(orbit.altitude_km),
+                    (None, None) => return Err(ScenarioError::MissingOrbit),
+                };
+                Some(RadiationDegradation::new(config.technology, fluence_rate_e_cm2_year).with_mission_age_years(self.mission_age_years))
+            }
+            None => None,
+        };
+        let mut panels = heapless::Vec::new();
+        for config in self.panels.iter() {
+            let mut panel = SolarPanel::new(&config.id, config.area_m2, config.efficiency, vector(config.normal));
+            if let Some(radiation) = radiation {
+                panel = panel.with_radiation(radiation);
+            }
+            if config.deployed {
+                panel.deploy();
+            }
+            panel.apply_degradation(1.0 - (1.0 - self.panel_degradation_per_year).powf(self.mission_age_years));
+            panels.push(panel).map_err(|_| ScenarioError::TooManyPanels)?;
+        }
+
+        let mut pdu = PowerDistributionUnit::new();
+        for config in self.loads.iter() {
+            let id = resolve_load(registry, &config.name)?;
+            let mut load = Load::new(id, config.power_w, config.critical).with_shed_priority(config.shed_priority).with_rail(config.rail);
+            load = match config.profile {
+                Some(ProfileConfig::DutyCycle { period_s, on_s, offset_s, standby_w }) => {
+                    load.with_profile(LoadProfile::DutyCycle { period_s, on_s, offset_s, standby_w })
+                }
+                Some(ProfileConfig::Pulsed { period_s, pulse_s, offset_s, pulse_w }) => {
+                    load.with_profile(LoadProfile::Pulsed { period_s, pulse_s, offset_s, pulse_w })
+                }
+                None => load,
+            };
+            if let Some(inrush) = &config.inrush {
+                load = load.with_inrush(inrush.peak_w, inrush.duration_s);
+            }
+            pdu.add_load(load).map_err(|_| ScenarioError::InvalidLoad(config.name.clone()))?;
+            if config.on {
+                let _ = pdu.switch_load(id, true); // Just added
+            }
+            for [start_s, end_s] in config.schedule.iter().copied() {
+                pdu.schedule_window(id, start_s, end_s).map_err(|_| ScenarioError::InvalidLoad(config.name.clone()))?;
+            }
+        }
+
+        let config = &self.battery;
+        let mut battery = Battery::new(
+            "BAT0",
+            config.capacity_wh,
+            config.capacity_wh * config.initial_soc,
+            config.voltage_v,
+            config.max_charge_w,
+            config.max_discharge_w,
+        );
+        if let Some(temperature_c) = config.temperature_c {
+            battery = battery.with_thermal(BatteryThermal::new(temperature_c));
+        }
+
+        let mut eps = EPS::new(panels, battery, pdu);
+        eps.set_sun_vector_body(vector(self.sun_vector));
+        if let Some(orbit) = &self.orbit {
+            eps = eps.with_orbit(Orbit::new(
+                orbit.altitude_km,
+                orbit.inclination_deg,
+                orbit.raan_deg,
+                orbit.beta_angle_deg,
+                orbit.epoch_s,
+            ));
+        }
+        if let Some(attitude) = &self.attitude {
+            let mode = match attitude {
+                AttitudeConfig::SunPointing { axis } => AttitudeMode::SunPointing { axis_body: vector(*axis) },
+                AttitudeConfig::NadirPointing => AttitudeMode::NadirPointing,
+                AttitudeConfig::InertialHold => AttitudeMode::InertialHold,
+                AttitudeConfig::Tumbling { body_rates_deg_s } => {
+                    AttitudeMode::Tumbling { body_rates_deg_s: vector(*body_rates_deg_s) }
+                }
+            };
+            eps = eps.with_attitude(Attitude::new(mode, vector(self.sun_vector)));
+        }
+        if let Some(config) = &self.deployment {
+            let mut deployment =
+                DeploymentSequencer::new(config.hold_off_s, config.burn_s, config.retry_interval_s, config.max_attempts);
+            for deployable in config.panels.iter() {
+                let panel_index = self
+                    .panels
+                    .iter()
+                    .position(|panel| panel.id == deployable.panel)
+                    .ok_or_else(|| ScenarioError::UnknownPanel(deployable.panel.clone()))?;
+                if !self.loads.iter().any(|load| load.name == deployable.burn_wire) {
+                    return Err(ScenarioError::UnknownLoad(deployable.burn_wire.clone()));
+                }
+                let invalid = |_| ScenarioError::InvalidDeployment(deployable.panel.clone());
+                deployment.add_panel(panel_index, resolve_load(registry, &deployable.burn_wire)?).map_err(invalid)?;
+                let failure = match deployable.failure {
+                    Some(DeploymentFailureConfig::Stuck) => Some(DeploymentFailure::Stuck),
+                    Some(DeploymentFailureConfig::ReleasesOnAttempt { attempt }) => Some(DeploymentFailure::ReleasesOnAttempt(attempt)),
+                    Some(DeploymentFailureConfig::Partial { fraction }) => Some(DeploymentFailure::Partial(fraction)),
+                    Some(DeploymentFailureConfig::SwitchStuckOpen) => Some(DeploymentFailure::SwitchStuckOpen),
+                    None => None,
+                };
+                if let Some(failure) = failure {
+                    deployment.inject_failure(panel_index, failure).map_err(invalid)?;
+                }
+            }
+            eps = eps.with_deployment(deployment).expect("panels and burn wires checked above");
+        }
+        if let Some(config) = &self.launch {
+            let mut launch = LaunchSequencer::new(config.separation_switches, config.rf_silence_s);
+            if let Some(separation_s) = config.separation_s {
+                launch = launch.with_separation_at(separation_s);
+            }
+            if config.rbf_pin_inserted {
+                launch.insert_rbf_pin();
+            }
+            for stage in config.stages.iter() {
+                if !self.loads.iter().any(|load| load.name == stage.load) {
+                    return Err(ScenarioError::UnknownLoad(stage.load.clone()));
+                }
+                let invalid = |_| ScenarioError::InvalidLaunch(stage.load.clone());
+                launch.add_stage(resolve_load(registry, &stage.load)?, stage.delay_s).map_err(invalid)?;
+            }
+            for name in config.transmitters.iter() {
+                let invalid = |_| ScenarioError::InvalidLaunch(name.clone());
+                launch.add_transmitter(resolve_load(registry, name)?).map_err(invalid)?;
+            }
+            eps = eps.with_launch(launch).expect("boot stage loads checked above");
+        }
+        for config in self.faults.iter() {
+            let trigger = match config.trigger {
+                FaultTriggerConfig::At { time_s } => FaultTrigger::At(time_s),
+                FaultTriggerConfig::SocBelow { soc_percentage } => FaultTrigger::SocBelow(soc_percentage),
+                FaultTriggerConfig::InMode { mode } => FaultTrigger::InMode(mode),
+            };
+            let fault = match &config.fault {
+                InjectedFaultConfig::StringOpen { panel } => InjectedFault::StringOpen {
+                    panel_index: self
+                        .panels
+                        .iter()
+                        .position(|config| config.id == *panel)
+                        .ok_or_else(|| ScenarioError::UnknownPanel(panel.clone()))?,
+                },
+                InjectedFaultConfig::LoadStuckOn { load } => {
+                    if !self.loads.iter().any(|config| config.name == *load) {
+                        return Err(ScenarioError::UnknownLoad(load.clone()));
+                    }
+                    InjectedFault::LoadStuckOn(resolve_load(registry, load)?)
+                }
+                InjectedFaultConfig::SensorFrozen { sensor } => InjectedFault::SensorFrozen(*sensor),
+                InjectedFaultConfig::SensorOffset { sensor, offset } => InjectedFault::SensorOffset(*sensor, *offset),
+                InjectedFaultConfig::RegulatorFailure => InjectedFault::RegulatorFailure,
+            };
+            eps.schedule_fault(trigger, fault).map_err(|_| ScenarioError::TooManyFaults)?; // Targets checked above
+        }
+        if self.initial_mode != SatelliteOperationalMode::NominalSunlit {
+            eps.set_satellite_mode(self.initial_mode);
+        }
+        Ok(eps)
+    }
+
+    /// `None` unless the scenario asks for adaptive stepping.
+    pub fn build_stepper(&self, eps: &EPS) -> Option<AdaptiveStepper> {
+        let config = self.adaptive.as_ref()?;
+        let mut stepper = AdaptiveStepper::for_eps(eps).with_step_limits(config.max_step_s, config.resolution_s);
+        for soc_percentage in config.soc_thresholds.iter().copied() {
+            stepper = stepper.with_soc_threshold(soc_percentage);
+        }
+        for voltage_v in config.voltage_thresholds.iter().copied() {
+            stepper = stepper.with_voltage_threshold(voltage_v);
+        }
+        Some(stepper)
+    }
+}
+
+pub(crate) fn resolve_load(registry: &LoadRegistry, name: &str) -> Result<LoadId, ScenarioError> {
+    registry.get_id(name).ok_or_else(|| ScenarioError::UnknownLoad(name.to_string()))
+}
+
+pub(crate) fn check_positive(name: &str, value: f64) -> Result<(), ScenarioError> {
+    if value > 0.0 && value.is_finite() {
+        return Ok(());
+    }
+    Err(ScenarioError::Parse(format!("`{name}` must be positive and finite, got {value}")))
+}
+
+fn vector([x, y, z]: [f64; 3]) -> Vector3 {
+    Vector3::new(x, y, z)
+}
+
+fn default_step_s() -> f64 {
+    60.0
+}
+
+fn default_hold_off_s() -> f64 {
+    DEPLOYMENT_HOLD_OFF_S
+}
+
+fn default_burn_s() -> f64 {
+    BURN_WIRE_DURATION_S
+}
+
+fn default_retry_interval_s() -> f64 {
+    BURN_RETRY_INTERVAL_S
+}
+
+fn default_max_attempts() -> u8 {
+    MAX_BURN_ATTEMPTS
+}
+
+fn default_separation_switches() -> usize {
+    DEFAULT_SEPARATION_SWITCHES
+}
+
+fn default_rf_silence_s() -> f64 {
+    RF_SILENCE_S
+}
+
+fn default_adaptive_max_step_s() -> f64 {
+    ADAPTIVE_MAX_STEP_S
+}
+
+fn default_adaptive_resolution_s() -> f64 {
+    ADAPTIVE_RESOLUTION_S
+}
+
+fn default_mode() -> SatelliteOperationalMode {
+    SatelliteOperationalMode::NominalSunlit
+}
+
+fn default_sun_vector() -> [f64; 3] {
+    [0.0, 0.0, 1.0]
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,173 @@
+// Runs a scenario step by step, applying its timeline and sampling the EPS state after every step.
+use eps::{Crossing, EPS, InjectedFault, LoadId, LoadRegistry, StepRecord};
+use serde::Serialize;
+
+use crate::scenario::{Action, Scenario, ScenarioError, check_positive, resolve_load};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sample {
+    pub time_s: f64, // End of the step
+    pub mode: String,
+    pub sunlit: bool,
+    pub generated_power_w: f64,
+    pub demanded_power_w: f64, // Average over the step
+    pub peak_demand_w: f64,
+    pub conversion_loss_w: f64, // Included in the demand
+    pub net_power_w: f64,
+    pub battery_energy_in_wh: f64,
+    pub battery_energy_out_wh: f64,
+    pub soc_percentage: f64,
+    pub battery_voltage_v: f64,
+    pub battery_current_a: f64,
+    pub battery_temperature_c: f64,
+    pub battery_state: String,
+    pub active_faults: Vec<String>,
+    pub mode_transitions: usize, // During the step, ground commands included
+    pub shed_loads: Vec<String>,
+    pub tripped_loads: Vec<String>,
+    pub crossings: Vec<String>, // Located by adaptive stepping, as `kind@time_s`
+    pub deployed_panels: usize, // Partly deployed ones included
+    pub burn_attempts: usize,   // Deployment burns whose switch was read during the step
+    pub launch_phase: String,   // Empty without a launch sequence
+    pub faults_injected: Vec<String>, // At the start of the step
+    pub loads: Vec<LoadSample>,
+    pub rails: Vec<RailSample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadSample {
+    pub name: String,
+    pub is_on: bool,
+    pub is_powered: bool,
+    pub is_tripped: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RailSample {
+    pub name: String,
+    pub output_w: f64, // Average over the step
+    pub loss_w: f64,
+}
+
+/// Runs the scenario at `step_s`, returning one sample per step.
+/// Timeline actions are applied before the first step starting at or after their time.
+pub fn run(scenario: &Scenario, step_s: f64) -> Result<Vec<Sample>, ScenarioError> {
+    check_positive("step_s", step_s)?;
+    let mut samples = Vec::with_capacity((scenario.duration_s / step_s).ceil() as usize);
+    simulate(scenario, step_s, |eps, registry, record, crossings| samples.push(sample(eps, registry, record, crossings)))?;
+    Ok(samples)
+}
+
+/// Runs the scenario at `step_s`, handing every step record to `observer` together with the EPS after the step
+/// and the crossings located within it (none without adaptive stepping).
+pub fn simulate(
+    scenario: &Scenario,
+    step_s: f64,
+    mut observer: impl FnMut(&EPS, &LoadRegistry, &StepRecord, &[Crossing]),
+) -> Result<(), ScenarioError> {
+    check_positive("step_s", step_s)?;
+    check_positive("duration_s", scenario.duration_s)?;
+    let registry = scenario.load_registry()?;
+    let mut eps = scenario.build_eps(&registry)?;
+    let mut timeline: Vec<_> = scenario.timeline.iter().collect();
+    timeline.sort_by(|a, b| a.time_s.total_cmp(&b.time_s));
+    let mut timeline = timeline.into_iter().peekable();
+    let stepper = scenario.build_stepper(&eps);
+    let mut crossings = Vec::new();
+
+    for _ in 0..(scenario.duration_s / step_s).ceil() as usize {
+        while let Some(entry) = timeline.next_if(|entry| entry.time_s <= eps.get_elapsed_time_s()) {
+            apply(&mut eps, &registry, &entry.action)?;
+        }
+        let record = match &stepper {
+            Some(stepper) => {
+                let step = stepper.advance(&mut eps, step_s);
+                crossings.extend(step.crossings);
+                step.record
+            }
+            None => eps.manage_power(step_s / 3600.0),
+        };
+        observer(&eps, &registry, &record, &crossings);
+        crossings.clear();
+    }
+    Ok(())
+}
+
+fn apply(eps: &mut EPS, registry: &LoadRegistry, action: &Action) -> Result<(), ScenarioError> {
+    match action {
+        Action::SetMode { mode } => eps.set_satellite_mode(*mode),
+        Action::SwitchLoad { load, on } => {
+            let id = resolve_load(registry, load)?;
+            eps.get_pdu_mut().switch_load(id, *on).map_err(|_| ScenarioError::UnknownLoad(load.clone()))?;
+        }
+        Action::LatchUp { load, extra_current_a } => {
+            let id = resolve_load(registry, load)?;
+            eps.get_pdu_mut().inject_latch_up(id, *extra_current_a).map_err(|_| ScenarioError::UnknownLoad(load.clone()))?;
+        }
+        Action::RaiseBatteryFault { fault } => {
+            eps.raise_battery_fault(*fault);
+        }
+        Action::ClearBatteryFault { fault } => {
+            eps.clear_battery_fault(*fault);
+        }
+    }
+    Ok(())
+}
+
+fn sample(eps: &EPS, registry: &LoadRegistry, record: &StepRecord, crossings: &[Crossing]) -> Sample {
+    let battery = eps.get_battery();
+    let name = |id: LoadId| registry.get_name(id).map_or_else(|| format!("#{}", id.0), str::to_string);
+    Sample {
+        time_s: eps.get_elapsed_time_s(),
+        mode: format!("{:?}", record.mode),
+        sunlit: eps.is_sunlit(),
+        generated_power_w: record.generated_power_w,
+        demanded_power_w: record.demanded_power_w,
+        peak_demand_w: record.peak_demand_w,
+        conversion_loss_w: record.conversion_loss_w(),
+        net_power_w: record.net_power_w,
+        battery_energy_in_wh: record.battery_energy_in_wh,
+        battery_energy_out_wh: record.battery_energy_out_wh,
+        soc_percentage: record.soc_percentage,
+        battery_voltage_v: battery.get_terminal_voltage_v(),
+        battery_current_a: battery.get_current_a(),
+        battery_temperature_c: battery.get_temperature_c(),
+        battery_state: format!("{:?}", battery.get_status()),
+        active_faults: battery.get_faults().get_active().iter().map(|latched| format!("{:?}", latched.fault)).collect(),
+        mode_transitions: record.mode_transitions.len(),
+        shed_loads: record.shed_loads().map(name).collect(),
+        tripped_loads: record.tripped_loads.iter().copied().map(name).collect(),
+        crossings: crossings.iter().map(|crossing| format!("{:?}@{}", crossing.kind, crossing.time_s)).collect(),
+        deployed_panels: eps.get_solar_panels().iter().filter(|panel| panel.is_deployed()).count(),
+        burn_attempts: record.burn_attempts.len(),
+        launch_phase: eps.get_launch().map_or_else(String::new, |launch| format!("{:?}", launch.get_phase())),
+        faults_injected: record
+            .faults_injected
+            .iter()
+            .map(|fault| match *fault {
+                InjectedFault::StringOpen { panel_index } => format!("StringOpen({})", eps.get_solar_panels()[panel_index].get_id()),
+                InjectedFault::CellShort { group, position } => format!("CellShort({group}/{position})"),
+                InjectedFault::LoadStuckOn(load_id) => format!("LoadStuckOn({})", name(load_id)),
+                InjectedFault::SensorFrozen(sensor) => format!("SensorFrozen({sensor:?})"),
+                InjectedFault::SensorOffset(sensor, offset) => format!("SensorOffset({sensor:?}{offset:+})"),
+                InjectedFault::RegulatorFailure => "RegulatorFailure".to_string(),
+            })
+            .collect(),
+        loads: eps
+            .get_pdu()
+            .get_loads()
+            .iter()
+            .map(|load| LoadSample {
+                name: name(load.get_id()),
+                is_on: load.is_on(),
+                is_powered: load.is_powered(),
+                is_tripped: load.get_lcl().is_tripped(),
+            })
+            .collect(),
+        rails: record
+            .rail_demand
+            .iter()
+            .map(|rail| RailSample { name: rail.rail.get_name().to_string(), output_w: rail.output.average_w, loss_w: rail.loss_w })
+            .collect(),
+    }
+}
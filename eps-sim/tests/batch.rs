@@ -0,0 +1,135 @@
+use eps_sim::batch::Statistics;
+use eps_sim::{BatchConfig, Distribution, Scenario, ScenarioError, run_batch};
+
+// Eclipse only, so SoC falls steadily from the initial value
+const SCENARIO: &str = r#"
+duration_s = 7200.0
+initial_mode = "NominalEclipse"
+
+[battery]
+capacity_wh = 20.0
+initial_soc = 0.35
+voltage_v = 7.4
+max_charge_w = 20.0
+max_discharge_w = 20.0
+
+[[loads]]
+name = "OBC"
+power_w = 0.5
+critical = true
+on = true
+
+[[loads]]
+name = "PayloadCam"
+power_w = 1.5
+on = true
+"#;
+
+const BATCH: &str = r#"
+runs = 4
+seed = 7
+step_s = 60.0
+
+[[parameters]]
+parameter = "battery_capacity_wh"
+distribution = "values"
+values = [10.0, 40.0]
+
+[[parameters]]
+parameter = "load_power_w"
+load = "PayloadCam"
+distribution = "uniform"
+min = 1.0
+max = 2.0
+
+[[parameters]]
+parameter = "initial_soc"
+distribution = "values"
+values = [0.2, 0.35, 0.6]
+"#;
+
+#[test]
+fn sweeps_form_a_grid_and_random_draws_are_reproducible() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let config = BatchConfig::from_toml(BATCH).unwrap();
+    let report = run_batch(&scenario, &config).unwrap();
+    assert_eq!(report.runs.len(), 2 * 3 * 4);
+    let labels: Vec<&str> = report.runs[0].parameters.iter().map(|(label, _)| label.as_str()).collect();
+    assert_eq!(labels, ["battery_capacity_wh", "load_power_w[PayloadCam]", "initial_soc"]);
+    // Grid points in parameter order, each repeated `runs` times
+    let point = |index: usize| (report.runs[index].parameters[0].1, report.runs[index].parameters[2].1);
+    assert_eq!([point(0), point(3), point(4), point(23)], [(10.0, 0.2), (10.0, 0.2), (10.0, 0.35), (40.0, 0.6)]);
+    assert!(report.runs.iter().all(|run| (1.0..=2.0).contains(&run.parameters[1].1)));
+    assert_ne!(report.runs[0].parameters[1].1, report.runs[1].parameters[1].1);
+
+    assert_eq!(run_batch(&scenario, &config).unwrap(), report);
+}
+
+#[test]
+fn reports_min_soc_safe_mode_time_and_tier_firing() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let report = run_batch(&scenario, &BatchConfig::from_toml(BATCH).unwrap()).unwrap();
+    for run in report.runs.iter() {
+        assert!(run.min_soc_percentage <= run.final_soc_percentage + 1e-9);
+        assert!(run.min_soc_percentage < 100.0 * run.parameters[2].1);
+    }
+    // Small batteries starting at 20 % go straight to SafeMode, large ones at 60 % never shed
+    let small_low = &report.runs[0];
+    assert!(small_low.time_in_safe_mode_s > 0.0);
+    assert_eq!(small_low.tier_sheds.iter().map(|(priority, _)| *priority).collect::<Vec<_>>(), [0, 1, 2]);
+    assert!(small_low.tier_sheds[0].1 > 0);
+    let large_high = report.runs.last().unwrap();
+    assert_eq!(large_high.time_in_safe_mode_s, 0.0);
+    assert!(large_high.tier_sheds.iter().all(|(_, count)| *count == 0));
+
+    assert!(report.safe_mode_fraction > 0.0 && report.safe_mode_fraction < 1.0);
+    assert!(report.tiers[0].fired_fraction > 0.0 && report.tiers[0].fired_fraction < 1.0);
+    assert_eq!(report.min_soc_percentage.min, report.runs.iter().map(|run| run.min_soc_percentage).fold(f64::INFINITY, f64::min));
+}
+
+#[test]
+fn statistics_use_nearest_rank_percentiles() {
+    let values: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+    let statistics = Statistics::new(&values);
+    assert_eq!((statistics.min, statistics.p05, statistics.p95, statistics.max), (1.0, 5.0, 95.0, 100.0));
+    assert_eq!(statistics.mean, 50.5);
+}
+
+#[test]
+fn orbit_parameters_need_an_orbit() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let config = BatchConfig::from_toml("[[parameters]]\nparameter = \"orbit_altitude_km\"\ndistribution = \"values\"\nvalues = [400.0]\n").unwrap();
+    assert!(matches!(run_batch(&scenario, &config), Err(ScenarioError::MissingOrbit)));
+}
+
+#[test]
+fn batch_steps_must_be_positive() {
+    assert!(matches!(BatchConfig::from_toml("step_s = 0.0\n"), Err(ScenarioError::Parse(_))));
+    assert!(matches!(BatchConfig::from_json(r#"{ "step_s": -1.0, "parameters": [] }"#), Err(ScenarioError::Parse(_))));
+}
+
+#[test]
+fn bad_distributions_fail_the_batch_before_it_runs() {
+    let spec = |distribution: &str| format!("[[parameters]]\nparameter = \"initial_soc\"\n{distribution}\n");
+    for distribution in [
+        "distribution = \"uniform\"\nmin = 0.6\nmax = 0.2",
+        "distribution = \"uniform\"\nmin = 0.2\nmax = inf",
+        "distribution = \"values\"\nvalues = []",
+        "distribution = \"normal\"\nmean = 0.5\nstd_dev = -0.1",
+    ] {
+        assert!(matches!(BatchConfig::from_toml(&spec(distribution)), Err(ScenarioError::Parse(_))));
+    }
+    assert!(matches!(BatchConfig::from_toml("runs = 0\n"), Err(ScenarioError::Parse(_))));
+
+    // Configs built in code are checked too
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let mut config = BatchConfig::from_toml(&spec("distribution = \"uniform\"\nmin = 0.2\nmax = 0.6")).unwrap();
+    config.parameters[0].distribution = Distribution::Uniform { min: 0.6, max: 0.2 };
+    assert!(matches!(run_batch(&scenario, &config), Err(ScenarioError::Parse(_))));
+}
+
+#[test]
+fn bundled_batch_parses() {
+    let config = BatchConfig::from_toml(include_str!("../scenarios/leo_3u_sizing.toml")).unwrap();
+    assert_eq!(config.parameters.len(), 5);
+}
@@ -0,0 +1,268 @@
+use eps_sim::{OutputFormat, Scenario, ScenarioError, output, run};
+
+const SCENARIO: &str = r#"
+duration_s = 600.0
+
+[battery]
+capacity_wh = 40.0
+initial_soc = 0.5
+voltage_v = 7.4
+max_charge_w = 20.0
+max_discharge_w = 20.0
+
+[[panels]]
+id = "+Z"
+area_m2 = 0.06
+efficiency = 0.3
+normal = [0.0, 0.0, 1.0]
+
+[[loads]]
+name = "OBC"
+power_w = 0.5
+critical = true
+on = true
+
+[[loads]]
+name = "Imager"
+id = 16
+power_w = 2.0
+
+[[timeline]]
+time_s = 120.0
+action = "switch_load"
+load = "Imager"
+on = true
+
+[[timeline]]
+time_s = 300.0
+action = "set_mode"
+mode = "NominalEclipse"
+"#;
+
+#[test]
+fn timeline_drives_the_run() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let samples = run(&scenario, scenario.step_s).unwrap();
+    assert_eq!(samples.len(), 10);
+    assert_eq!(samples[0].time_s, 60.0);
+    assert!((samples[0].demanded_power_w - 0.5).abs() < 1e-9);
+    // Applied before the step starting at 120 s, reported at its end
+    assert!(!samples[1].loads[1].is_on && samples[2].loads[1].is_on);
+    assert!((samples[2].demanded_power_w - 2.5).abs() < 1e-9);
+    assert_eq!(samples[4].mode, "NominalSunlit");
+    assert_eq!((samples[5].mode.as_str(), samples[5].mode_transitions), ("NominalEclipse", 1));
+    assert!(samples[5].net_power_w < 0.0 && samples[9].soc_percentage < samples[4].soc_percentage);
+}
+
+#[test]
+fn step_size_comes_from_the_caller() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let samples = run(&scenario, 10.0).unwrap();
+    assert_eq!(samples.len(), 60);
+    assert_eq!(samples.last().unwrap().time_s, 600.0);
+}
+
+#[test]
+fn steps_and_durations_must_be_positive() {
+    for text in [
+        format!("step_s = 0.0\n{SCENARIO}"),
+        format!("step_s = -60.0\n{SCENARIO}"),
+        SCENARIO.replace("duration_s = 600.0", "duration_s = 0.0"),
+        format!("{SCENARIO}\n[adaptive]\nmax_step_s = -1.0\n"),
+        format!("{SCENARIO}\n[adaptive]\nresolution_s = 0.0\n"),
+    ] {
+        assert!(matches!(Scenario::from_toml(&text), Err(ScenarioError::Parse(_))));
+    }
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    assert!(matches!(run(&scenario, 0.0), Err(ScenarioError::Parse(_))));
+    assert!(matches!(run(&scenario, f64::NAN), Err(ScenarioError::Parse(_))));
+}
+
+#[test]
+fn json_scenarios_match_toml() {
+    let json = r#"{
+        "duration_s": 600.0,
+        "battery": { "capacity_wh": 40.0, "initial_soc": 0.5, "voltage_v": 7.4, "max_charge_w": 20.0, "max_discharge_w": 20.0 },
+        "panels": [{ "id": "+Z", "area_m2": 0.06, "efficiency": 0.3, "normal": [0.0, 0.0, 1.0] }],
+        "loads": [
+            { "name": "OBC", "power_w": 0.5, "critical": true, "on": true },
+            { "name": "Imager", "id": 16, "power_w": 2.0 }
+        ],
+        "timeline": [
+            { "time_s": 120.0, "action": "switch_load", "load": "Imager", "on": true },
+            { "time_s": 300.0, "action": "set_mode", "mode": "NominalEclipse" }
+        ]
+    }"#;
+    let from_json = Scenario::from_json(json).unwrap();
+    let from_toml = Scenario::from_toml(SCENARIO).unwrap();
+    assert_eq!(run(&from_json, 60.0).unwrap(), run(&from_toml, 60.0).unwrap());
+}
+
+#[test]
+fn unknown_loads_are_rejected() {
+    let scenario = Scenario::from_toml(&SCENARIO.replace("id = 16\n", "")).unwrap();
+    assert!(matches!(run(&scenario, 60.0), Err(ScenarioError::UnknownLoad(name)) if name == "Imager"));
+    let scenario = Scenario::from_toml(&SCENARIO.replace("load = \"Imager\"", "load = \"Downlink\"")).unwrap();
+    assert!(matches!(run(&scenario, 60.0), Err(ScenarioError::UnknownLoad(name)) if name == "Downlink"));
+}
+
+#[test]
+fn csv_has_a_column_per_load_flag() {
+    let scenario = Scenario::from_toml(SCENARIO).unwrap();
+    let samples = run(&scenario, 60.0).unwrap();
+    let mut csv = Vec::new();
+    output::write(&samples, OutputFormat::Csv, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let loads = ["OBC_on", "OBC_powered", "OBC_tripped", "Imager_on", "Imager_powered", "Imager_tripped"];
+    assert!(header.windows(loads.len()).any(|columns| columns == loads));
+    assert!(header.ends_with(&["5V_output_w", "5V_loss_w", "12V_output_w", "12V_loss_w"]));
+    assert_eq!(lines.clone().count(), samples.len());
+    assert!(lines.all(|line| line.split(',').count() == header.len()));
+}
+
+#[test]
+fn load_profiles_and_schedules_shape_the_demand() {
+    let toml = SCENARIO.replace(
+        "id = 16\npower_w = 2.0\n",
+        "id = 16\npower_w = 2.0\nprofile = { type = \"duty_cycle\", period_s = 60.0, on_s = 15.0 }\n\
+         inrush = { peak_w = 9.0, duration_s = 1.0 }\nschedule = [[330.0, 420.0]]\n",
+    );
+    let samples = run(&Scenario::from_toml(&toml).unwrap(), 60.0).unwrap();
+    // Duty-cycled from the switch at 120 s: 15 s of 2 W in every minute, inrush first
+    assert!((samples[2].demanded_power_w - 0.5 - (9.0 + 2.0 * 14.0) / 60.0).abs() < 1e-9);
+    assert_eq!(samples[2].peak_demand_w, 9.5);
+    assert!((samples[3].demanded_power_w - 1.0).abs() < 1e-9);
+    // The window's end at 420 s switches the imager off again
+    assert!(samples[6].loads[1].is_on && !samples[7].loads[1].is_on);
+}
+
+#[test]
+fn rails_add_converter_losses() {
+    let toml = SCENARIO.replace("id = 16\npower_w = 2.0\n", "id = 16\npower_w = 2.0\nrail = \"5V\"\n");
+    let samples = run(&Scenario::from_toml(&toml).unwrap(), 60.0).unwrap();
+    assert_eq!(samples[0].conversion_loss_w, 0.0);
+    let rail = samples[2].rails.iter().find(|rail| rail.name == "5V").unwrap();
+    assert!((rail.output_w - 2.0).abs() < 1e-9 && rail.loss_w > 0.0);
+    assert!((samples[2].demanded_power_w - 2.5 - rail.loss_w).abs() < 1e-9);
+    assert_eq!(samples[2].conversion_loss_w, rail.loss_w);
+    assert!(Scenario::from_toml(&toml.replace("\"5V\"", "\"7V\"")).is_err());
+}
+
+#[test]
+fn adaptive_stepping_reports_crossings_independent_of_the_step() {
+    let toml = format!("{SCENARIO}\n[adaptive]\nmax_step_s = 45.0\nsoc_thresholds = [50.5]\n");
+    let scenario = Scenario::from_toml(&toml).unwrap();
+    // Before the first timeline entry, so both runs see the same spacecraft
+    let crossing_s = |step_s: f64| -> f64 {
+        let crossings: Vec<String> = run(&scenario, step_s).unwrap().into_iter().flat_map(|sample| sample.crossings).collect();
+        assert_eq!(crossings.len(), 1, "{crossings:?}");
+        crossings[0].strip_prefix("SocAbove(50.5)@").unwrap().parse().unwrap()
+    };
+    let fine_s = crossing_s(60.0);
+    assert!(fine_s > 0.0 && fine_s < 120.0);
+    assert!((crossing_s(600.0) - fine_s).abs() <= 1.0);
+    assert!(run(&Scenario::from_toml(SCENARIO).unwrap(), 60.0).unwrap().iter().all(|sample| sample.crossings.is_empty()));
+}
+
+#[test]
+fn deployed_wings_add_to_the_body_mounted_power() {
+    let toml = include_str!("../scenarios/leo_3u_deployment.toml");
+    let peak_w = |samples: &[eps_sim::Sample]| samples.iter().map(|sample| sample.generated_power_w).fold(0.0, f64::max);
+    let samples = run(&Scenario::from_toml(toml).unwrap(), 60.0).unwrap();
+    let (stowed, deployed) = samples.split_at(30);
+    assert!(stowed.iter().all(|sample| sample.deployed_panels == 2));
+    assert_eq!(samples.iter().map(|sample| sample.burn_attempts).sum::<usize>(), 2);
+    assert_eq!(deployed.last().unwrap().deployed_panels, 4);
+    // Sun-pointing +X: each wing doubles the +X face, the +Y face sees the sun edge-on
+    assert!((peak_w(deployed) - 5.0 * peak_w(stowed)).abs() < 0.01 * peak_w(stowed), "{} vs {}", peak_w(deployed), peak_w(stowed));
+
+    let stuck = toml.replace("# failure", "failure");
+    let samples = run(&Scenario::from_toml(&stuck).unwrap(), 60.0).unwrap();
+    assert_eq!(samples.iter().map(|sample| sample.burn_attempts).sum::<usize>(), 4);
+    assert_eq!(samples.last().unwrap().deployed_panels, 3);
+    assert!((peak_w(&samples[30..]) - 3.0 * peak_w(&samples[..30])).abs() < 0.01 * peak_w(stowed));
+
+    let unknown = toml.replace("panel = \"WING2\"", "panel = \"WING3\"");
+    assert!(matches!(run(&Scenario::from_toml(&unknown).unwrap(), 60.0), Err(ScenarioError::UnknownPanel(_))));
+}
+
+#[test]
+fn radiation_damage_grows_with_mission_age() {
+    let toml = |extra: &str| format!("mission_age_years = 0.0\n{extra}\n{SCENARIO}");
+    let generated_w = |toml: &str| run(&Scenario::from_toml(toml).unwrap(), 60.0).unwrap()[0].generated_power_w;
+    let radiation = "radiation = { technology = \"silicon\", fluence_rate_e_cm2_year = 1e14 }";
+    let bol_w = generated_w(&toml(radiation));
+    assert_eq!(bol_w, generated_w(&toml("")));
+    let eol_w = generated_w(&toml(radiation).replace("mission_age_years = 0.0", "mission_age_years = 10.0"));
+    assert!((eol_w / bol_w - 0.74).abs() < 1e-9); // 1e15 e/cm^2
+    // The rate needs an orbit when not given
+    let from_orbit = toml("radiation = { technology = \"triple_junction_gaas\" }");
+    assert!(matches!(run(&Scenario::from_toml(&from_orbit).unwrap(), 60.0), Err(ScenarioError::MissingOrbit)));
+}
+
+#[test]
+fn launch_keeps_the_spacecraft_dark_until_separation() {
+    let toml = include_str!("../scenarios/leo_3u_launch.toml");
+    let samples = run(&Scenario::from_toml(toml).unwrap(), 60.0).unwrap();
+    let powered = |sample: &eps_sim::Sample, name: &str| sample.loads.iter().any(|load| load.name == name && load.is_powered);
+    let (stowed, separated) = samples.split_at(20);
+    assert!(stowed.iter().all(|sample| sample.launch_phase == "Stowed" && sample.demanded_power_w == 0.0));
+    assert!(stowed.iter().all(|sample| sample.battery_energy_out_wh == 0.0 && sample.generated_power_w == 0.0));
+    assert!(powered(&separated[0], "OBC") && !powered(&separated[0], "COM_RX"));
+    // Nothing transmits before the RF silence is over, the wing waits for the same 30 minutes
+    let beacon = samples.iter().position(|sample| powered(sample, "COM_TX")).unwrap();
+    assert_eq!(samples[beacon].time_s, 3060.0);
+    let burn = samples.iter().position(|sample| sample.burn_attempts > 0).unwrap();
+    assert!(samples[burn].time_s > 3000.0);
+    assert_eq!(samples.last().unwrap().launch_phase, "Operational");
+
+    let stuck = toml.replace("separation_s = 1200.0", "");
+    let samples = run(&Scenario::from_toml(&stuck).unwrap(), 60.0).unwrap();
+    assert!(samples.iter().all(|sample| sample.launch_phase == "Stowed" && sample.deployed_panels == 1));
+    let unknown = toml.replace("load = \"COM_RX\"", "load = \"ADCS\"");
+    assert!(matches!(run(&Scenario::from_toml(&unknown).unwrap(), 60.0), Err(ScenarioError::UnknownLoad(name)) if name == "ADCS"));
+}
+
+#[test]
+fn bundled_scenario_parses() {
+    for toml in [
+        include_str!("../scenarios/leo_3u.toml"),
+        include_str!("../scenarios/leo_3u_deployment.toml"),
+        include_str!("../scenarios/leo_3u_launch.toml"),
+    ] {
+        let scenario = Scenario::from_toml(toml).unwrap();
+        scenario.build_eps(&scenario.load_registry().unwrap()).unwrap();
+    }
+}
+
+#[test]
+fn scheduled_faults_show_in_the_samples() {
+    let faults = r#"
+[[faults]]
+trigger = { type = "at", time_s = 180.0 }
+fault = { type = "string_open", panel = "+Z" }
+
+[[faults]]
+trigger = { type = "in_mode", mode = "NominalEclipse" }
+fault = { type = "sensor_offset", sensor = "battery_soc", offset = -40.0 }
+"#;
+    let toml = format!("{SCENARIO}{faults}");
+    let samples = run(&Scenario::from_toml(&toml).unwrap(), 60.0).unwrap();
+    let injected: Vec<(f64, String)> =
+        samples.iter().flat_map(|sample| sample.faults_injected.iter().map(|fault| (sample.time_s, fault.clone()))).collect();
+    assert_eq!(injected, [(240.0, "StringOpen(+Z)".to_string()), (360.0, "SensorOffset(BatterySoc-40)".to_string())]);
+    assert!(samples[2].generated_power_w > 0.0 && samples[3].generated_power_w == 0.0);
+    // Reading 10 % the SoC guard puts the spacecraft in SafeMode in the step the offset is injected
+    assert_eq!(samples[5].mode, "SafeMode");
+    let mut csv = Vec::new();
+    output::write(&samples, OutputFormat::Csv, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.lines().nth(4).unwrap().contains(",StringOpen(+Z)"));
+
+    let unknown = toml.replace("panel = \"+Z\"", "panel = \"-Z\"");
+    assert!(matches!(run(&Scenario::from_toml(&unknown).unwrap(), 60.0), Err(ScenarioError::UnknownPanel(id)) if id == "-Z"));
+    let stuck = toml.replace("type = \"string_open\", panel = \"+Z\"", "type = \"load_stuck_on\", load = \"ADCS\"");
+    assert!(matches!(run(&Scenario::from_toml(&stuck).unwrap(), 60.0), Err(ScenarioError::UnknownLoad(name)) if name == "ADCS"));
+}
@@ -0,0 +1,104 @@
+// Battery capacity fade from cycling and calendar ageing.
+// Cycle ageing: rainflow-counted SoC cycles with a Woehler-type life curve
+// N(DoD) = N_100 * DoD^-k, each cycle consuming 1 / N(DoD) of the cycle life.
+// Calendar ageing: fade proportional to the square root of time, doubling every 10 degC.
+// Interpretation of page 26-27, Section 3.5
+use crate::consts::{
+    BATTERY_EOL_CAPACITY_FADE, CALENDAR_FADE_PER_SQRT_YEAR_25C, CALENDAR_FADE_DOUBLING_C, CYCLES_TO_EOL_AT_FULL_DOD,
+    DOD_LIFE_EXPONENT, HOURS_PER_YEAR, RAINFLOW_SOC_HYSTERESIS,
+};
+use crate::rainflow::RainflowCounter;
+
+#[derive(Debug, Clone)]
+pub struct BatteryAgeing {
+    rainflow: RainflowCounter,
+    cycle_life_used: f64,        // Fraction of the cycle life consumed (1.0 = end of life)
+    calendar_fade: f64,          // Capacity fraction lost to calendar ageing
+    calendar_age_h: f64,
+    cycles_to_eol_at_full_dod: f64,
+    dod_life_exponent: f64,
+    eol_capacity_fade: f64,      // Capacity lost when the cycle life is used up (e.g. 0.2 for 80%)
+    calendar_fade_per_sqrt_year: f64,
+}
+
+impl BatteryAgeing {
+    pub fn new() -> Self {
+        BatteryAgeing {
+            rainflow: RainflowCounter::new(RAINFLOW_SOC_HYSTERESIS),
+            cycle_life_used: 0.0,
+            calendar_fade: 0.0,
+            calendar_age_h: 0.0,
+            cycles_to_eol_at_full_dod: CYCLES_TO_EOL_AT_FULL_DOD,
+            dod_life_exponent: DOD_LIFE_EXPONENT,
+            eol_capacity_fade: BATTERY_EOL_CAPACITY_FADE,
+            calendar_fade_per_sqrt_year: CALENDAR_FADE_PER_SQRT_YEAR_25C,
+        }
+    }
+
+    pub fn with_cycle_life(mut self, cycles_to_eol_at_full_dod: f64, dod_life_exponent: f64, eol_capacity_fade: f64) -> Self {
+        self.cycles_to_eol_at_full_dod = cycles_to_eol_at_full_dod;
+        self.dod_life_exponent = dod_life_exponent;
+        self.eol_capacity_fade = eol_capacity_fade;
+        self
+    }
+
+    pub fn with_calendar_fade(mut self, calendar_fade_per_sqrt_year_25c: f64) -> Self {
+        self.calendar_fade_per_sqrt_year = calendar_fade_per_sqrt_year_25c;
+        self
+    }
+
+    /// Cycles to end of life at a given depth of discharge (0.0 to 1.0).
+    pub fn cycles_to_eol(&self, depth_of_discharge: f64) -> f64 {
+        cycles_to_eol(self.cycles_to_eol_at_full_dod, self.dod_life_exponent, depth_of_discharge)
+    }
+
+    /// Records the state of charge (0.0 to 1.0) at the end of a step and ages the cells for its duration.
+    pub fn update(&mut self, soc: f64, temperature_c: f64, duration_h: f64) {
+        let mut life_used = 0.0;
+        let cycles_to_eol_at_full_dod = self.cycles_to_eol_at_full_dod;
+        let exponent = self.dod_life_exponent;
+        self.rainflow.push_sample(soc, |range, count| {
+            life_used += count / cycles_to_eol(cycles_to_eol_at_full_dod, exponent, range);
+        });
+        self.cycle_life_used += life_used;
+
+        if duration_h > 0.0 {
+            let rate = self.calendar_fade_per_sqrt_year * libm::pow(2.0, (temperature_c - 25.0) / CALENDAR_FADE_DOUBLING_C);
+            let years_before = self.calendar_age_h / HOURS_PER_YEAR;
+            self.calendar_age_h += duration_h;
+            let years_after = self.calendar_age_h / HOURS_PER_YEAR;
+            self.calendar_fade += rate * (libm::sqrt(years_after) - libm::sqrt(years_before));
+        }
+    }
+
+    pub fn get_cycle_count(&self) -> f64 {
+        self.rainflow.get_cycle_count()
+    }
+
+    pub fn get_rainflow(&self) -> &RainflowCounter {
+        &self.rainflow
+    }
+
+    pub fn get_cycle_fade(&self) -> f64 {
+        self.cycle_life_used * self.eol_capacity_fade
+    }
+
+    pub fn get_calendar_fade(&self) -> f64 {
+        self.calendar_fade
+    }
+
+    /// Remaining fraction of the rated capacity.
+    pub fn get_health(&self) -> f64 {
+        (1.0 - self.get_cycle_fade() - self.calendar_fade).max(0.0)
+    }
+}
+
+impl Default for BatteryAgeing {
+    fn default() -> Self {
+        BatteryAgeing::new()
+    }
+}
+
+fn cycles_to_eol(cycles_to_eol_at_full_dod: f64, dod_life_exponent: f64, depth_of_discharge: f64) -> f64 {
+    cycles_to_eol_at_full_dod * libm::pow(depth_of_discharge.clamp(1e-3, 1.0), -dod_life_exponent)
+}
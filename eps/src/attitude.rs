@@ -0,0 +1,121 @@
+// Simplified attitude subsystem producing the sun direction in the body frame for the panel model.
+// Dependence on Satellite Attitude (Angle to Sun): page 12, Section 1.3
+use crate::orbit::Orbit;
+use crate::vector::Vector3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttitudeMode {
+    // Keeps the given body axis pointed at the sun
+    SunPointing { axis_body: Vector3 },
+    // LVLH frame: +Z towards nadir, +X along the velocity, +Y towards the negative orbit normal
+    NadirPointing,
+    // Holds the current attitude fixed in inertial space
+    InertialHold,
+    // Uncontrolled rotation at constant body rates, e.g. right after separation
+    Tumbling { body_rates_deg_s: Vector3 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Attitude {
+    mode: AttitudeMode,
+    sun_vector_body: Vector3,
+    last_update_time_s: Option<f64>,
+    safe_pointing_axis_body: Vector3, // Axis pointed at the sun when the EPS enters SafeMode
+    mode_before_safe: Option<AttitudeMode>, // Restored when the EPS leaves SafeMode
+}
+
+impl Attitude {
+    pub fn new(mode: AttitudeMode, initial_sun_vector_body: Vector3) -> Self {
+        Attitude {
+            mode,
+            sun_vector_body: initial_sun_vector_body.normalized(),
+            last_update_time_s: None,
+            safe_pointing_axis_body: Vector3::Z,
+            mode_before_safe: None,
+        }
+    }
+
+    pub fn with_safe_pointing_axis(mut self, axis_body: Vector3) -> Self {
+        self.safe_pointing_axis_body = axis_body.normalized();
+        self
+    }
+
+    pub fn get_mode(&self) -> &AttitudeMode {
+        &self.mode
+    }
+
+    /// Also replaces the mode saved on entering SafeMode, so leaving SafeMode keeps the commanded one.
+    pub fn set_mode(&mut self, mode: AttitudeMode) {
+        self.mode = mode;
+        self.mode_before_safe = None;
+    }
+
+    /// Survival attitude: point the configured axis (usually the largest array) at the sun.
+    pub fn enter_safe_mode(&mut self) {
+        self.mode_before_safe.get_or_insert_with(|| self.mode.clone());
+        self.mode = AttitudeMode::SunPointing { axis_body: self.safe_pointing_axis_body };
+    }
+
+    /// Goes back to the mode held before [`Attitude::enter_safe_mode`].
+    pub fn exit_safe_mode(&mut self) {
+        if let Some(mode) = self.mode_before_safe.take() {
+            self.mode = mode;
+        }
+    }
+
+    pub fn get_sun_vector_body(&self) -> Vector3 {
+        self.sun_vector_body
+    }
+
+    /// Overrides the current attitude estimate, e.g. the starting point of an inertial hold or tumble.
+    pub fn set_sun_vector_body(&mut self, sun_vector_body: Vector3) {
+        self.sun_vector_body = sun_vector_body.normalized();
+    }
+
+    /// Propagates the attitude to mission time `time_s` and returns the sun vector in the body frame.
+    /// Nadir pointing needs an orbit; without one the last known attitude is held.
+    pub fn update(&mut self, orbit: Option<&Orbit>, time_s: f64) -> Vector3 {
+        let dt_s = self.last_update_time_s.map_or(0.0, |last| time_s - last);
+        self.last_update_time_s = Some(time_s);
+
+        match &self.mode {
+            AttitudeMode::SunPointing { axis_body } => {
+                self.sun_vector_body = axis_body.normalized();
+            }
+            AttitudeMode::NadirPointing => {
+                if let Some(orbit) = orbit {
+                    let sun_eci = orbit.get_sun_vector_eci();
+                    let z_body = -orbit.get_position_eci_km(time_s).normalized();
+                    let x_body = orbit.get_velocity_direction_eci(time_s);
+                    let y_body = z_body.cross(&x_body);
+                    self.sun_vector_body = Vector3::new(sun_eci.dot(&x_body), sun_eci.dot(&y_body), sun_eci.dot(&z_body));
+                }
+            }
+            AttitudeMode::InertialHold => {
+                // The sun is fixed in inertial space over an orbit, so its body direction is too
+            }
+            AttitudeMode::Tumbling { body_rates_deg_s } => {
+                let rate_deg_s = body_rates_deg_s.norm();
+                if rate_deg_s > 0.0 {
+                    // An inertially fixed vector rotates at -omega as seen from the spinning body
+                    let axis = body_rates_deg_s.normalized();
+                    let angle_rad = -(rate_deg_s * dt_s).to_radians();
+                    self.sun_vector_body = rotate(&self.sun_vector_body, &axis, angle_rad).normalized();
+                }
+            }
+        }
+        self.sun_vector_body
+    }
+}
+
+impl Default for Attitude {
+    fn default() -> Self {
+        Attitude::new(AttitudeMode::InertialHold, Vector3::Z)
+    }
+}
+
+// Rodrigues' rotation of `v` by `angle_rad` around the unit vector `axis`
+fn rotate(v: &Vector3, axis: &Vector3, angle_rad: f64) -> Vector3 {
+    let (sin_a, cos_a) = libm::sincos(angle_rad);
+    *v * cos_a + axis.cross(v) * sin_a + *axis * (axis.dot(v) * (1.0 - cos_a))
+}
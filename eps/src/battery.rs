@@ -0,0 +1,509 @@
+// Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars
+use crate::ageing::BatteryAgeing;
+use crate::battery_thermal::BatteryThermal;
+use crate::cell_pack::CellPack;
+use crate::consts::{
+    LI_ION_CELL_MAX_V, LI_ION_CELL_MIN_V, LI_ION_CELL_NOMINAL_V, LI_ION_MIN_DISCHARGE_TEMPERATURE_C, LI_ION_OVER_TEMPERATURE_C,
+    OVER_CURRENT_MARGIN, OVER_VOLTAGE_MARGIN, SEVERE_DEGRADATION_HEALTH, VOLTAGE_LIMIT_TOLERANCE_V,
+};
+use crate::enums::{BatteryFault, BatteryState, CellFailure, PackError};
+use crate::equivalent_circuit::EquivalentCircuit;
+use crate::faults::{BatteryFaultLimits, FaultLog};
+use crate::{Id, make_id};
+
+#[derive(Debug, Clone)]
+pub struct Battery {
+    id: Id,
+    capacity_wh: f64,         // Page 22, Section 3.2
+    pub(crate) current_charge_wh: f64,   // Page 22, Section 3.2
+    nominal_voltage_v: f64,   // Page 22, Section 3.2
+    max_discharge_rate_w: f64,// Page 22, Section 3.2
+    max_charge_rate_w: f64,   // Inferred from max_discharge_rate_w
+    pub(crate) state: BatteryState,
+    health_percentage: f64,   // Interpretation of page 26-27, Section 3.5, range 0.0 to 1.0, from `ageing`
+    charge_efficiency: f64,   // How efficiently it stores power (e.g. 95%)
+    discharge_efficiency: f64,// How efficiently it delivers power (e.g. 95%)
+    circuit: EquivalentCircuit,
+    rc_voltage_v: f64,        // Voltage across the RC pair
+    current_a: f64,           // Positive while discharging
+    terminal_voltage_v: f64,
+    thermal: BatteryThermal,
+    heat_generated_w: f64,    // Resistive losses over the last step
+    ageing: BatteryAgeing,    // Battery lifespan: rainflow-counted cycles and calendar fade
+    faults: FaultLog,         // Page 25, Section 3.4
+    fault_limits: BatteryFaultLimits,
+    time_s: f64,              // Mission elapsed time, used to timestamp faults
+    pack: Option<CellPack>,   // Cell-level model; when present it holds the charge and sets the limits
+}
+
+impl Battery {
+    /// Uses a generic Li-ion equivalent circuit with as many series cells as fit `voltage_v`.
+    pub fn new(id: &str, capacity_wh: f64, initial_charge_wh: f64, voltage_v: f64, max_charge_rate_w: f64, max_discharge_rate_w: f64) -> Self {
+        let initial_charge_wh = initial_charge_wh.min(capacity_wh);
+        let series_cells = libm::round(voltage_v / LI_ION_CELL_NOMINAL_V).max(1.0) as u32;
+        let circuit = EquivalentCircuit::li_ion(series_cells);
+        let fault_limits = BatteryFaultLimits {
+            over_temperature_c: LI_ION_OVER_TEMPERATURE_C,
+            min_discharge_temperature_c: LI_ION_MIN_DISCHARGE_TEMPERATURE_C,
+            over_voltage_v: circuit.get_max_voltage_v() * OVER_VOLTAGE_MARGIN,
+            over_current_a: max_charge_rate_w.max(max_discharge_rate_w) / circuit.get_min_voltage_v() * OVER_CURRENT_MARGIN,
+            severe_degradation_health: SEVERE_DEGRADATION_HEALTH,
+        };
+        let mut battery = Battery {
+            id: make_id(id),
+            capacity_wh,
+            current_charge_wh: initial_charge_wh,
+            nominal_voltage_v: voltage_v,
+            max_charge_rate_w,
+            max_discharge_rate_w,
+            state: BatteryState::Idle,
+            health_percentage: 1.0,     // Starts at 100%
+            charge_efficiency: 0.9,     // Default 90%
+            discharge_efficiency: 0.9,  // Default 90%
+            circuit,
+            rc_voltage_v: 0.0,
+            current_a: 0.0,
+            terminal_voltage_v: 0.0,
+            thermal: BatteryThermal::default(),
+            heat_generated_w: 0.0,
+            ageing: BatteryAgeing::default(),
+            faults: FaultLog::new(),
+            fault_limits,
+            time_s: 0.0,
+            pack: None,
+        };
+        battery.terminal_voltage_v = battery.get_open_circuit_voltage_v();
+        battery
+    }
+
+    /// Battery made of an SxP cell pack. Capacity, voltage and resistance follow from the cells, and
+    /// the weakest series group limits charge and discharge.
+    pub fn from_pack(id: &str, pack: CellPack, max_charge_rate_w: f64, max_discharge_rate_w: f64) -> Self {
+        let circuit = pack.get_equivalent_circuit();
+        let mut battery = Battery::new(id, pack.get_capacity_wh(), 0.0, pack.get_nominal_voltage_v(), max_charge_rate_w, max_discharge_rate_w)
+            .with_equivalent_circuit(circuit);
+        battery.pack = Some(pack);
+        battery.sync_pack();
+        battery.terminal_voltage_v = battery.get_open_circuit_voltage_v();
+        battery
+    }
+
+    pub fn with_equivalent_circuit(mut self, circuit: EquivalentCircuit) -> Self {
+        self.fault_limits.over_voltage_v = circuit.get_max_voltage_v() * OVER_VOLTAGE_MARGIN;
+        self.circuit = circuit;
+        self.rc_voltage_v = 0.0;
+        self.terminal_voltage_v = self.get_open_circuit_voltage_v();
+        self
+    }
+
+    pub fn with_fault_limits(mut self, fault_limits: BatteryFaultLimits) -> Self {
+        self.fault_limits = fault_limits;
+        self
+    }
+
+    pub fn with_ageing(mut self, ageing: BatteryAgeing) -> Self {
+        self.health_percentage = ageing.get_health();
+        self.ageing = ageing;
+        self
+    }
+
+    pub fn with_thermal(mut self, thermal: BatteryThermal) -> Self {
+        self.thermal = thermal;
+        self
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_pack(&self) -> Option<&CellPack> {
+        self.pack.as_ref()
+    }
+
+    /// Fails one cell of the pack. The pack circuit is rebuilt and the resulting faults are latched at once.
+    pub fn fail_cell(&mut self, group: usize, position: usize, failure: CellFailure) -> Result<(), PackError> {
+        let pack = self.pack.as_mut().ok_or(PackError::CellNotFound)?;
+        pack.fail_cell(group, position, failure)?;
+        let circuit = pack.get_equivalent_circuit();
+        self.fault_limits.over_voltage_v = circuit.get_max_voltage_v() * OVER_VOLTAGE_MARGIN;
+        self.circuit = circuit;
+        self.sync_pack();
+        self.check_faults();
+        Ok(())
+    }
+
+    pub fn get_effective_capacity_wh(&self) -> f64 {
+        self.capacity_wh * self.health_percentage
+    }
+
+    /// Energy that can still be discharged at the current temperature. Cold cells cannot
+    /// deliver the bottom part of their capacity; it becomes available again once warm.
+    pub fn get_available_energy_wh(&self) -> f64 {
+        if let Some(pack) = &self.pack {
+            return pack.get_available_charge_ah(self.thermal.get_capacity_factor()) * pack.get_nominal_voltage_v();
+        }
+        (self.current_charge_wh - self.get_effective_capacity_wh() * (1.0 - self.thermal.get_capacity_factor())).max(0.0)
+    }
+
+    /// Charges with `power_w` measured at the battery terminals. Active faults are respected by [`Battery::charge_current`].
+    /// Returns the power actually accepted at the terminals.
+    pub fn charge(&mut self, mut power_w: f64, duration_h: f64) -> f64 {
+        power_w = power_w.min(self.max_charge_rate_w * self.health_percentage);
+        let (emf_v, resistance_ohm) = self.get_step_thevenin(duration_h);
+        // P = I * (E + I * R) solved for the charge current I
+        let current_a = if resistance_ohm > 0.0 {
+            (-emf_v + libm::sqrt(emf_v * emf_v + 4.0 * resistance_ohm * power_w)) / (2.0 * resistance_ohm)
+        } else {
+            power_w / emf_v
+        };
+        let accepted_a = self.charge_current(current_a, duration_h);
+        accepted_a * self.terminal_voltage_v
+    }
+
+    /// Charges with a constant current, reduced as needed to respect the over-voltage limit.
+    /// Returns the current actually accepted, zero while a fault blocks charging.
+    pub fn charge_current(&mut self, current_a: f64, duration_h: f64) -> f64 {
+        if current_a > 0.0 && self.thermal.is_charge_inhibited() {
+            self.raise_fault(BatteryFault::ChargeUnderTemperature);
+        } else {
+            self.release_charge_inhibit();
+        }
+        if let Some(fault) = self.faults.charge_blocker() {
+            self.state = BatteryState::Fault(fault);
+            self.rest(duration_h);
+            return 0.0;
+        }
+
+        let effective_capacity = self.get_effective_capacity_wh();
+
+        if self.is_full() {
+            self.state = BatteryState::Full;
+            self.current_charge_wh = self.current_charge_wh.min(effective_capacity);
+            self.rest(duration_h);
+            return 0.0;
+        }
+
+        let (emf_v, resistance_ohm) = self.get_step_thevenin(duration_h);
+        let mut current_a = current_a.max(0.0);
+        if resistance_ohm > 0.0 {
+            // Constant-voltage taper once the terminal voltage reaches the end-of-charge limit
+            current_a = current_a.min(((self.circuit.get_max_voltage_v() - emf_v) / resistance_ohm).max(0.0));
+        }
+        let ocv_v = self.get_open_circuit_voltage_v();
+        if let Some(pack) = &self.pack {
+            current_a = current_a.min(pack.get_max_charge_current_a(duration_h, self.charge_efficiency));
+        } else {
+            let headroom_wh = effective_capacity - self.current_charge_wh;
+            if current_a * ocv_v * duration_h * self.charge_efficiency > headroom_wh {
+                current_a = headroom_wh / (ocv_v * duration_h * self.charge_efficiency);
+            }
+            self.current_charge_wh += current_a * ocv_v * duration_h * self.charge_efficiency;
+        }
+
+        self.state = BatteryState::Charging;
+        self.apply_current(-current_a, ocv_v, duration_h);
+
+        if self.is_full() {
+            self.current_charge_wh = self.current_charge_wh.min(effective_capacity);
+            self.state = BatteryState::Full;
+        }
+        current_a
+    }
+
+    /// Charges from a source holding the terminals at `voltage_v` (e.g. a direct-energy-transfer bus),
+    /// limited to `max_current_a`. Returns the current actually accepted.
+    pub fn charge_at_voltage(&mut self, voltage_v: f64, max_current_a: f64, duration_h: f64) -> f64 {
+        let (emf_v, resistance_ohm) = self.get_step_thevenin(duration_h);
+        let current_a = if resistance_ohm > 0.0 { (voltage_v - emf_v) / resistance_ohm } else { max_current_a };
+        self.charge_current(current_a.clamp(0.0, max_current_a), duration_h)
+    }
+
+    // Returns actual power supplied at the terminals. Active faults are respected by `discharge_current`.
+    pub fn discharge(&mut self, mut power_demand_w: f64, duration_h: f64) -> f64 {
+        power_demand_w = power_demand_w.min(self.max_discharge_rate_w * self.health_percentage);
+        let (emf_v, resistance_ohm) = self.get_step_thevenin(duration_h);
+        // P = I * (E - I * R) solved for the smaller root; beyond E^2 / 4R the demand cannot be met
+        let current_a = if resistance_ohm > 0.0 {
+            let discriminant = emf_v * emf_v - 4.0 * resistance_ohm * power_demand_w;
+            (emf_v - libm::sqrt(discriminant.max(0.0))) / (2.0 * resistance_ohm)
+        } else {
+            power_demand_w / emf_v
+        };
+        let delivered_a = self.discharge_current(current_a, duration_h);
+        delivered_a * self.terminal_voltage_v
+    }
+
+    /// Discharges with a constant current, reduced as needed to respect the under-voltage limit
+    /// and the stored energy. Returns the current actually delivered, zero while a fault blocks discharging.
+    pub fn discharge_current(&mut self, current_a: f64, duration_h: f64) -> f64 {
+        if let Some(fault) = self.faults.discharge_blocker() {
+            self.state = BatteryState::Fault(fault);
+            self.rest(duration_h);
+            return 0.0;
+        }
+
+        let available_wh = self.get_available_energy_wh();
+        if available_wh <= 0.0 {
+            self.state = BatteryState::Empty;
+            self.current_charge_wh = self.current_charge_wh.max(0.0);
+            self.rest(duration_h);
+            return 0.0;
+        }
+
+        let (emf_v, resistance_ohm) = self.get_step_thevenin(duration_h);
+        let requested_a = current_a.max(0.0);
+        let mut current_a = requested_a;
+        if resistance_ohm > 0.0 {
+            current_a = current_a.min(((emf_v - self.circuit.get_min_voltage_v()) / resistance_ohm).max(0.0));
+        }
+        let ocv_v = self.get_open_circuit_voltage_v();
+        if let Some(pack) = &self.pack {
+            let capacity_factor = self.thermal.get_capacity_factor();
+            current_a = current_a.min(pack.get_max_discharge_current_a(duration_h, self.discharge_efficiency, capacity_factor));
+        } else {
+            if current_a * ocv_v * duration_h / self.discharge_efficiency > available_wh {
+                current_a = available_wh * self.discharge_efficiency / (ocv_v * duration_h);
+            }
+            self.current_charge_wh -= current_a * ocv_v * duration_h / self.discharge_efficiency;
+        }
+
+        self.state = BatteryState::Discharging;
+        self.apply_current(current_a, ocv_v, duration_h);
+
+        // Empty once depleted or once the under-voltage limit chokes the current almost completely
+        if self.get_available_energy_wh() <= 0.0 || (requested_a > 0.0 && current_a <= requested_a * 0.01) {
+            self.current_charge_wh = self.current_charge_wh.max(0.0);
+            self.state = BatteryState::Empty;
+        }
+        current_a
+    }
+
+    /// No current flows: the RC pair relaxes towards the open-circuit voltage.
+    pub fn rest(&mut self, duration_h: f64) {
+        self.apply_current(0.0, self.get_open_circuit_voltage_v(), duration_h);
+    }
+
+    /// With a cell pack, the SoC of its weakest series group.
+    pub fn get_soc_percentage(&self) -> f64 {
+        if let Some(pack) = &self.pack {
+            return pack.get_soc() * 100.0;
+        }
+        let capacity_wh = self.get_effective_capacity_wh();
+        if capacity_wh <= 0.0 {
+            return 0.0;
+        }
+        (self.current_charge_wh / capacity_wh) * 100.0
+    }
+
+    pub fn get_charge_wh(&self) -> f64 {
+        self.current_charge_wh
+    }
+
+    pub fn get_nominal_voltage_v(&self) -> f64 {
+        self.nominal_voltage_v
+    }
+
+    pub fn get_open_circuit_voltage_v(&self) -> f64 {
+        if let Some(pack) = &self.pack {
+            return pack.get_open_circuit_voltage_v();
+        }
+        self.circuit.get_open_circuit_voltage_v(self.get_soc_percentage() / 100.0)
+    }
+
+    pub fn get_terminal_voltage_v(&self) -> f64 {
+        self.terminal_voltage_v
+    }
+
+    pub fn get_current_a(&self) -> f64 {
+        self.current_a
+    }
+
+    pub fn get_equivalent_circuit(&self) -> &EquivalentCircuit {
+        &self.circuit
+    }
+
+    // The limits clamp the current so the terminal voltage lands on them up to rounding
+    pub fn is_under_voltage(&self) -> bool {
+        self.terminal_voltage_v <= self.circuit.get_min_voltage_v() + VOLTAGE_LIMIT_TOLERANCE_V
+    }
+
+    pub fn is_over_voltage(&self) -> bool {
+        self.terminal_voltage_v >= self.circuit.get_max_voltage_v() - VOLTAGE_LIMIT_TOLERANCE_V
+    }
+
+    pub fn get_thermal(&self) -> &BatteryThermal {
+        &self.thermal
+    }
+
+    pub fn get_temperature_c(&self) -> f64 {
+        self.thermal.get_temperature_c()
+    }
+
+    pub fn get_heat_generated_w(&self) -> f64 {
+        self.heat_generated_w
+    }
+
+    /// Advances the battery temperature with the environment, the I^2*R losses of the last step
+    /// and any heater power.
+    pub fn update_temperature(&mut self, sunlit: bool, heater_power_w: f64, duration_h: f64) {
+        self.thermal.update(sunlit, self.heat_generated_w + heater_power_w, duration_h);
+    }
+
+    /// Mission elapsed time used to timestamp the faults latched from now on.
+    pub fn set_time_s(&mut self, time_s: f64) {
+        self.time_s = time_s;
+    }
+
+    pub fn get_faults(&self) -> &FaultLog {
+        &self.faults
+    }
+
+    pub fn get_fault_limits(&self) -> &BatteryFaultLimits {
+        &self.fault_limits
+    }
+
+    /// Latches `fault` at the current time. Returns true if it was not already active.
+    pub fn raise_fault(&mut self, fault: BatteryFault) -> bool {
+        self.faults.raise(fault, self.time_s)
+    }
+
+    /// Ground command: clears a latched fault. Returns false if it was not active.
+    pub fn clear_fault(&mut self, fault: BatteryFault) -> bool {
+        let cleared = self.faults.clear(fault);
+        if self.state == BatteryState::Fault(fault) {
+            self.state = BatteryState::Idle;
+        }
+        cleared
+    }
+
+    pub fn clear_all_faults(&mut self) {
+        self.faults.clear_all();
+        if matches!(self.state, BatteryState::Fault(_)) {
+            self.state = BatteryState::Idle;
+        }
+    }
+
+    /// Rainflow-counted charge/discharge cycles, half cycles weighing 0.5.
+    pub fn get_cycles(&self) -> f64 {
+        self.ageing.get_cycle_count()
+    }
+
+    pub fn get_ageing(&self) -> &BatteryAgeing {
+        &self.ageing
+    }
+
+    pub fn get_health(&self) -> f64 {
+        self.health_percentage
+    }
+
+    pub fn get_status(&self) -> &BatteryState {
+        &self.state
+    }
+
+    // Thevenin source seen over a step with constant current: the RC voltage decays from its
+    // current value while the RC resistance charges up, so V_end = E - I * R
+    fn get_step_thevenin(&self, duration_h: f64) -> (f64, f64) {
+        let resistance_factor = self.thermal.get_resistance_factor();
+        let decay = self.circuit.rc_decay(duration_h * 3600.0, resistance_factor);
+        let emf_v = self.get_open_circuit_voltage_v() - self.rc_voltage_v * decay;
+        let resistance_ohm = (self.circuit.get_series_resistance_ohm() + self.circuit.get_rc_resistance_ohm() * (1.0 - decay)) * resistance_factor;
+        (emf_v, resistance_ohm)
+    }
+
+    // Exact RC update for a constant current over the step, then the resulting terminal voltage.
+    // The OCV is held at its start-of-step value so the terminal power matches the solved current.
+    fn apply_current(&mut self, current_a: f64, ocv_v: f64, duration_h: f64) {
+        let resistance_factor = self.thermal.get_resistance_factor();
+        let r0_ohm = self.circuit.get_series_resistance_ohm() * resistance_factor;
+        let r1_ohm = self.circuit.get_rc_resistance_ohm() * resistance_factor;
+        let decay = self.circuit.rc_decay(duration_h * 3600.0, resistance_factor);
+        self.rc_voltage_v = self.rc_voltage_v * decay + current_a * r1_ohm * (1.0 - decay);
+        self.current_a = current_a;
+        self.terminal_voltage_v = ocv_v - current_a * r0_ohm - self.rc_voltage_v;
+        self.heat_generated_w = current_a * current_a * r0_ohm + if r1_ohm > 0.0 { self.rc_voltage_v * self.rc_voltage_v / r1_ohm } else { 0.0 };
+        if let Some(pack) = &mut self.pack {
+            pack.apply_current(current_a, duration_h, self.charge_efficiency, self.discharge_efficiency);
+            self.heat_generated_w += pack.balance(duration_h);
+        }
+        self.apply_ageing(duration_h);
+        self.check_faults();
+    }
+
+    fn check_faults(&mut self) {
+        let temperature_c = self.thermal.get_temperature_c();
+        if temperature_c > self.fault_limits.over_temperature_c {
+            self.raise_fault(BatteryFault::OverTemperature);
+        }
+        if temperature_c < self.fault_limits.min_discharge_temperature_c && self.current_a > 0.0 {
+            self.raise_fault(BatteryFault::UnderTemperature);
+        }
+        if self.terminal_voltage_v > self.fault_limits.over_voltage_v {
+            self.raise_fault(BatteryFault::OverVoltage);
+        }
+        if self.current_a > 0.0 && self.is_under_voltage() {
+            self.raise_fault(BatteryFault::UnderVoltage);
+        }
+        if libm::fabs(self.current_a) > self.fault_limits.over_current_a {
+            self.raise_fault(BatteryFault::OverCurrent);
+        }
+        if self.health_percentage < self.fault_limits.severe_degradation_health {
+            self.raise_fault(BatteryFault::SevereDegradation);
+        }
+        self.release_charge_inhibit();
+        // Cell-level limits: the weakest and the fullest group trip before the pack voltage shows it
+        let Some(pack) = &self.pack else {
+            return;
+        };
+        let (is_open, has_short) = (pack.is_open(), pack.has_shorted_cell());
+        let cell_over_voltage = pack.get_max_cell_voltage_v() > LI_ION_CELL_MAX_V * OVER_VOLTAGE_MARGIN;
+        let cell_under_voltage = self.current_a > 0.0 && pack.get_min_cell_voltage_v() <= LI_ION_CELL_MIN_V + VOLTAGE_LIMIT_TOLERANCE_V;
+        if is_open {
+            self.raise_fault(BatteryFault::OpenCircuit);
+        }
+        if has_short {
+            self.raise_fault(BatteryFault::CellShort);
+        }
+        if cell_over_voltage {
+            self.raise_fault(BatteryFault::OverVoltage);
+        }
+        if cell_under_voltage {
+            self.raise_fault(BatteryFault::UnderVoltage);
+        }
+    }
+
+    // The cold charge inhibit lifts as soon as the cells are warm enough again
+    fn release_charge_inhibit(&mut self) {
+        if !self.thermal.is_charge_inhibited()
+            && self.faults.release(BatteryFault::ChargeUnderTemperature)
+            && self.state == BatteryState::Fault(BatteryFault::ChargeUnderTemperature)
+        {
+            self.state = BatteryState::Idle;
+        }
+    }
+
+    // Capacity fade replaces the manual health degradation of the prototype
+    fn apply_ageing(&mut self, duration_h: f64) {
+        self.ageing.update(self.get_soc_percentage() / 100.0, self.thermal.get_temperature_c(), duration_h);
+        self.health_percentage = self.ageing.get_health();
+        self.current_charge_wh = self.current_charge_wh.min(self.get_effective_capacity_wh());
+        self.sync_pack();
+    }
+
+    fn is_full(&self) -> bool {
+        match &self.pack {
+            Some(pack) => pack.get_max_soc() >= 0.999,
+            None => self.current_charge_wh >= self.get_effective_capacity_wh() * 0.999,
+        }
+    }
+
+    // The pack holds the charge; the pack-level figures follow its weakest group
+    fn sync_pack(&mut self) {
+        let Some(pack) = &mut self.pack else {
+            return;
+        };
+        pack.set_health(self.health_percentage);
+        self.capacity_wh = pack.get_capacity_wh();
+        self.nominal_voltage_v = pack.get_nominal_voltage_v();
+        self.current_charge_wh = pack.get_soc() * self.capacity_wh * self.health_percentage;
+    }
+}
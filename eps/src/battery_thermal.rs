@@ -0,0 +1,85 @@
+// Lumped-capacitance battery thermal model:
+// C * dT/dt = (T_env - T) / R_th + P_internal + P_heater
+use crate::consts::{
+    BATTERY_ENVIRONMENT_ECLIPSE_C, BATTERY_ENVIRONMENT_SUNLIT_C, BATTERY_HEAT_CAPACITY_J_K, BATTERY_INITIAL_TEMPERATURE_C,
+    BATTERY_THERMAL_RESISTANCE_K_W, LI_ION_CAPACITY_TEMPERATURE_CURVE, LI_ION_CHARGE_INHIBIT_BELOW_C,
+    LI_ION_REFERENCE_TEMPERATURE_C, LI_ION_RESISTANCE_TEMPERATURE_COEFF_PER_C,
+};
+use crate::interpolate;
+
+#[derive(Debug, Clone)]
+pub struct BatteryThermal {
+    temperature_c: f64,
+    heat_capacity_j_k: f64,
+    thermal_resistance_k_w: f64,  // Battery to its surroundings
+    environment_sunlit_c: f64,    // Surroundings temperature in sunlight
+    environment_eclipse_c: f64,   // Surroundings temperature in eclipse
+    charge_inhibit_below_c: f64,  // Li-ion must not be charged below ~0 degC (lithium plating)
+}
+
+impl BatteryThermal {
+    pub fn new(initial_temperature_c: f64) -> Self {
+        BatteryThermal {
+            temperature_c: initial_temperature_c,
+            heat_capacity_j_k: BATTERY_HEAT_CAPACITY_J_K,
+            thermal_resistance_k_w: BATTERY_THERMAL_RESISTANCE_K_W,
+            environment_sunlit_c: BATTERY_ENVIRONMENT_SUNLIT_C,
+            environment_eclipse_c: BATTERY_ENVIRONMENT_ECLIPSE_C,
+            charge_inhibit_below_c: LI_ION_CHARGE_INHIBIT_BELOW_C,
+        }
+    }
+
+    pub fn with_thermal_mass(mut self, heat_capacity_j_k: f64, thermal_resistance_k_w: f64) -> Self {
+        self.heat_capacity_j_k = heat_capacity_j_k;
+        self.thermal_resistance_k_w = thermal_resistance_k_w;
+        self
+    }
+
+    pub fn with_environment(mut self, environment_sunlit_c: f64, environment_eclipse_c: f64) -> Self {
+        self.environment_sunlit_c = environment_sunlit_c;
+        self.environment_eclipse_c = environment_eclipse_c;
+        self
+    }
+
+    pub fn with_charge_inhibit_below(mut self, temperature_c: f64) -> Self {
+        self.charge_inhibit_below_c = temperature_c;
+        self
+    }
+
+    pub fn get_temperature_c(&self) -> f64 {
+        self.temperature_c
+    }
+
+    pub fn set_temperature_c(&mut self, temperature_c: f64) {
+        self.temperature_c = temperature_c;
+    }
+
+    pub fn is_charge_inhibited(&self) -> bool {
+        self.temperature_c < self.charge_inhibit_below_c
+    }
+
+    /// Fraction of the rated capacity that can be discharged at the current temperature.
+    pub fn get_capacity_factor(&self) -> f64 {
+        interpolate(&LI_ION_CAPACITY_TEMPERATURE_CURVE, self.temperature_c)
+    }
+
+    /// Multiplier on the equivalent-circuit resistances, 1.0 at the reference temperature.
+    pub fn get_resistance_factor(&self) -> f64 {
+        libm::exp(LI_ION_RESISTANCE_TEMPERATURE_COEFF_PER_C * (LI_ION_REFERENCE_TEMPERATURE_C - self.temperature_c))
+    }
+
+    /// Advances the temperature over the step with the exact solution for constant heat inputs.
+    pub fn update(&mut self, sunlit: bool, heat_w: f64, duration_h: f64) {
+        let environment_c = if sunlit { self.environment_sunlit_c } else { self.environment_eclipse_c };
+        let equilibrium_c = environment_c + heat_w * self.thermal_resistance_k_w;
+        let tau_s = self.heat_capacity_j_k * self.thermal_resistance_k_w;
+        let decay = if tau_s > 0.0 { libm::exp(-duration_h * 3600.0 / tau_s) } else { 0.0 };
+        self.temperature_c = equilibrium_c + (self.temperature_c - equilibrium_c) * decay;
+    }
+}
+
+impl Default for BatteryThermal {
+    fn default() -> Self {
+        BatteryThermal::new(BATTERY_INITIAL_TEMPERATURE_C)
+    }
+}
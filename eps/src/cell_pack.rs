@@ -0,0 +1,417 @@
+// SxP Li-ion pack: S series groups of P parallel cells. Cells within a group share their voltage, so
+// they always sit at the same SoC and the group behaves like one larger cell; series groups carry the
+// same current and drift apart with their capacity and resistance spread. The weakest group sets the
+// usable pack capacity and is the first to hit a voltage limit. Passive balancing bleeds the high groups
+// while the pack is charging.
+use heapless::Vec;
+
+use crate::consts::{
+    BALANCE_BLEED_RESISTANCE_OHM, BALANCE_THRESHOLD_V, LI_ION_CELL_C1_F, LI_ION_CELL_CAPACITY_AH, LI_ION_CELL_MAX_V,
+    LI_ION_CELL_MIN_V, LI_ION_CELL_NOMINAL_V, LI_ION_CELL_OCV_CURVE, LI_ION_CELL_R0_OHM, LI_ION_CELL_R1_OHM,
+    MAX_OCV_POINTS, MAX_PACK_PARALLEL, MAX_PACK_SERIES,
+};
+use crate::enums::{CellFailure, PackError};
+use crate::equivalent_circuit::EquivalentCircuit;
+use crate::interpolate;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    capacity_ah: f64, // Beginning of life
+    charge_ah: f64,
+    series_resistance_ohm: f64,
+    failure: Option<CellFailure>,
+}
+
+impl Cell {
+    pub fn new(capacity_ah: f64, series_resistance_ohm: f64, soc: f64) -> Self {
+        Cell { capacity_ah, charge_ah: capacity_ah * soc.clamp(0.0, 1.0), series_resistance_ohm, failure: None }
+    }
+
+    pub fn get_capacity_ah(&self) -> f64 {
+        self.capacity_ah
+    }
+
+    pub fn get_charge_ah(&self) -> f64 {
+        self.charge_ah
+    }
+
+    pub fn get_series_resistance_ohm(&self) -> f64 {
+        self.series_resistance_ohm
+    }
+
+    pub fn get_failure(&self) -> Option<CellFailure> {
+        self.failure
+    }
+
+    fn is_connected(&self) -> bool {
+        self.failure != Some(CellFailure::Open)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CellGroup {
+    cells: Vec<Cell, MAX_PACK_PARALLEL>,
+    is_bleeding: bool,
+}
+
+impl CellGroup {
+    fn is_shorted(&self) -> bool {
+        self.cells.iter().any(|cell| cell.failure == Some(CellFailure::Short))
+    }
+
+    // A shorted group is a wire: it no longer stores energy but still conducts
+    fn is_active(&self) -> bool {
+        !self.is_shorted()
+    }
+
+    fn is_open(&self) -> bool {
+        !self.is_shorted() && !self.cells.iter().any(Cell::is_connected)
+    }
+
+    fn capacity_ah(&self, health: f64) -> f64 {
+        self.cells.iter().filter(|cell| cell.is_connected()).map(|cell| cell.capacity_ah * health).sum()
+    }
+
+    fn charge_ah(&self) -> f64 {
+        self.cells.iter().filter(|cell| cell.is_connected()).map(|cell| cell.charge_ah).sum()
+    }
+
+    fn soc(&self, health: f64) -> f64 {
+        let capacity_ah = self.capacity_ah(health);
+        if capacity_ah > 0.0 { (self.charge_ah() / capacity_ah).min(1.0) } else { 0.0 }
+    }
+
+    fn open_circuit_voltage_v(&self, health: f64) -> f64 {
+        if self.is_shorted() { 0.0 } else { interpolate(&LI_ION_CELL_OCV_CURVE, self.soc(health)) }
+    }
+
+    fn series_resistance_ohm(&self) -> f64 {
+        if self.is_shorted() {
+            return 0.0;
+        }
+        let conductance: f64 = self.cells.iter().filter(|cell| cell.is_connected()).map(|cell| 1.0 / cell.series_resistance_ohm).sum();
+        if conductance > 0.0 { 1.0 / conductance } else { f64::INFINITY }
+    }
+
+    // Parallel cells equalise, so the charge is shared out by capacity
+    fn set_soc(&mut self, soc: f64, health: f64) {
+        for cell in self.cells.iter_mut().filter(|cell| cell.is_connected()) {
+            cell.charge_ah = cell.capacity_ah * health * soc.clamp(0.0, 1.0);
+        }
+    }
+
+    fn add_charge(&mut self, charge_ah: f64, health: f64) {
+        let capacity_ah = self.capacity_ah(health);
+        if capacity_ah > 0.0 {
+            self.set_soc((self.charge_ah() + charge_ah) / capacity_ah, health);
+        }
+    }
+}
+
+/// Per-cell telemetry, as reported by a pack monitor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellTelemetry {
+    pub group: u8,        // Series position
+    pub position: u8,     // Parallel position within the group
+    pub voltage_v: f64,   // Group terminal voltage at the last current
+    pub soc_percentage: f64,
+    pub capacity_ah: f64, // Including ageing
+    pub failure: Option<CellFailure>,
+    pub is_bleeding: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellPack {
+    groups: Vec<CellGroup, MAX_PACK_SERIES>,
+    health: f64,               // Capacity fade from the battery's ageing model, applied to every cell
+    current_a: f64,            // Last pack current, positive while discharging
+    balancing: bool,
+    balance_threshold_v: f64,
+    bleed_resistance_ohm: f64,
+}
+
+impl CellPack {
+    /// `series` x `parallel` identical cells of `cell_capacity_ah` at `soc`, with balancing enabled.
+    pub fn new(series: usize, parallel: usize, cell_capacity_ah: f64, soc: f64) -> Result<Self, PackError> {
+        if series == 0 || parallel == 0 || series > MAX_PACK_SERIES || parallel > MAX_PACK_PARALLEL {
+            return Err(PackError::TooManyCells);
+        }
+        let mut groups = Vec::new();
+        for _ in 0..series {
+            let mut cells = Vec::new();
+            for _ in 0..parallel {
+                let _ = cells.push(Cell::new(cell_capacity_ah, LI_ION_CELL_R0_OHM, soc)); // Checked above
+            }
+            let _ = groups.push(CellGroup { cells, is_bleeding: false });
+        }
+        Ok(CellPack {
+            groups,
+            health: 1.0,
+            current_a: 0.0,
+            balancing: true,
+            balance_threshold_v: BALANCE_THRESHOLD_V,
+            bleed_resistance_ohm: BALANCE_BLEED_RESISTANCE_OHM,
+        })
+    }
+
+    /// Generic 18650 cells, e.g. `li_ion(2, 4, 0.5)` for a 2S4P pack at half charge.
+    pub fn li_ion(series: usize, parallel: usize, soc: f64) -> Result<Self, PackError> {
+        CellPack::new(series, parallel, LI_ION_CELL_CAPACITY_AH, soc)
+    }
+
+    /// Manufacturing spread: every cell's capacity and resistance are scaled by an independent factor
+    /// drawn uniformly from `1 +/- spread`. The same `seed` always gives the same pack.
+    pub fn with_spread(mut self, capacity_spread: f64, resistance_spread: f64, seed: u64) -> Self {
+        let mut state = seed;
+        let health = self.health;
+        for group in self.groups.iter_mut() {
+            let soc = group.soc(health);
+            for cell in group.cells.iter_mut() {
+                cell.capacity_ah *= 1.0 + capacity_spread * uniform(&mut state);
+                cell.series_resistance_ohm *= 1.0 + resistance_spread * uniform(&mut state);
+            }
+            group.set_soc(soc, health);
+        }
+        self
+    }
+
+    pub fn with_balancing(mut self, threshold_v: f64, bleed_resistance_ohm: f64) -> Self {
+        self.balancing = true;
+        self.balance_threshold_v = threshold_v;
+        self.bleed_resistance_ohm = bleed_resistance_ohm;
+        self
+    }
+
+    pub fn without_balancing(mut self) -> Self {
+        self.balancing = false;
+        self
+    }
+
+    /// Replaces the cell at `group`, `position`.
+    pub fn set_cell(&mut self, group: usize, position: usize, cell: Cell) -> Result<(), PackError> {
+        *self.get_cell_mut(group, position)? = cell;
+        Ok(())
+    }
+
+    /// Sets the SoC of a whole series group, e.g. to start from an unbalanced pack.
+    pub fn set_group_soc(&mut self, group: usize, soc: f64) -> Result<(), PackError> {
+        let health = self.health;
+        self.groups.get_mut(group).ok_or(PackError::CellNotFound)?.set_soc(soc, health);
+        Ok(())
+    }
+
+    /// Fails one cell. A short drains its group on the spot.
+    pub fn fail_cell(&mut self, group: usize, position: usize, failure: CellFailure) -> Result<(), PackError> {
+        self.get_cell_mut(group, position)?.failure = Some(failure);
+        if failure == CellFailure::Short {
+            for cell in self.groups[group].cells.iter_mut() {
+                cell.charge_ah = 0.0;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_series(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn get_parallel(&self) -> usize {
+        self.groups.first().map_or(0, |group| group.cells.len())
+    }
+
+    pub fn get_cell(&self, group: usize, position: usize) -> Option<&Cell> {
+        self.groups.get(group)?.cells.get(position)
+    }
+
+    /// Series groups still holding a voltage.
+    pub fn get_active_series(&self) -> usize {
+        self.groups.iter().filter(|group| group.is_active()).count()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.groups.iter().any(CellGroup::is_open)
+    }
+
+    pub fn has_shorted_cell(&self) -> bool {
+        self.groups.iter().any(CellGroup::is_shorted)
+    }
+
+    pub fn is_balancing(&self) -> bool {
+        self.groups.iter().any(|group| group.is_bleeding)
+    }
+
+    /// Usable capacity at beginning of life: the weakest group times the active series count.
+    pub fn get_capacity_wh(&self) -> f64 {
+        let weakest_ah = self.active_groups().map(|group| group.capacity_ah(1.0)).fold(f64::INFINITY, f64::min);
+        if weakest_ah.is_finite() { weakest_ah * self.get_active_series() as f64 * LI_ION_CELL_NOMINAL_V } else { 0.0 }
+    }
+
+    pub fn get_nominal_voltage_v(&self) -> f64 {
+        self.get_active_series() as f64 * LI_ION_CELL_NOMINAL_V
+    }
+
+    /// SoC of the weakest group: what can still be discharged before its cells reach the cut-off.
+    pub fn get_soc(&self) -> f64 {
+        let min_soc = self.active_groups().map(|group| group.soc(self.health)).fold(f64::INFINITY, f64::min);
+        if min_soc.is_finite() { min_soc } else { 0.0 }
+    }
+
+    /// Charge the weakest group can still deliver, less the share `capacity_factor` holds back at the present temperature.
+    pub fn get_available_charge_ah(&self, capacity_factor: f64) -> f64 {
+        let available_ah = self
+            .active_groups()
+            .map(|group| group.charge_ah() - group.capacity_ah(self.health) * (1.0 - capacity_factor))
+            .fold(f64::INFINITY, f64::min);
+        if available_ah.is_finite() { available_ah.max(0.0) } else { 0.0 }
+    }
+
+    /// SoC of the fullest group, which ends the charge.
+    pub fn get_max_soc(&self) -> f64 {
+        self.active_groups().map(|group| group.soc(self.health)).fold(0.0, f64::max)
+    }
+
+    pub fn get_open_circuit_voltage_v(&self) -> f64 {
+        self.active_groups().map(|group| group.open_circuit_voltage_v(self.health)).sum()
+    }
+
+    /// Resistance of the groups that still conduct; an open group carries no current at all.
+    pub fn get_series_resistance_ohm(&self) -> f64 {
+        self.groups.iter().filter(|group| !group.is_open()).map(CellGroup::series_resistance_ohm).sum()
+    }
+
+    pub fn get_min_cell_voltage_v(&self) -> f64 {
+        self.conducting_groups().map(|group| self.group_voltage_v(group)).fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn get_max_cell_voltage_v(&self) -> f64 {
+        self.conducting_groups().map(|group| self.group_voltage_v(group)).fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Pack-level circuit for the active series groups, built from the actual cell resistances.
+    pub fn get_equivalent_circuit(&self) -> EquivalentCircuit {
+        let series = self.get_active_series().max(1) as f64;
+        let parallel = self.get_parallel().max(1) as f64;
+        let mut curve: Vec<(f64, f64), MAX_OCV_POINTS> = Vec::new();
+        for (soc, cell_v) in LI_ION_CELL_OCV_CURVE.iter() {
+            let _ = curve.push((*soc, cell_v * series));
+        }
+        EquivalentCircuit::new(
+            &curve,
+            self.get_series_resistance_ohm(),
+            LI_ION_CELL_R1_OHM * series / parallel,
+            LI_ION_CELL_C1_F * parallel / series,
+            LI_ION_CELL_MIN_V * series,
+            LI_ION_CELL_MAX_V * series,
+        )
+    }
+
+    pub fn get_cell_telemetry(&self) -> Vec<CellTelemetry, { MAX_PACK_SERIES * MAX_PACK_PARALLEL }> {
+        let mut telemetry = Vec::new();
+        for (group_index, group) in self.groups.iter().enumerate() {
+            let voltage_v = if group.is_open() { 0.0 } else { self.group_voltage_v(group) };
+            for (position, cell) in group.cells.iter().enumerate() {
+                let capacity_ah = cell.capacity_ah * self.health;
+                let _ = telemetry.push(CellTelemetry {
+                    group: group_index as u8,
+                    position: position as u8,
+                    voltage_v: if cell.failure.is_some() { 0.0 } else { voltage_v },
+                    soc_percentage: if capacity_ah > 0.0 { 100.0 * cell.charge_ah / capacity_ah } else { 0.0 },
+                    capacity_ah,
+                    failure: cell.failure,
+                    is_bleeding: group.is_bleeding,
+                }); // At most MAX_PACK_SERIES * MAX_PACK_PARALLEL cells
+            }
+        }
+        telemetry
+    }
+
+    /// Largest charging current before the fullest group reaches its voltage limit or fills up over the step.
+    pub fn get_max_charge_current_a(&self, duration_h: f64, charge_efficiency: f64) -> f64 {
+        self.active_groups()
+            .map(|group| {
+                let voltage_limit_a = (LI_ION_CELL_MAX_V - group.open_circuit_voltage_v(self.health)) / group.series_resistance_ohm();
+                let headroom_a = (group.capacity_ah(self.health) - group.charge_ah()) / (charge_efficiency * duration_h);
+                voltage_limit_a.min(headroom_a)
+            })
+            .fold(f64::INFINITY, f64::min)
+            .max(0.0)
+    }
+
+    /// Largest discharging current before the weakest group reaches its cut-off or runs empty over the step.
+    /// `capacity_factor` is the fraction of capacity available at the present temperature.
+    pub fn get_max_discharge_current_a(&self, duration_h: f64, discharge_efficiency: f64, capacity_factor: f64) -> f64 {
+        self.active_groups()
+            .map(|group| {
+                let voltage_limit_a = (group.open_circuit_voltage_v(self.health) - LI_ION_CELL_MIN_V) / group.series_resistance_ohm();
+                let reserve_ah = group.capacity_ah(self.health) * (1.0 - capacity_factor);
+                let available_a = (group.charge_ah() - reserve_ah) * discharge_efficiency / duration_h;
+                voltage_limit_a.min(available_a)
+            })
+            .fold(f64::INFINITY, f64::min)
+            .max(0.0)
+    }
+
+    /// Passes `current_a` (positive while discharging) through every series group for the step.
+    pub(crate) fn apply_current(&mut self, current_a: f64, duration_h: f64, charge_efficiency: f64, discharge_efficiency: f64) {
+        let charge_ah = if current_a < 0.0 { -current_a * duration_h * charge_efficiency } else { -current_a * duration_h / discharge_efficiency };
+        let health = self.health;
+        for group in self.groups.iter_mut().filter(|group| group.is_active()) {
+            group.add_charge(charge_ah, health);
+        }
+        self.current_a = current_a;
+    }
+
+    /// While the pack is charging, bleeds every group sitting more than the threshold above the lowest one.
+    /// A resting or discharging pack is left alone. Returns the heat dissipated.
+    pub(crate) fn balance(&mut self, duration_h: f64) -> f64 {
+        let health = self.health;
+        let charging = self.current_a < 0.0;
+        let lowest_v = self.active_groups().map(|group| group.open_circuit_voltage_v(health)).fold(f64::INFINITY, f64::min);
+        let mut heat_w = 0.0;
+        for group in self.groups.iter_mut() {
+            let voltage_v = group.open_circuit_voltage_v(health);
+            group.is_bleeding = self.balancing && charging && group.is_active() && !group.is_open() && voltage_v - lowest_v > self.balance_threshold_v;
+            if group.is_bleeding {
+                let bleed_a = voltage_v / self.bleed_resistance_ohm;
+                group.add_charge(-bleed_a * duration_h, health);
+                heat_w += voltage_v * bleed_a;
+            }
+        }
+        heat_w
+    }
+
+    /// Applies the battery's capacity fade to every cell.
+    pub(crate) fn set_health(&mut self, health: f64) {
+        self.health = health;
+        for cell in self.groups.iter_mut().flat_map(|group| group.cells.iter_mut()) {
+            cell.charge_ah = cell.charge_ah.min(cell.capacity_ah * health);
+        }
+    }
+
+    fn active_groups(&self) -> impl Iterator<Item = &CellGroup> {
+        self.groups.iter().filter(|group| group.is_active())
+    }
+
+    fn conducting_groups(&self) -> impl Iterator<Item = &CellGroup> {
+        self.active_groups().filter(|group| !group.is_open())
+    }
+
+    fn group_voltage_v(&self, group: &CellGroup) -> f64 {
+        group.open_circuit_voltage_v(self.health) - self.current_a * group.series_resistance_ohm()
+    }
+
+    fn get_cell_mut(&mut self, group: usize, position: usize) -> Result<&mut Cell, PackError> {
+        self.groups.get_mut(group).and_then(|group| group.cells.get_mut(position)).ok_or(PackError::CellNotFound)
+    }
+}
+
+// xorshift64* over a Weyl sequence, mapped to [-1, 1]
+fn uniform(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut x = *state;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    let bits = x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+    2.0 * (bits as f64 / (1u64 << 53) as f64) - 1.0
+}
@@ -0,0 +1,249 @@
+use crate::enums::{LoadSetting, SatelliteOperationalMode};
+use crate::load_id::LoadId;
+
+// Average solar flux in LEO (Watts per square meter):
+// value taken from NASA's On-Orbit_Thermal_Environments_TFAWS_2014.pdf
+pub const SOLAR_FLUX_LEO_AVG_W_M2: f64 = 1367.0;
+
+// Fixed capacities replacing the heap-allocated collections of the prototype
+pub const MAX_SOLAR_PANELS: usize = 8;
+pub const MAX_LOADS: usize = 16;
+pub const MAX_SCHEDULED_SWITCHES: usize = 32;
+pub const ID_MAX_LEN: usize = 16;
+
+// Earth parameters for the circular orbit model (WGS-84 equatorial radius, EGM-96 gravitational parameter)
+pub const EARTH_RADIUS_KM: f64 = 6378.137;
+pub const EARTH_MU_KM3_S2: f64 = 398600.4418;
+pub const EARTH_OBLIQUITY_DEG: f64 = 23.44;
+
+// Body-mounted array layout: 30.18 cm^2 triple-junction cells (e.g. AzurSpace 3G30),
+// two per 10x10 cm face and seven along a 10x30 cm face of a 3U
+pub const SOLAR_CELL_AREA_M2: f64 = 0.003018;
+pub const BODY_CELLS_PER_1U_FACE: usize = 2;
+pub const BODY_CELLS_PER_3U_LONG_FACE: usize = 7;
+
+// Generic Li-ion (NMC, 18650 class) cell used by the default equivalent-circuit battery model
+pub const MAX_OCV_POINTS: usize = 16;
+pub const LI_ION_CELL_NOMINAL_V: f64 = 3.7;
+pub const LI_ION_CELL_MIN_V: f64 = 3.0;
+pub const LI_ION_CELL_MAX_V: f64 = 4.2;
+pub const LI_ION_CELL_R0_OHM: f64 = 0.04;
+pub const LI_ION_CELL_R1_OHM: f64 = 0.02;
+pub const LI_ION_CELL_C1_F: f64 = 2500.0;
+pub const VOLTAGE_LIMIT_TOLERANCE_V: f64 = 1e-6;
+pub const LI_ION_CELL_OCV_CURVE: [(f64, f64); 12] = [
+    (0.00, 3.00),
+    (0.05, 3.30),
+    (0.10, 3.45),
+    (0.20, 3.55),
+    (0.30, 3.62),
+    (0.40, 3.68),
+    (0.50, 3.74),
+    (0.60, 3.80),
+    (0.70, 3.88),
+    (0.80, 3.96),
+    (0.90, 4.06),
+    (1.00, 4.20),
+];
+
+// Lumped battery thermal model defaults: ~0.2 kg of 18650 cells coupled to the structure
+pub const BATTERY_HEAT_CAPACITY_J_K: f64 = 200.0;
+pub const BATTERY_THERMAL_RESISTANCE_K_W: f64 = 5.0;
+pub const BATTERY_INITIAL_TEMPERATURE_C: f64 = 20.0;
+pub const BATTERY_ENVIRONMENT_SUNLIT_C: f64 = 15.0;
+pub const BATTERY_ENVIRONMENT_ECLIPSE_C: f64 = -5.0;
+pub const LI_ION_CHARGE_INHIBIT_BELOW_C: f64 = 0.0;
+pub const LI_ION_REFERENCE_TEMPERATURE_C: f64 = 25.0;
+pub const LI_ION_RESISTANCE_TEMPERATURE_COEFF_PER_C: f64 = 0.025; // R roughly doubles every 28 degC colder
+pub const LI_ION_CAPACITY_TEMPERATURE_CURVE: [(f64, f64); 6] = [
+    (-20.0, 0.60),
+    (-10.0, 0.75),
+    (0.0, 0.88),
+    (10.0, 0.96),
+    (20.0, 1.00),
+    (45.0, 1.00),
+];
+
+// PDU load whose power heats the battery
+pub const BATTERY_HEATER_LOAD_ID: LoadId = LoadId::HEATERS;
+
+// Li-ion ageing defaults: 80% capacity after 1000 full-depth cycles, much longer life at shallow
+// depth of discharge, and ~2% calendar fade after the first year at 25 degC
+pub const MAX_RAINFLOW_REVERSALS: usize = 32;
+pub const RAINFLOW_SOC_HYSTERESIS: f64 = 0.005;
+pub const CYCLES_TO_EOL_AT_FULL_DOD: f64 = 1000.0;
+pub const DOD_LIFE_EXPONENT: f64 = 1.8;
+pub const BATTERY_EOL_CAPACITY_FADE: f64 = 0.2;
+pub const CALENDAR_FADE_PER_SQRT_YEAR_25C: f64 = 0.02;
+pub const CALENDAR_FADE_DOUBLING_C: f64 = 10.0;
+pub const HOURS_PER_YEAR: f64 = 8766.0;
+pub const SEVERE_DEGRADATION_HEALTH: f64 = 0.2;
+
+// Battery fault detection defaults
+pub const MAX_LATCHED_FAULTS: usize = 9; // One per BatteryFault variant
+pub const LI_ION_OVER_TEMPERATURE_C: f64 = 60.0;
+pub const LI_ION_MIN_DISCHARGE_TEMPERATURE_C: f64 = -20.0;
+pub const OVER_VOLTAGE_MARGIN: f64 = 1.02;  // Fault above 2% beyond the end-of-charge voltage
+pub const OVER_CURRENT_MARGIN: f64 = 1.5;   // Fault above 150% of the rated current
+
+// Single-diode solar cell model. Space cells are rated at AM0 and 28 degC
+pub const SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2: f64 = SOLAR_FLUX_LEO_AVG_W_M2;
+pub const SOLAR_CELL_REFERENCE_TEMPERATURE_C: f64 = 28.0;
+pub const BOLTZMANN_J_K: f64 = 1.380649e-23;
+pub const ELEMENTARY_CHARGE_C: f64 = 1.602176634e-19;
+pub const ZERO_CELSIUS_K: f64 = 273.15;
+
+// Solar cell radiation damage. 1 MeV electron equivalent fluence per year behind 150 um coverglass for
+// circular LEO orbits at 50-98 deg inclination, trapped protons included, as log10(e/cm^2) by altitude.
+// Order of magnitude from published AE8/AP8 runs; a design review should use the mission's own analysis.
+pub const FLUENCE_RATE_BY_ALTITUDE: [(f64, f64); 8] = [
+    (300.0, 11.0),
+    (400.0, 11.8),
+    (500.0, 12.4),
+    (600.0, 12.9),
+    (800.0, 13.6),
+    (1000.0, 14.1),
+    (1400.0, 14.7),
+    (2000.0, 15.0),
+];
+
+// Remaining factors as a function of log10(1 MeV fluence in e/cm^2), after AzurSpace 3G30 and
+// high-efficiency space silicon datasheets
+pub const TJ_GAAS_ISC_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 1.0), (14.0, 1.0), (14.7, 0.99), (15.0, 0.98), (15.5, 0.95), (16.0, 0.90)];
+pub const TJ_GAAS_VOC_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 0.99), (14.0, 0.96), (14.7, 0.93), (15.0, 0.91), (15.5, 0.88), (16.0, 0.84)];
+pub const TJ_GAAS_PMAX_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 0.99), (14.0, 0.95), (14.7, 0.90), (15.0, 0.87), (15.5, 0.81), (16.0, 0.74)];
+pub const SILICON_ISC_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 0.98), (14.0, 0.93), (14.7, 0.88), (15.0, 0.85), (15.5, 0.80), (16.0, 0.74)];
+pub const SILICON_VOC_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 0.97), (14.0, 0.92), (14.7, 0.89), (15.0, 0.87), (15.5, 0.84), (16.0, 0.81)];
+pub const SILICON_PMAX_REMAINING: [(f64, f64); 7] =
+    [(12.0, 1.0), (13.0, 0.95), (14.0, 0.85), (14.7, 0.78), (15.0, 0.74), (15.5, 0.67), (16.0, 0.60)];
+
+// Charge regulators
+pub const DET_BLOCKING_DIODE_DROP_V: f64 = 0.3;   // Schottky diode between array and bus
+pub const MPPT_CONVERTER_EFFICIENCY: f64 = 0.95;
+pub const MPPT_ITERATIONS_PER_STEP: usize = 25;   // Tracker updates between two simulation steps
+pub const MPPT_PERTURBATION_FRACTION: f64 = 0.005; // Voltage perturbation as a fraction of Voc
+pub const MPPT_INITIAL_VOC_FRACTION: f64 = 0.8;    // Fractional-Voc starting point
+
+// Latching current limiter defaults. Limits are sized from the load's nominal power at the sizing voltage
+pub const LCL_SIZING_BUS_VOLTAGE_V: f64 = 7.4;
+pub const LCL_DEFAULT_LIMIT_FACTOR: f64 = 2.0;
+pub const LCL_DEFAULT_TRIP_TIME_S: f64 = 0.01;
+pub const LCL_DEFAULT_RETRY_DELAY_S: f64 = 1.0;
+pub const LCL_DEFAULT_MAX_RETRIES: u32 = 3;
+
+// Power conditioning rails. Rated powers follow a GomSpace P31u (3.3 V at 5 A, 5 V at 4 A) with a
+// P60-style 12 V boost; efficiency against the fraction of rated power, poor at light load
+pub const MAX_RAILS: usize = 4;
+pub const MAX_EFFICIENCY_POINTS: usize = 8;
+pub const RAIL_3V3_RATED_POWER_W: f64 = 16.5;
+pub const RAIL_5V_RATED_POWER_W: f64 = 20.0;
+pub const RAIL_12V_RATED_POWER_W: f64 = 18.0;
+pub const BUCK_EFFICIENCY_CURVE: [(f64, f64); 6] = [
+    (0.01, 0.60),
+    (0.05, 0.80),
+    (0.10, 0.87),
+    (0.30, 0.93),
+    (0.60, 0.94),
+    (1.00, 0.91),
+];
+pub const BOOST_EFFICIENCY_CURVE: [(f64, f64); 6] = [
+    (0.01, 0.55),
+    (0.05, 0.75),
+    (0.10, 0.83),
+    (0.30, 0.90),
+    (0.60, 0.91),
+    (1.00, 0.88),
+];
+
+// Load shedding: tiers are shed as SoC falls and restored above a higher threshold
+pub const MAX_SHED_TIERS: usize = 4;
+pub const DEFAULT_SHED_TIERS: [(u8, f64, f64); 3] = [
+    // (priority, shed below SoC %, restore above SoC %)
+    (0, 30.0, 40.0),
+    (1, 20.0, 30.0),
+    (2, 10.0, 20.0),
+];
+
+// Autonomous mode management
+pub const MAX_MODE_TRANSITIONS: usize = 32;           // Oldest entries are dropped from the log
+pub const MODE_MIN_DWELL_S: f64 = 300.0;
+pub const SAFE_MODE_ENTRY_SOC: f64 = 15.0;
+pub const SAFE_MODE_EXIT_SOC: f64 = 40.0;
+pub const SAFE_MODE_ENTRY_BUS_VOLTAGE_MARGIN: f64 = 1.1; // Above the battery under-voltage limit
+pub const SAFE_MODE_RECOVERY_TIME_S: f64 = 5400.0;   // About one LEO orbit of positive energy balance
+pub const PAYLOAD_EXIT_SOC: f64 = 50.0;
+pub const MAX_STEP_MODE_TRANSITIONS: usize = 4;      // Reported per step, including commands since the last one
+
+// Adaptive stepping: sub-steps are bisected until a crossing is located within the resolution
+pub const ADAPTIVE_MAX_STEP_S: f64 = 60.0;
+pub const ADAPTIVE_RESOLUTION_S: f64 = 1.0;
+pub const ADAPTIVE_MIN_STEP_S: f64 = 1e-3; // Floor for both limits: a zero step would never reach the end
+pub const MAX_WATCHED_THRESHOLDS: usize = 16;
+pub const MAX_STEP_CROSSINGS: usize = 8;
+
+// Deployable panels: CubeSat Design Specification hold-off after separation, then one burn-wire release
+// at a time with a cool-down before each retry
+pub const MAX_DEPLOYABLES: usize = 4;
+pub const MAX_STEP_BURNS: usize = 8;
+pub const DEPLOYMENT_HOLD_OFF_S: f64 = 1800.0;
+pub const BURN_WIRE_DURATION_S: f64 = 20.0;
+pub const BURN_RETRY_INTERVAL_S: f64 = 300.0;
+pub const MAX_BURN_ATTEMPTS: u8 = 3;
+pub const BURN_POWERED_TOLERANCE_S: f64 = 1e-6; // Rounding in the powered time summed over the steps
+
+// Launch: CubeSat Design Specification inhibits hold the EPS dark in the deployer, then a staged power-up
+// after separation with the transmitters silent for the first 30 minutes
+pub const MAX_SEPARATION_SWITCHES: usize = 4;
+pub const DEFAULT_SEPARATION_SWITCHES: usize = 3;
+pub const MAX_BOOT_STAGES: usize = 8;
+pub const MAX_TRANSMITTERS: usize = 4;
+pub const RF_SILENCE_S: f64 = 1800.0;
+pub const DEFAULT_BOOT_STAGES: [(LoadId, f64); 3] = [
+    // (load, delay after separation in s); transmitters wait for the RF silence regardless
+    (LoadId::OBC, 0.0),
+    (LoadId::COM_RX, 60.0),
+    (LoadId::COM_TX, RF_SILENCE_S), // Beacon
+];
+pub const DEFAULT_TRANSMITTERS: [LoadId; 2] = [LoadId::COM_TX, LoadId::PAYLOAD_TX];
+
+// Fault injection: pending faults, the injections kept in the log and the responses kept per injection
+pub const MAX_SCHEDULED_FAULTS: usize = 16;
+pub const MAX_INJECTIONS: usize = 16;        // Later injections still apply but are not logged
+pub const MAX_FAULT_RESPONSES: usize = 16;   // The earliest responses are kept
+pub const MAX_STEP_INJECTIONS: usize = 4;    // Reported per step
+
+// Mode-to-load configuration applied on top of the baseline (critical loads on, the rest off).
+// Rules naming loads the PDU does not have are kept for this default table and act once the load is added.
+pub const DEFAULT_MODE_LOAD_RULES: [(SatelliteOperationalMode, LoadId, LoadSetting); 8] = [
+    (SatelliteOperationalMode::PayloadOperation, LoadId::PAYLOAD_CAM, LoadSetting::On),
+    (SatelliteOperationalMode::PayloadOperation, LoadId::PAYLOAD_TX, LoadSetting::On),
+    (SatelliteOperationalMode::NominalSunlit, LoadId::COM_RX, LoadSetting::On),
+    (SatelliteOperationalMode::NominalSunlit, LoadId::COM_TX, LoadSetting::Off),
+    (SatelliteOperationalMode::NominalSunlit, LoadId::HEATERS, LoadSetting::On),
+    (SatelliteOperationalMode::NominalEclipse, LoadId::COM_RX, LoadSetting::On),
+    (SatelliteOperationalMode::NominalEclipse, LoadId::COM_TX, LoadSetting::Off),
+    (SatelliteOperationalMode::NominalEclipse, LoadId::HEATERS, LoadSetting::On),
+];
+
+// Names of the standard load IDs, for the ground
+pub const STANDARD_LOAD_NAMES: [(LoadId, &str); 7] = [
+    (LoadId::OBC, "OBC"),
+    (LoadId::COM_RX, "COM_RX"),
+    (LoadId::COM_TX, "COM_TX"),
+    (LoadId::PAYLOAD_CAM, "PayloadCam"),
+    (LoadId::PAYLOAD_TX, "PayloadTx"),
+    (LoadId::HEATERS, "Heaters"),
+    (LoadId::ADCS, "ADCS"),
+];
+
+// SxP cell packs: 18650 class cells, passive balancing through a bleed resistor per series group
+pub const MAX_PACK_SERIES: usize = 8;
+pub const MAX_PACK_PARALLEL: usize = 8;
+pub const LI_ION_CELL_CAPACITY_AH: f64 = 2.6;
+pub const BALANCE_THRESHOLD_V: f64 = 0.01;         // Groups this far above the lowest one are bled
+pub const BALANCE_BLEED_RESISTANCE_OHM: f64 = 33.0; // About 0.12 A at 4 V
@@ -0,0 +1,258 @@
+// Deployable panel release. After the post-separation hold-off the sequencer fires one burn-wire heater
+// at a time through the PDU, so the burns show up in the power budget like any other load. Burns run for
+// their full duration; the deployment switch is read once a burn has ended, and an open switch means
+// another attempt after a cool-down until the attempts are used up. A burn whose wire was not powered
+// throughout, e.g. behind a tripped current limiter, is not an attempt: it is repeated after the cool-down.
+use heapless::Vec;
+
+use crate::consts::{BURN_POWERED_TOLERANCE_S, BURN_RETRY_INTERVAL_S, BURN_WIRE_DURATION_S, DEPLOYMENT_HOLD_OFF_S, MAX_BURN_ATTEMPTS, MAX_DEPLOYABLES, MAX_STEP_BURNS};
+use crate::enums::{DeploymentError, DeploymentFailure, DeploymentPhase};
+use crate::load_id::LoadId;
+use crate::pdu::PowerDistributionUnit;
+use crate::solar_panel::SolarPanel;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeploymentChannel {
+    panel_index: usize, // Into the EPS solar panels
+    burn_wire: LoadId,
+    phase: DeploymentPhase,
+    attempts: u8,
+    switch_closed: bool,
+    finished_s: f64, // When the last burn ended, once deployed or failed
+    burned_s: f64,   // Time the burn wire has been powered during the current burn
+    failure: Option<DeploymentFailure>,
+}
+
+impl DeploymentChannel {
+    pub fn get_panel_index(&self) -> usize {
+        self.panel_index
+    }
+
+    pub fn get_burn_wire(&self) -> LoadId {
+        self.burn_wire
+    }
+
+    pub fn get_phase(&self) -> DeploymentPhase {
+        self.phase
+    }
+
+    pub fn get_attempts(&self) -> u8 {
+        self.attempts
+    }
+
+    /// Deployment switch feedback: closed once the hinge has latched at its stop.
+    pub fn is_switch_closed(&self) -> bool {
+        self.switch_closed
+    }
+
+    pub fn get_failure(&self) -> Option<DeploymentFailure> {
+        self.failure
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self.phase, DeploymentPhase::Deployed | DeploymentPhase::Failed)
+    }
+}
+
+/// One burn whose switch reading came in during a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurnAttempt {
+    pub panel_index: usize,
+    pub attempt: u8, // Counting from 1
+    pub start_s: f64,
+    pub switch_closed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeploymentSequencer {
+    separation_time_s: Option<f64>, // None until armed
+    hold_off_s: f64,
+    burn_s: f64,
+    retry_interval_s: f64,
+    max_attempts: u8,
+    channels: Vec<DeploymentChannel, MAX_DEPLOYABLES>,
+}
+
+impl Default for DeploymentSequencer {
+    fn default() -> Self {
+        DeploymentSequencer::new(DEPLOYMENT_HOLD_OFF_S, BURN_WIRE_DURATION_S, BURN_RETRY_INTERVAL_S, MAX_BURN_ATTEMPTS)
+    }
+}
+
+impl DeploymentSequencer {
+    /// Armed at mission time zero, taken as the separation from the deployer.
+    pub fn new(hold_off_s: f64, burn_s: f64, retry_interval_s: f64, max_attempts: u8) -> Self {
+        DeploymentSequencer {
+            separation_time_s: Some(0.0),
+            hold_off_s,
+            burn_s,
+            retry_interval_s,
+            max_attempts: max_attempts.max(1),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Adds a panel released by the heater on `burn_wire`. Panels are released in the order added.
+    pub fn add_panel(&mut self, panel_index: usize, burn_wire: LoadId) -> Result<(), DeploymentError> {
+        if self.get_channel(panel_index).is_some() {
+            return Err(DeploymentError::DuplicatePanel);
+        }
+        let channel = DeploymentChannel {
+            panel_index,
+            burn_wire,
+            phase: DeploymentPhase::Stowed,
+            attempts: 0,
+            switch_closed: false,
+            finished_s: 0.0,
+            burned_s: 0.0,
+            failure: None,
+        };
+        self.channels.push(channel).map_err(|_| DeploymentError::TooManyPanels)
+    }
+
+    /// Restarts the hold-off timer from `separation_time_s`. Burns already under way are not affected.
+    pub fn arm(&mut self, separation_time_s: f64) {
+        self.separation_time_s = Some(separation_time_s);
+    }
+
+    pub fn get_separation_time_s(&self) -> Option<f64> {
+        self.separation_time_s
+    }
+
+    pub fn get_hold_off_s(&self) -> f64 {
+        self.hold_off_s
+    }
+
+    pub fn get_channels(&self) -> &[DeploymentChannel] {
+        &self.channels
+    }
+
+    pub fn get_channel(&self, panel_index: usize) -> Option<&DeploymentChannel> {
+        self.channels.iter().find(|channel| channel.panel_index == panel_index)
+    }
+
+    /// True once every panel is deployed or has failed.
+    pub fn is_complete(&self) -> bool {
+        self.channels.iter().all(DeploymentChannel::is_finished)
+    }
+
+    pub fn inject_failure(&mut self, panel_index: usize, failure: DeploymentFailure) -> Result<(), DeploymentError> {
+        let channel = self.channels.iter_mut().find(|channel| channel.panel_index == panel_index).ok_or(DeploymentError::UnknownPanel)?;
+        channel.failure = Some(failure);
+        Ok(())
+    }
+
+    pub fn clear_failure(&mut self, panel_index: usize) -> Result<(), DeploymentError> {
+        let channel = self.channels.iter_mut().find(|channel| channel.panel_index == panel_index).ok_or(DeploymentError::UnknownPanel)?;
+        channel.failure = None;
+        Ok(())
+    }
+
+    // Every panel and burn wire must exist
+    pub(crate) fn validate(&self, panels: &[SolarPanel], pdu: &PowerDistributionUnit) -> Result<(), DeploymentError> {
+        for channel in self.channels.iter() {
+            if channel.panel_index >= panels.len() {
+                return Err(DeploymentError::UnknownPanel);
+            }
+            if pdu.get_load(channel.burn_wire).is_none() {
+                return Err(DeploymentError::UnknownLoad);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the switches of the burns that ended by `start_s` and schedules the burns starting before
+    /// `end_s` on the PDU, so a burn draws power from its exact start within the step.
+    pub(crate) fn update(
+        &mut self,
+        start_s: f64,
+        end_s: f64,
+        pdu: &mut PowerDistributionUnit,
+        panels: &mut [SolarPanel],
+    ) -> Vec<BurnAttempt, MAX_STEP_BURNS> {
+        let mut attempts = Vec::new();
+        let Some(separation_time_s) = self.separation_time_s else {
+            return attempts;
+        };
+        let mut ready_s = separation_time_s + self.hold_off_s;
+        for channel in self.channels.iter_mut() {
+            loop {
+                match channel.phase {
+                    DeploymentPhase::Stowed | DeploymentPhase::Retrying { .. } => {
+                        let next_burn_s = match channel.phase {
+                            DeploymentPhase::Retrying { next_burn_s } => next_burn_s,
+                            _ => ready_s,
+                        }
+                        .max(start_s);
+                        // A full schedule leaves the burn for a later step
+                        if next_burn_s >= end_s || pdu.schedule_window(channel.burn_wire, next_burn_s, next_burn_s + self.burn_s).is_err() {
+                            return attempts;
+                        }
+                        channel.phase = DeploymentPhase::Burning { start_s: next_burn_s };
+                        channel.burned_s = 0.0;
+                        set_burning(pdu, channel.burn_wire, true);
+                    }
+                    DeploymentPhase::Burning { start_s: burn_start_s } => {
+                        let burn_end_s = burn_start_s + self.burn_s;
+                        if burn_end_s > start_s {
+                            return attempts;
+                        }
+                        set_burning(pdu, channel.burn_wire, false);
+                        if channel.burned_s < self.burn_s - BURN_POWERED_TOLERANCE_S {
+                            channel.phase = DeploymentPhase::Retrying { next_burn_s: burn_end_s + self.retry_interval_s };
+                            continue;
+                        }
+                        channel.attempts = channel.attempts.saturating_add(1);
+                        let released = match channel.failure {
+                            Some(DeploymentFailure::Stuck) => false,
+                            Some(DeploymentFailure::ReleasesOnAttempt(attempt)) => channel.attempts >= attempt,
+                            _ => true,
+                        };
+                        if released && let Some(panel) = panels.get_mut(channel.panel_index) {
+                            match channel.failure {
+                                Some(DeploymentFailure::Partial(fraction)) => panel.deploy_partially(fraction),
+                                Some(DeploymentFailure::SwitchStuckOpen) => panel.deploy(),
+                                _ => {
+                                    panel.deploy();
+                                    channel.switch_closed = true;
+                                }
+                            }
+                        }
+                        let _ = attempts.push(BurnAttempt {
+                            panel_index: channel.panel_index,
+                            attempt: channel.attempts,
+                            start_s: burn_start_s,
+                            switch_closed: channel.switch_closed,
+                        });
+                        channel.phase = if channel.switch_closed {
+                            DeploymentPhase::Deployed
+                        } else if channel.attempts >= self.max_attempts {
+                            DeploymentPhase::Failed
+                        } else {
+                            DeploymentPhase::Retrying { next_burn_s: burn_end_s + self.retry_interval_s }
+                        };
+                        channel.finished_s = burn_end_s;
+                    }
+                    DeploymentPhase::Deployed | DeploymentPhase::Failed => {
+                        ready_s = ready_s.max(channel.finished_s); // The next panel waits for this one
+                        break;
+                    }
+                }
+            }
+        }
+        attempts
+    }
+
+    /// Adds the time each burning wire is powered over the step about to run, `duration_s` from the PDU's time.
+    pub(crate) fn record_burn_power(&mut self, pdu: &PowerDistributionUnit, duration_s: f64) {
+        for channel in self.channels.iter_mut().filter(|channel| matches!(channel.phase, DeploymentPhase::Burning { .. })) {
+            channel.burned_s += pdu.get_load_powered_s(channel.burn_wire, duration_s).unwrap_or(0.0);
+        }
+    }
+}
+
+fn set_burning(pdu: &mut PowerDistributionUnit, burn_wire: LoadId, burning: bool) {
+    if let Some(load) = pdu.loads.iter_mut().find(|load| load.get_id() == burn_wire) {
+        load.burning = burning;
+    }
+}
@@ -0,0 +1,301 @@
+// Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars
+use crate::load_id::LoadId;
+
+// Concurrent faults are tracked by the battery's fault log; `Fault` reports the one blocking the last operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    Idle,
+    Full,
+    Empty,
+    Fault(BatteryFault), // Page 25, Section 3.4
+}
+
+// Page 25, Section 3.4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryFault {
+    OverTemperature,
+    UnderTemperature, // Discharged below the minimum discharge temperature
+    OverVoltage,
+    UnderVoltage,
+    OverCurrent,
+    SevereDegradation,
+    CellShort,
+    OpenCircuit, // No path through the pack, e.g. every cell of a series group failed open
+    ChargeUnderTemperature, // Charge offered below the charge inhibit temperature; clears itself once the cells warm
+}
+
+impl BatteryFault {
+    /// Cleared by the battery once its condition has gone; every other fault stays latched until commanded.
+    pub fn clears_itself(&self) -> bool {
+        matches!(self, BatteryFault::ChargeUnderTemperature)
+    }
+
+    pub fn blocks_charge(&self) -> bool {
+        !matches!(self, BatteryFault::UnderVoltage | BatteryFault::SevereDegradation)
+    }
+
+    pub fn blocks_discharge(&self) -> bool {
+        matches!(
+            self,
+            BatteryFault::UnderTemperature
+                | BatteryFault::UnderVoltage
+                | BatteryFault::OverCurrent
+                | BatteryFault::CellShort
+                | BatteryFault::OpenCircuit
+        )
+    }
+}
+
+// Page 12, Section 1.3
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SatelliteOperationalMode {
+    NominalSunlit,
+    NominalEclipse,
+    SafeMode,
+    PayloadOperation,
+}
+
+impl SatelliteOperationalMode {
+    pub const ALL: [SatelliteOperationalMode; 4] = [
+        SatelliteOperationalMode::NominalSunlit,
+        SatelliteOperationalMode::NominalEclipse,
+        SatelliteOperationalMode::SafeMode,
+        SatelliteOperationalMode::PayloadOperation,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// What a mode does to one load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadSetting {
+    On,
+    Off,
+    DontCare, // Left as it is
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModeConfigError {
+    UnknownLoad { rule: usize },      // Index of the rule naming a load the PDU does not have
+    ConflictingRule { rule: usize },  // Index of a rule contradicting an earlier one
+}
+
+// What a latching current limiter does after tripping
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LclRecovery {
+    AutoRetry { retry_delay_s: f64, max_retries: u32 }, // Re-close after a delay, then latch off
+    LatchOff,                                           // Stay open until commanded back on
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SheddingAction {
+    Shed,
+    Restore,
+}
+
+// Why a shedding tier was switched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SheddingReason {
+    SocBelowShedThreshold,
+    SocAboveRestoreThreshold,
+    DeficitNotCovered, // The battery could not supply the power deficit
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SheddingError {
+    TooManyTiers,
+    NoHysteresis, // Restore threshold not above the shed threshold
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PduError {
+    LoadNotFound,
+    TooManyLoads,
+    DuplicateLoad,
+    ScheduleFull,
+    InvalidWindow, // Ends before it starts
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellFailure {
+    Open,  // The cell drops out of its parallel group
+    Short, // Drains its group and takes it out of the series string
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackError {
+    TooManyCells,
+    CellNotFound,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    DuplicateId,
+    DuplicateName,
+    Full,
+}
+
+// Standard CubeSat sizes used by the body-mounted solar panel presets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CubeSatFormFactor {
+    OneU,
+    ThreeU,
+}
+
+// Solar cell technologies with radiation remaining-factor curves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellTechnology {
+    TripleJunctionGaAs,
+    Silicon,
+}
+
+// Why the operational mode changed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionReason {
+    GroundCommand,
+    EnteredEclipse,
+    LeftEclipse,
+    LowSoc,
+    LowBusVoltage,
+    BatteryFault,
+    BatteryEmpty,
+    PayloadLowSoc,
+    SafeModeRecovered, // Sustained positive energy balance with enough SoC
+}
+
+// How the solar array is connected to the battery bus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegulatorKind {
+    DirectEnergyTransfer, // Array clamped to the battery voltage through a blocking diode
+    Mppt(MpptAlgorithm),  // DC-DC converter tracking the maximum power point
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MpptAlgorithm {
+    PerturbAndObserve,
+    IncrementalConductance,
+}
+
+// Output rails of the power conditioning module; `Raw` is the unregulated battery bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerRail {
+    #[default]
+    Raw,
+    V3_3,
+    V5,
+    V12,
+}
+
+impl PowerRail {
+    /// Regulated output voltage, `None` for the battery bus.
+    pub fn get_nominal_voltage_v(&self) -> Option<f64> {
+        match self {
+            PowerRail::Raw => None,
+            PowerRail::V3_3 => Some(3.3),
+            PowerRail::V5 => Some(5.0),
+            PowerRail::V12 => Some(12.0),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PowerRail::Raw => "VBAT",
+            PowerRail::V3_3 => "3V3",
+            PowerRail::V5 => "5V",
+            PowerRail::V12 => "12V",
+        }
+    }
+}
+
+// Boundaries watched by the adaptive stepper; values are the thresholds crossed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossingKind {
+    SocBelow(f64), // Percent
+    SocAbove(f64),
+    VoltageBelow(f64), // Battery terminal voltage
+    VoltageAbove(f64),
+    EnteredEclipse,
+    LeftEclipse,
+}
+
+// Where a deployable panel is in its release sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeploymentPhase {
+    Stowed,                        // Waiting for the hold-off or for the panel before it
+    Burning { start_s: f64 },      // Burn-wire heater on
+    Retrying { next_burn_s: f64 }, // Wire cooling down after a burn the switch did not confirm
+    Deployed,                      // Switch confirms the hinge latched
+    Failed,                        // Attempts used up without confirmation
+}
+
+// Mechanism failures injected into a deployment channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeploymentFailure {
+    Stuck,                 // The restraint never releases
+    ReleasesOnAttempt(u8), // The restraint holds through the earlier burns, e.g. a wire cooled by a draft
+    Partial(f64),          // Opens short of its stop: fraction of the full output, switch stays open
+    SwitchStuckOpen,       // Deploys fully but the switch never confirms it
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentError {
+    TooManyPanels,
+    DuplicatePanel,
+    UnknownPanel, // No panel at that index
+    UnknownLoad,  // The burn wire is not a PDU load
+}
+
+// Where the spacecraft is between integration and handing over to the mode logic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LaunchPhase {
+    Integration, // Remove-before-flight pin inserted
+    Stowed,      // In the deployer, separation switches depressed
+    Booting,     // Separated: powering up stage by stage, transmitters silent
+    Operational, // Every stage powered and the RF silence over
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchError {
+    TooManyStages,
+    TooManyTransmitters,
+    UnknownSwitch,
+    UnknownLoad, // A boot stage is not a PDU load
+}
+
+// Telemetry the EPS logic acts on, which the fault injector can corrupt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sensor {
+    BatterySoc, // Percent, as seen by the mode guards and the shedding policy
+    BusVoltage, // As seen by the mode guards
+}
+
+// Faults applied by the fault injector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InjectedFault {
+    StringOpen { panel_index: usize },            // One parallel string opens; a panel without an I-V model is one string
+    CellShort { group: usize, position: usize },  // A cell of the battery pack shorts
+    LoadStuckOn(LoadId),                          // The channel switch welds closed: off commands and shedding have no effect
+    SensorFrozen(Sensor),                         // Keeps reporting the reading it had at injection
+    SensorOffset(Sensor, f64),                    // Adds a bias to the reading
+    RegulatorFailure,                             // The charge regulator delivers no array power
+}
+
+// When a scheduled fault is injected: at the start of the first step it holds for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FaultTrigger {
+    At(f64), // Mission elapsed time
+    SocBelow(f64),
+    InMode(SatelliteOperationalMode),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaultInjectionError {
+    TooManyFaults,
+    UnknownPanel,
+    UnknownLoad,
+    UnknownCell, // Not a cell of the battery pack, or the battery has no pack
+}
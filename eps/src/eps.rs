@@ -0,0 +1,536 @@
+// Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars
+use heapless::Vec;
+
+use crate::attitude::{Attitude, AttitudeMode};
+use crate::battery::Battery;
+use crate::consts::{
+    BATTERY_HEATER_LOAD_ID, DEFAULT_MODE_LOAD_RULES, MAX_SOLAR_PANELS, MAX_STEP_MODE_TRANSITIONS, SAFE_MODE_ENTRY_BUS_VOLTAGE_MARGIN,
+    SOLAR_FLUX_LEO_AVG_W_M2,
+};
+use crate::deployment::DeploymentSequencer;
+use crate::enums::{
+    BatteryFault, BatteryState, CellFailure, DeploymentError, FaultInjectionError, FaultTrigger, InjectedFault, LaunchError, ModeConfigError,
+    PackError, SatelliteOperationalMode, Sensor, TransitionReason,
+};
+use crate::events::{EventSink, NullSink, StepRecord};
+use crate::fault_injection::FaultInjector;
+use crate::launch::LaunchSequencer;
+use crate::mode_config::{ModeLoadRule, ModeLoadTable};
+use crate::mode_manager::{ModeInputs, ModeManager, ModeThresholds, ModeTransition};
+use crate::orbit::Orbit;
+use crate::pdu::PowerDistributionUnit;
+use crate::regulator::ChargeRegulator;
+use crate::shedding::{LoadSheddingPolicy, SheddingDecisions};
+use crate::solar_panel::SolarPanel;
+use crate::vector::Vector3;
+
+// Page 5, Section 1.1: Typically, the EPS Consists of power generation, power storage, power control & distribution
+#[derive(Debug, Clone)]
+pub struct EPS {
+    solar_panels: Vec<SolarPanel, MAX_SOLAR_PANELS>,
+    battery: Battery,
+    pdu: PowerDistributionUnit,
+    current_mode: SatelliteOperationalMode,
+    orbit: Option<Orbit>,       // When set, sunlight and eclipse follow the orbit geometry
+    elapsed_time_s: f64,        // Mission elapsed time
+    attitude: Attitude,         // Provides the direction to the sun in the body frame
+    regulator: ChargeRegulator, // Extracts power from panels that have an I-V model
+    shedding: LoadSheddingPolicy,
+    shedding_decisions: SheddingDecisions, // Made during the last step
+    mode_manager: ModeManager,
+    reported_transitions: u32,               // Mode transitions already included in a step record
+    mode_loads: ModeLoadTable,               // Loads switched on entering each mode
+    deployment: Option<DeploymentSequencer>, // Releases the deployable panels
+    launch: Option<LaunchSequencer>,         // Inhibits and staged power-up until handing over to the mode logic
+    fault_injector: FaultInjector,
+}
+
+impl EPS {
+    pub fn new(solar_panels: Vec<SolarPanel, MAX_SOLAR_PANELS>, battery: Battery, pdu: PowerDistributionUnit) -> Self {
+        let thresholds = ModeThresholds {
+            safe_entry_bus_voltage_v: battery.get_equivalent_circuit().get_min_voltage_v() * SAFE_MODE_ENTRY_BUS_VOLTAGE_MARGIN,
+            ..ModeThresholds::default()
+        };
+        let mode_loads = ModeLoadTable::from_rules(&DEFAULT_MODE_LOAD_RULES);
+        EPS {
+            solar_panels,
+            battery,
+            pdu,
+            current_mode: SatelliteOperationalMode::NominalSunlit, // Initial mode
+            orbit: None,
+            elapsed_time_s: 0.0,
+            attitude: Attitude::default(),
+            regulator: ChargeRegulator::default(),
+            shedding: LoadSheddingPolicy::default(),
+            shedding_decisions: SheddingDecisions::new(),
+            mode_manager: ModeManager::new(thresholds),
+            reported_transitions: 0,
+            mode_loads,
+            deployment: None,
+            launch: None,
+            fault_injector: FaultInjector::new(),
+        }
+    }
+
+    pub fn with_orbit(mut self, orbit: Orbit) -> Self {
+        self.orbit = Some(orbit);
+        self
+    }
+
+    pub fn get_orbit(&self) -> Option<&Orbit> {
+        self.orbit.as_ref()
+    }
+
+    pub fn get_elapsed_time_s(&self) -> f64 {
+        self.elapsed_time_s
+    }
+
+    pub fn with_attitude(mut self, attitude: Attitude) -> Self {
+        self.attitude = attitude;
+        self
+    }
+
+    pub fn get_attitude(&self) -> &Attitude {
+        &self.attitude
+    }
+
+    pub fn set_attitude_mode(&mut self, mode: AttitudeMode) {
+        self.attitude.set_mode(mode);
+    }
+
+    pub fn get_sun_vector_body(&self) -> Vector3 {
+        self.attitude.get_sun_vector_body()
+    }
+
+    pub fn set_sun_vector_body(&mut self, sun_vector_body: Vector3) {
+        self.attitude.set_sun_vector_body(sun_vector_body);
+    }
+
+    pub fn with_regulator(mut self, regulator: ChargeRegulator) -> Self {
+        self.regulator = regulator;
+        self
+    }
+
+    pub fn get_regulator(&self) -> &ChargeRegulator {
+        &self.regulator
+    }
+
+    pub fn with_shedding_policy(mut self, shedding: LoadSheddingPolicy) -> Self {
+        self.shedding = shedding;
+        self
+    }
+
+    pub fn get_shedding_policy(&self) -> &LoadSheddingPolicy {
+        &self.shedding
+    }
+
+    pub fn get_shedding_decisions(&self) -> &SheddingDecisions {
+        &self.shedding_decisions
+    }
+
+    pub fn with_mode_thresholds(mut self, thresholds: ModeThresholds) -> Self {
+        self.mode_manager.set_thresholds(thresholds);
+        self
+    }
+
+    /// Replaces the default mode-to-load configuration, validated against this EPS's loads.
+    pub fn with_mode_load_rules(mut self, rules: &[ModeLoadRule]) -> Result<Self, ModeConfigError> {
+        self.mode_loads = ModeLoadTable::new(rules, &self.pdu)?;
+        Ok(self)
+    }
+
+    /// Fails if a channel names a panel or burn-wire load this EPS does not have.
+    pub fn with_deployment(mut self, deployment: DeploymentSequencer) -> Result<Self, DeploymentError> {
+        deployment.validate(&self.solar_panels, &self.pdu)?;
+        self.deployment = Some(deployment);
+        Ok(self)
+    }
+
+    pub fn get_deployment(&self) -> Option<&DeploymentSequencer> {
+        self.deployment.as_ref()
+    }
+
+    /// For arming the hold-off and injecting mechanism failures.
+    pub fn get_deployment_mut(&mut self) -> Option<&mut DeploymentSequencer> {
+        self.deployment.as_mut()
+    }
+
+    /// Starts the EPS in the deployer. Fails if a boot stage names a load this EPS does not have.
+    /// The deployment hold-off is re-armed from the separation time.
+    pub fn with_launch(mut self, launch: LaunchSequencer) -> Result<Self, LaunchError> {
+        launch.validate(&self.pdu)?;
+        self.launch = Some(launch);
+        Ok(self)
+    }
+
+    pub fn get_launch(&self) -> Option<&LaunchSequencer> {
+        self.launch.as_ref()
+    }
+
+    /// For pulling the pin and setting separation switches.
+    pub fn get_launch_mut(&mut self) -> Option<&mut LaunchSequencer> {
+        self.launch.as_mut()
+    }
+
+    /// Fails if a scheduled fault names a panel, load or battery cell this EPS does not have.
+    pub fn with_fault_injector(mut self, fault_injector: FaultInjector) -> Result<Self, FaultInjectionError> {
+        for scheduled in fault_injector.get_pending() {
+            self.check_fault(&scheduled.fault)?;
+        }
+        self.fault_injector = fault_injector;
+        Ok(self)
+    }
+
+    pub fn get_fault_injector(&self) -> &FaultInjector {
+        &self.fault_injector
+    }
+
+    /// Adds a fault to the schedule, see [`FaultInjector::schedule`].
+    pub fn schedule_fault(&mut self, trigger: FaultTrigger, fault: InjectedFault) -> Result<(), FaultInjectionError> {
+        self.check_fault(&fault)?;
+        self.fault_injector.schedule(trigger, fault)
+    }
+
+    /// Test hook: applies `fault` now. Its responses are recorded from the next step on.
+    pub fn inject_fault(&mut self, fault: InjectedFault) -> Result<(), FaultInjectionError> {
+        self.check_fault(&fault)?;
+        self.apply_fault(fault);
+        Ok(())
+    }
+
+    /// Ground command: recalibrates a sensor, see [`FaultInjector::clear_sensor`].
+    pub fn clear_sensor_fault(&mut self, sensor: Sensor) {
+        self.fault_injector.clear_sensor(sensor);
+    }
+
+    fn check_fault(&self, fault: &InjectedFault) -> Result<(), FaultInjectionError> {
+        match *fault {
+            InjectedFault::StringOpen { panel_index } if panel_index >= self.solar_panels.len() => Err(FaultInjectionError::UnknownPanel),
+            InjectedFault::CellShort { group, position }
+                if self.battery.get_pack().and_then(|pack| pack.get_cell(group, position)).is_none() =>
+            {
+                Err(FaultInjectionError::UnknownCell)
+            }
+            InjectedFault::LoadStuckOn(load_id) if self.pdu.get_load(load_id).is_none() => Err(FaultInjectionError::UnknownLoad),
+            _ => Ok(()),
+        }
+    }
+
+    // The fault has been checked against this EPS
+    fn apply_fault(&mut self, fault: InjectedFault) {
+        match fault {
+            InjectedFault::StringOpen { panel_index } => self.solar_panels[panel_index].open_string(),
+            InjectedFault::CellShort { group, position } => {
+                let _ = self.battery.fail_cell(group, position, CellFailure::Short);
+            }
+            InjectedFault::LoadStuckOn(load_id) => {
+                let _ = self.pdu.inject_stuck_on(load_id);
+            }
+            InjectedFault::RegulatorFailure => self.regulator.fail(),
+            InjectedFault::SensorFrozen(_) | InjectedFault::SensorOffset(..) => {}
+        }
+        let reading = match fault {
+            InjectedFault::SensorFrozen(Sensor::BusVoltage) => self.battery.get_terminal_voltage_v(),
+            _ => self.battery.get_soc_percentage(),
+        };
+        self.fault_injector.record_injection(fault, self.elapsed_time_s, self.current_mode, reading);
+    }
+
+    pub fn get_mode_load_table(&self) -> &ModeLoadTable {
+        &self.mode_loads
+    }
+
+    pub fn get_mode_manager(&self) -> &ModeManager {
+        &self.mode_manager
+    }
+
+    pub fn get_mode_transitions(&self) -> &[ModeTransition] {
+        self.mode_manager.get_log()
+    }
+
+    pub fn get_current_mode(&self) -> &SatelliteOperationalMode {
+        &self.current_mode
+    }
+
+    pub fn get_battery(&self) -> &Battery {
+        &self.battery
+    }
+
+    pub fn get_pdu(&self) -> &PowerDistributionUnit {
+        &self.pdu
+    }
+
+    pub fn get_pdu_mut(&mut self) -> &mut PowerDistributionUnit {
+        &mut self.pdu
+    }
+
+    /// Test hook: latches a battery fault as if the battery had detected it. Returns false if it was already active.
+    pub fn raise_battery_fault(&mut self, fault: BatteryFault) -> bool {
+        self.battery.raise_fault(fault)
+    }
+
+    /// Test hook: fails one cell of a cell-pack battery.
+    pub fn fail_battery_cell(&mut self, group: usize, position: usize, failure: CellFailure) -> Result<(), PackError> {
+        self.battery.fail_cell(group, position, failure)
+    }
+
+    /// Ground command: clears a latched battery fault. Returns false if it was not active.
+    pub fn clear_battery_fault(&mut self, fault: BatteryFault) -> bool {
+        self.battery.clear_fault(fault)
+    }
+
+    pub fn get_solar_panels(&self) -> &[SolarPanel] {
+        &self.solar_panels
+    }
+
+    /// Whether the spacecraft is in sunlight. Without an orbit, eclipse is only the commanded `NominalEclipse` mode.
+    /// Eclipse Condition: page 21, Section 3.1
+    pub fn is_sunlit(&self) -> bool {
+        match &self.orbit {
+            Some(orbit) => orbit.is_sunlit(self.elapsed_time_s),
+            None => self.current_mode != SatelliteOperationalMode::NominalEclipse,
+        }
+    }
+
+    // Runs the mode state machine on the state at the start of the step
+    fn update_mode(&mut self) {
+        let faults = self.battery.get_faults();
+        let inputs = ModeInputs {
+            time_s: self.elapsed_time_s,
+            soc_percentage: self.fault_injector.read(Sensor::BatterySoc, self.battery.get_soc_percentage()),
+            bus_voltage_v: self.fault_injector.read(Sensor::BusVoltage, self.battery.get_terminal_voltage_v()),
+            // The cold charge inhibit lifts by itself as the heaters warm the battery
+            battery_fault: faults.get_active().iter().any(|latched| {
+                !latched.fault.clears_itself() && (latched.fault.blocks_charge() || latched.fault.blocks_discharge())
+            }),
+            sunlit: self.orbit.as_ref().map(|orbit| orbit.is_sunlit(self.elapsed_time_s)),
+        };
+        if let Some((mode, reason)) = self.mode_manager.evaluate(self.current_mode, &inputs) {
+            self.transition_to(mode, reason);
+        }
+    }
+
+    /// Updates the power output of all solar panels based on sunlight and each panel's angle to the sun.
+    /// References:
+    /// - Solar Power as Primary Generation: page 14, Section 2.1
+    /// - Dependence on Sun Intensity: page 14, Section 2.1
+    /// - Dependence on Satellite Attitude (Angle to Sun) page 12, Section 1.3
+    /// - Eclipse Condition: page 21, Section 3.1
+    ///
+    /// Panels with an I-V model deliver what the charge regulator extracts at the present battery voltage.
+    ///
+    /// Launch inhibits disconnect the arrays until separation.
+    fn update_solar_power_generation(&mut self) {
+        let inhibited = self.launch.as_ref().is_some_and(LaunchSequencer::is_inhibited);
+        let sun_intensity = if self.is_sunlit() && !inhibited { SOLAR_FLUX_LEO_AVG_W_M2 } else { 0.0 };
+        let sun_vector_body = self.attitude.update(self.orbit.as_ref(), self.elapsed_time_s);
+
+        let battery_voltage_v = self.battery.get_terminal_voltage_v();
+        for (index, panel) in self.solar_panels.iter_mut().enumerate() {
+            panel.update_radiation_damage(self.elapsed_time_s);
+            panel.update_power_output(sun_intensity, &sun_vector_body);
+            let delivered_w = self.regulator.regulate(index, panel, battery_voltage_v);
+            panel.set_power_output_w(delivered_w);
+        }
+    }
+
+    pub fn get_total_generated_power_w(&self) -> f64 {
+        self.solar_panels.iter().map(|p| p.get_power_output_w()).sum()
+    }
+
+
+    /// Manages the power balance of the CubeSat (power generation, demand from loads, and battery usage) for a given time step.
+    /// References:
+    /// - Battery Charging with Surplus: page 7  and page 21
+    /// - Battery Discharging During Deficit: page 21, Section 3.1
+    /// - Load Management / Load Shedding: page 12, Section 1.3
+    /// - Transition to Safe Mode: page 12, Section 1.3
+    pub fn manage_power(&mut self, time_step_h: f64) -> StepRecord {
+        self.manage_power_with(time_step_h, &mut NullSink)
+    }
+
+    /// [`EPS::manage_power`], also publishing the step's events to `sink`.
+    pub fn manage_power_with(&mut self, time_step_h: f64, sink: &mut dyn EventSink) -> StepRecord {
+        let time_s = self.elapsed_time_s;
+        let faults_before = self.battery.get_faults().clone();
+        self.battery.set_time_s(time_s);
+        let faults_injected = self.fault_injector.take_due(time_s, self.battery.get_soc_percentage(), self.current_mode);
+        for fault in faults_injected.iter().copied() {
+            self.apply_fault(fault);
+        }
+        let duration_s = time_step_h * 3600.0;
+        // In the deployer the EPS is dark and the battery isolated; the mode logic waits for the boot to finish
+        let (inhibited, booting) = match self.launch.as_mut() {
+            Some(launch) => {
+                if let Some(separation_time_s) = launch.update(time_s, time_s + duration_s, &mut self.pdu)
+                    && let Some(deployment) = self.deployment.as_mut()
+                {
+                    deployment.arm(separation_time_s);
+                }
+                (launch.is_inhibited(), !launch.is_operational())
+            }
+            None => (false, false),
+        };
+        if !booting {
+            self.update_mode();
+        }
+        let burn_attempts = match self.deployment.as_mut() {
+            Some(deployment) if !inhibited => deployment.update(time_s, time_s + duration_s, &mut self.pdu, &mut self.solar_panels),
+            _ => Vec::new(),
+        };
+        self.update_solar_power_generation();
+        let tripped_loads = self.pdu.update_current_limiters(self.battery.get_terminal_voltage_v(), duration_s);
+        let sensed_soc_percentage = self.fault_injector.read(Sensor::BatterySoc, self.battery.get_soc_percentage());
+        self.shedding_decisions = self.shedding.update(&mut self.pdu, sensed_soc_percentage);
+        let generated_power_w = self.get_total_generated_power_w();
+        let demanded_power_w = self.pdu.get_total_demand_w(duration_s).average_w;
+
+        let net_power_w = generated_power_w - demanded_power_w;
+        let mut battery_energy_in_wh = 0.0;
+        let mut battery_energy_out_wh = 0.0;
+        let mut charge_inhibited = false;
+
+        if net_power_w >= 0.0 {
+            if net_power_w > 0.0 {
+                // Surplus power available for charging
+                battery_energy_in_wh = self.battery.charge(net_power_w, time_step_h) * time_step_h;
+                charge_inhibited = self.battery.get_faults().is_active(BatteryFault::ChargeUnderTemperature);
+            } else {
+                 // Power balanced by solar generation
+                 self.battery.rest(time_step_h);
+                 if self.battery.state == BatteryState::Charging || self.battery.state == BatteryState::Discharging {
+                    if self.battery.current_charge_wh >= self.battery.get_effective_capacity_wh() * 0.999 {
+                        self.battery.state = BatteryState::Full;
+                    } else if self.battery.current_charge_wh <= self.battery.get_effective_capacity_wh() * 0.001 {
+                        self.battery.state = BatteryState::Empty;
+                    } else {
+                        self.battery.state = BatteryState::Idle;
+                    }
+                 }
+            }
+        } else {
+            // Tried on a copy first, so the battery is discharged (and aged) once per step
+            let deficit_w = -net_power_w;
+            let mut trial = self.battery.clone();
+            let power_from_battery_w = trial.discharge(deficit_w, time_step_h);
+
+            // Battery could not cover the deficit: load shedding may be required
+            let battery_blocked = trial.get_faults().discharge_blocker().is_some();
+            if power_from_battery_w < deficit_w * 0.99
+                && (trial.get_status() == &BatteryState::Empty || trial.get_soc_percentage() < 10.0 || battery_blocked)
+            {
+                 // Battery empty or critically low, shed every tier. Loads outside the tiers stay off until commanded.
+                 let soc_percentage = trial.get_soc_percentage();
+                 for decision in self.shedding.shed_all(&mut self.pdu, soc_percentage) {
+                     let _ = self.shedding_decisions.push(decision); // Each tier is shed at most once per step
+                 }
+                 self.pdu.shed_non_critical_loads();
+                 let demanded_power_w = self.pdu.get_total_demand_w(duration_s).average_w;
+                 let new_net_power_w = generated_power_w - demanded_power_w;
+                 if new_net_power_w < 0.0 {
+                    battery_energy_out_wh = self.battery.discharge(-new_net_power_w, time_step_h) * time_step_h;
+                    // Still can't meet critical demand
+                    if matches!(self.battery.get_status(), BatteryState::Empty | BatteryState::Fault(_)) {
+                        self.transition_to(SatelliteOperationalMode::SafeMode, TransitionReason::BatteryEmpty);
+                    }
+                 } else {
+                    self.battery.rest(time_step_h);
+                 }
+            } else {
+                self.battery = trial;
+                battery_energy_out_wh = power_from_battery_w * time_step_h;
+            }
+        }
+
+        // Battery temperature follows the environment, its own losses and the heater load
+        let heater_power_w = self.pdu.get_load_demand_w(BATTERY_HEATER_LOAD_ID, duration_s).map_or(0.0, |heater| heater.average_w);
+        let sunlit = self.is_sunlit();
+        self.battery.update_temperature(sunlit, heater_power_w, time_step_h);
+
+        let demand = self.pdu.get_total_demand_w(duration_s);
+        let rail_demand = self.pdu.get_rail_demand(duration_s);
+        let net_power_w = generated_power_w - demand.average_w;
+        self.mode_manager.record_energy(self.current_mode, net_power_w * time_step_h);
+
+        if let Some(deployment) = self.deployment.as_mut() {
+            deployment.record_burn_power(&self.pdu, duration_s); // After any shedding or mode change within the step
+        }
+        self.elapsed_time_s += duration_s;
+        self.pdu.advance_to(self.elapsed_time_s); // Runs the scheduled switches of the step
+
+        let record = StepRecord {
+            time_s,
+            duration_h: time_step_h,
+            generated_power_w,
+            demanded_power_w: demand.average_w,
+            peak_demand_w: demand.peak_w,
+            rail_demand,
+            net_power_w,
+            battery_energy_in_wh,
+            battery_energy_out_wh,
+            soc_percentage: self.battery.get_soc_percentage(),
+            mode: self.current_mode,
+            faults_injected,
+            mode_transitions: self.take_unreported_transitions(),
+            shedding_decisions: self.shedding_decisions.clone(),
+            tripped_loads,
+            faults_raised: self
+                .battery
+                .get_faults()
+                .get_active()
+                .iter()
+                .map(|latched| latched.fault)
+                .filter(|fault| !faults_before.is_active(*fault))
+                .collect(),
+            charge_inhibited,
+            burn_attempts,
+        };
+        self.fault_injector.record_responses(&record);
+        record.publish(sink);
+        record
+    }
+
+    // Transitions logged since the previous step, commands included
+    fn take_unreported_transitions(&mut self) -> Vec<ModeTransition, MAX_STEP_MODE_TRANSITIONS> {
+        let count = self.mode_manager.get_transition_count();
+        let log = self.mode_manager.get_log();
+        let unreported = ((count - self.reported_transitions) as usize).min(log.len()).min(MAX_STEP_MODE_TRANSITIONS);
+        self.reported_transitions = count;
+        log[log.len() - unreported..].iter().copied().collect()
+    }
+
+
+    /// Sets the satellite's operational mode and adjusts component power states accordingly.
+    /// References
+    /// - Concept of Operational Power Modes: page 12, Section 1.3
+    /// - Controlling Loads Based on Mode: page 12, Section 1.3
+    /// - Mode Configuration: page 12, Section 1.3
+    ///
+    /// This is the ground command path; the transition is logged as such. The autonomous guards
+    /// still apply on the next step, so commanding out of SafeMode with a flat battery does not stick.
+    pub fn set_satellite_mode(&mut self, mode: SatelliteOperationalMode) {
+        self.transition_to(mode, TransitionReason::GroundCommand);
+    }
+
+    fn transition_to(&mut self, mode: SatelliteOperationalMode, reason: TransitionReason) {
+        if self.current_mode == mode { // No change if already in the target mode
+            return;
+        }
+        if self.current_mode == SatelliteOperationalMode::SafeMode {
+            self.attitude.exit_safe_mode();
+        }
+        self.mode_manager.record_transition(ModeTransition {
+            time_s: self.elapsed_time_s,
+            from: self.current_mode,
+            to: mode,
+            reason,
+        });
+        self.current_mode = mode;
+
+        self.mode_loads.apply(mode, &mut self.pdu);
+        if mode == SatelliteOperationalMode::SafeMode {
+            // Survival attitude: point the main array at the sun.
+            self.attitude.enter_safe_mode();
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// Thevenin equivalent-circuit battery model: open-circuit voltage source, series resistance R0
+// and one RC pair for the polarisation dynamics. Terminal voltage: V = OCV(SoC) - I * R0 - V_rc
+// with the current I positive while discharging.
+use heapless::Vec;
+
+use crate::consts::{LI_ION_CELL_C1_F, LI_ION_CELL_MAX_V, LI_ION_CELL_MIN_V, LI_ION_CELL_OCV_CURVE, LI_ION_CELL_R0_OHM, LI_ION_CELL_R1_OHM, MAX_OCV_POINTS};
+use crate::interpolate;
+
+#[derive(Debug, Clone)]
+pub struct EquivalentCircuit {
+    ocv_curve: Vec<(f64, f64), MAX_OCV_POINTS>, // (SoC from 0.0 to 1.0, open-circuit voltage), ascending SoC
+    series_resistance_ohm: f64,                 // R0
+    rc_resistance_ohm: f64,                     // R1
+    rc_capacitance_f: f64,                      // C1
+    min_voltage_v: f64,                         // Under-voltage limit
+    max_voltage_v: f64,                         // Over-voltage limit (end of charge)
+}
+
+impl EquivalentCircuit {
+    /// Points beyond [`MAX_OCV_POINTS`] are dropped; the curve must be sorted by SoC.
+    pub fn new(ocv_curve: &[(f64, f64)], series_resistance_ohm: f64, rc_resistance_ohm: f64, rc_capacitance_f: f64, min_voltage_v: f64, max_voltage_v: f64) -> Self {
+        let mut curve = Vec::new();
+        for point in ocv_curve.iter().take(MAX_OCV_POINTS) {
+            let _ = curve.push(*point);
+        }
+        EquivalentCircuit {
+            ocv_curve: curve,
+            series_resistance_ohm,
+            rc_resistance_ohm,
+            rc_capacitance_f,
+            min_voltage_v,
+            max_voltage_v,
+        }
+    }
+
+    /// Generic Li-ion (NMC) string of `series_cells` cells.
+    pub fn li_ion(series_cells: u32) -> Self {
+        let cells = series_cells.max(1) as f64;
+        let mut curve: Vec<(f64, f64), MAX_OCV_POINTS> = Vec::new();
+        for (soc, cell_v) in LI_ION_CELL_OCV_CURVE.iter() {
+            let _ = curve.push((*soc, cell_v * cells));
+        }
+        EquivalentCircuit::new(
+            &curve,
+            LI_ION_CELL_R0_OHM * cells,
+            LI_ION_CELL_R1_OHM * cells,
+            LI_ION_CELL_C1_F / cells,
+            LI_ION_CELL_MIN_V * cells,
+            LI_ION_CELL_MAX_V * cells,
+        )
+    }
+
+    /// Linear interpolation of the OCV curve, held flat outside its SoC range.
+    pub fn get_open_circuit_voltage_v(&self, soc: f64) -> f64 {
+        interpolate(&self.ocv_curve, soc)
+    }
+
+    pub fn get_series_resistance_ohm(&self) -> f64 {
+        self.series_resistance_ohm
+    }
+
+    pub fn get_rc_resistance_ohm(&self) -> f64 {
+        self.rc_resistance_ohm
+    }
+
+    pub fn get_rc_time_constant_s(&self) -> f64 {
+        self.rc_resistance_ohm * self.rc_capacitance_f
+    }
+
+    pub fn get_min_voltage_v(&self) -> f64 {
+        self.min_voltage_v
+    }
+
+    pub fn get_max_voltage_v(&self) -> f64 {
+        self.max_voltage_v
+    }
+
+    /// Fraction of the RC voltage left after `duration_s` with no current, with both
+    /// resistances scaled by `resistance_factor` (temperature).
+    pub(crate) fn rc_decay(&self, duration_s: f64, resistance_factor: f64) -> f64 {
+        let tau_s = self.get_rc_time_constant_s() * resistance_factor;
+        if tau_s > 0.0 { libm::exp(-duration_s / tau_s) } else { 0.0 }
+    }
+}
@@ -0,0 +1,149 @@
+// What happened during one EPS step, for tests, telemetry and simulators.
+// `EPS::manage_power` returns a `StepRecord`; the same information is also pushed, event by event,
+// to an `EventSink` so consumers can react without polling.
+use heapless::Vec;
+
+use crate::consts::{MAX_LATCHED_FAULTS, MAX_LOADS, MAX_RAILS, MAX_STEP_BURNS, MAX_STEP_INJECTIONS, MAX_STEP_MODE_TRANSITIONS};
+use crate::deployment::BurnAttempt;
+use crate::enums::{BatteryFault, InjectedFault, SatelliteOperationalMode, SheddingAction};
+use crate::load_id::LoadId;
+use crate::mode_manager::ModeTransition;
+use crate::rail::RailDemand;
+use crate::stepper::Crossing;
+use crate::shedding::{SheddingDecision, SheddingDecisions};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepRecord {
+    pub time_s: f64,                 // Mission elapsed time at the start of the step
+    pub duration_h: f64,
+    pub generated_power_w: f64,
+    pub demanded_power_w: f64,       // After load shedding
+    pub peak_demand_w: f64,          // Worst case within the step: inrush, pulses
+    pub rail_demand: Vec<RailDemand, MAX_RAILS>, // Demand and converter losses per rail
+    pub net_power_w: f64,
+    pub battery_energy_in_wh: f64,   // Accepted at the battery terminals
+    pub battery_energy_out_wh: f64,  // Delivered at the battery terminals
+    pub soc_percentage: f64,         // At the end of the step
+    pub mode: SatelliteOperationalMode, // At the end of the step
+    pub faults_injected: Vec<InjectedFault, MAX_STEP_INJECTIONS>, // At the start of the step
+    pub mode_transitions: Vec<ModeTransition, MAX_STEP_MODE_TRANSITIONS>, // Since the previous step
+    pub shedding_decisions: SheddingDecisions,
+    pub tripped_loads: Vec<LoadId, MAX_LOADS>, // Channels whose current limiter opened
+    pub faults_raised: Vec<BatteryFault, MAX_LATCHED_FAULTS>,
+    pub charge_inhibited: bool,      // Surplus power was refused because the battery was too cold
+    pub burn_attempts: Vec<BurnAttempt, MAX_STEP_BURNS>, // Deployment burns whose switch was read
+}
+
+impl StepRecord {
+    /// DC-DC converter losses over all rails, included in `demanded_power_w`.
+    pub fn conversion_loss_w(&self) -> f64 {
+        self.rail_demand.iter().map(|rail| rail.loss_w).sum()
+    }
+
+    /// Loads switched off by the shedding policy during the step.
+    pub fn shed_loads(&self) -> impl Iterator<Item = LoadId> + '_ {
+        self.shedding_decisions
+            .iter()
+            .filter(|decision| decision.action == SheddingAction::Shed)
+            .flat_map(|decision| decision.loads.iter().copied())
+    }
+
+    /// Extends this record with the step that followed it, as if both were one step: powers are
+    /// time-weighted, energies added, end-of-step values taken from `next`. Lists keep what fits.
+    pub(crate) fn absorb(&mut self, next: &StepRecord) {
+        let total_h = self.duration_h + next.duration_h;
+        let weigh = |a: f64, b: f64| if total_h > 0.0 { (a * self.duration_h + b * next.duration_h) / total_h } else { b };
+        self.generated_power_w = weigh(self.generated_power_w, next.generated_power_w);
+        self.demanded_power_w = weigh(self.demanded_power_w, next.demanded_power_w);
+        self.net_power_w = weigh(self.net_power_w, next.net_power_w);
+        self.peak_demand_w = self.peak_demand_w.max(next.peak_demand_w);
+        for (rail, next_rail) in self.rail_demand.iter_mut().zip(next.rail_demand.iter()) {
+            rail.output.average_w = weigh(rail.output.average_w, next_rail.output.average_w);
+            rail.output.peak_w = rail.output.peak_w.max(next_rail.output.peak_w);
+            rail.input.average_w = weigh(rail.input.average_w, next_rail.input.average_w);
+            rail.input.peak_w = rail.input.peak_w.max(next_rail.input.peak_w);
+            rail.loss_w = weigh(rail.loss_w, next_rail.loss_w);
+        }
+        self.battery_energy_in_wh += next.battery_energy_in_wh;
+        self.battery_energy_out_wh += next.battery_energy_out_wh;
+        self.soc_percentage = next.soc_percentage;
+        self.mode = next.mode;
+        self.duration_h = total_h;
+        for fault in next.faults_injected.iter() {
+            let _ = self.faults_injected.push(*fault);
+        }
+        for transition in next.mode_transitions.iter() {
+            let _ = self.mode_transitions.push(*transition);
+        }
+        for decision in next.shedding_decisions.iter() {
+            let _ = self.shedding_decisions.push(decision.clone());
+        }
+        for load_id in next.tripped_loads.iter() {
+            if !self.tripped_loads.contains(load_id) {
+                let _ = self.tripped_loads.push(*load_id);
+            }
+        }
+        for fault in next.faults_raised.iter() {
+            if !self.faults_raised.contains(fault) {
+                let _ = self.faults_raised.push(*fault);
+            }
+        }
+        self.charge_inhibited |= next.charge_inhibited;
+        for attempt in next.burn_attempts.iter() {
+            let _ = self.burn_attempts.push(*attempt);
+        }
+    }
+
+    pub(crate) fn publish(&self, sink: &mut dyn EventSink) {
+        for fault in self.faults_injected.iter() {
+            sink.on_event(&EpsEvent::FaultInjected(*fault));
+        }
+        for transition in self.mode_transitions.iter() {
+            sink.on_event(&EpsEvent::ModeChanged(transition));
+        }
+        for decision in self.shedding_decisions.iter() {
+            sink.on_event(&EpsEvent::Shedding(decision));
+        }
+        for load_id in self.tripped_loads.iter() {
+            sink.on_event(&EpsEvent::ChannelTripped(*load_id));
+        }
+        for fault in self.faults_raised.iter() {
+            sink.on_event(&EpsEvent::FaultRaised(*fault));
+        }
+        for attempt in self.burn_attempts.iter() {
+            sink.on_event(&EpsEvent::BurnCompleted(attempt));
+        }
+        sink.on_event(&EpsEvent::StepCompleted(self));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EpsEvent<'a> {
+    FaultInjected(InjectedFault),
+    ModeChanged(&'a ModeTransition),
+    Shedding(&'a SheddingDecision),
+    ChannelTripped(LoadId),
+    FaultRaised(BatteryFault),
+    BurnCompleted(&'a BurnAttempt),
+    ThresholdCrossed(Crossing), // Located by the adaptive stepper, after the events of the sub-step ending at it
+    StepCompleted(&'a StepRecord), // Always the last event of a step
+}
+
+/// Receives the events of every step, in the order they are listed in [`StepRecord`].
+pub trait EventSink {
+    fn on_event(&mut self, event: &EpsEvent);
+}
+
+/// Discards every event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullSink;
+
+impl EventSink for NullSink {
+    fn on_event(&mut self, _event: &EpsEvent) {}
+}
+
+impl<F: FnMut(&EpsEvent)> EventSink for F {
+    fn on_event(&mut self, event: &EpsEvent) {
+        self(event)
+    }
+}
@@ -0,0 +1,166 @@
+// Scheduled fault injection for FDIR testing. A fault is applied at the start of the first step its
+// trigger holds for, and its injection record collects what the EPS did afterwards: mode changes, shed
+// loads, channel trips and latched battery faults. Sensor faults only corrupt what the mode guards and
+// the shedding policy read; the battery's own protection still sees the true values.
+use heapless::Vec;
+
+use crate::consts::{MAX_FAULT_RESPONSES, MAX_INJECTIONS, MAX_SCHEDULED_FAULTS, MAX_STEP_INJECTIONS};
+use crate::enums::{BatteryFault, FaultInjectionError, FaultTrigger, InjectedFault, SatelliteOperationalMode, Sensor};
+use crate::events::StepRecord;
+use crate::load_id::LoadId;
+use crate::mode_manager::ModeTransition;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduledFault {
+    pub trigger: FaultTrigger,
+    pub fault: InjectedFault,
+}
+
+/// Something the EPS did after an injection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FaultResponse {
+    ModeChanged(ModeTransition),
+    LoadShed { time_s: f64, load_id: LoadId },
+    ChannelTripped { time_s: f64, load_id: LoadId }, // Step start
+    BatteryFaultRaised { time_s: f64, fault: BatteryFault }, // Step start
+}
+
+impl FaultResponse {
+    pub fn get_time_s(&self) -> f64 {
+        match self {
+            FaultResponse::ModeChanged(transition) => transition.time_s,
+            FaultResponse::LoadShed { time_s, .. }
+            | FaultResponse::ChannelTripped { time_s, .. }
+            | FaultResponse::BatteryFaultRaised { time_s, .. } => *time_s,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InjectionRecord {
+    pub fault: InjectedFault,
+    pub time_s: f64,
+    pub mode: SatelliteOperationalMode, // When injected
+    pub responses: Vec<FaultResponse, MAX_FAULT_RESPONSES>, // In the order they happened
+}
+
+impl InjectionRecord {
+    /// Seconds from the injection until the EPS entered `mode`, if it did.
+    pub fn get_time_to_mode_s(&self, mode: SatelliteOperationalMode) -> Option<f64> {
+        self.responses.iter().find_map(|response| match response {
+            FaultResponse::ModeChanged(transition) if transition.to == mode => Some(transition.time_s - self.time_s),
+            _ => None,
+        })
+    }
+
+    pub fn was_load_shed(&self, load_id: LoadId) -> bool {
+        self.responses.iter().any(|response| matches!(response, FaultResponse::LoadShed { load_id: shed, .. } if *shed == load_id))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultInjector {
+    pending: Vec<ScheduledFault, MAX_SCHEDULED_FAULTS>,
+    log: Vec<InjectionRecord, MAX_INJECTIONS>,
+    frozen: [Option<f64>; 2], // Per sensor, the reading held
+    offsets: [f64; 2],
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        FaultInjector::default()
+    }
+
+    /// Injects `fault` once `trigger` holds. Faults triggered by the same step are applied in the order scheduled.
+    pub fn schedule(&mut self, trigger: FaultTrigger, fault: InjectedFault) -> Result<(), FaultInjectionError> {
+        self.pending.push(ScheduledFault { trigger, fault }).map_err(|_| FaultInjectionError::TooManyFaults)
+    }
+
+    pub fn get_pending(&self) -> &[ScheduledFault] {
+        &self.pending
+    }
+
+    pub fn get_log(&self) -> &[InjectionRecord] {
+        &self.log
+    }
+
+    /// The latest injection of `fault`.
+    pub fn get_record(&self, fault: InjectedFault) -> Option<&InjectionRecord> {
+        self.log.iter().rev().find(|record| record.fault == fault)
+    }
+
+    /// What the EPS logic reads for `sensor` when the true value is `value`.
+    pub fn read(&self, sensor: Sensor, value: f64) -> f64 {
+        self.frozen[sensor as usize].unwrap_or(value) + self.offsets[sensor as usize]
+    }
+
+    pub fn is_sensor_faulty(&self, sensor: Sensor) -> bool {
+        self.frozen[sensor as usize].is_some() || self.offsets[sensor as usize] != 0.0
+    }
+
+    /// Recalibration: the sensor reads true again.
+    pub fn clear_sensor(&mut self, sensor: Sensor) {
+        self.frozen[sensor as usize] = None;
+        self.offsets[sensor as usize] = 0.0;
+    }
+
+    // Removes and returns the faults whose trigger holds, up to the per-step limit
+    pub(crate) fn take_due(
+        &mut self,
+        time_s: f64,
+        soc_percentage: f64,
+        mode: SatelliteOperationalMode,
+    ) -> Vec<InjectedFault, MAX_STEP_INJECTIONS> {
+        let mut due = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            let scheduled = self.pending[index];
+            let holds = match scheduled.trigger {
+                FaultTrigger::At(at_s) => time_s >= at_s,
+                FaultTrigger::SocBelow(threshold) => soc_percentage < threshold,
+                FaultTrigger::InMode(in_mode) => mode == in_mode,
+            };
+            if holds && due.push(scheduled.fault).is_ok() {
+                self.pending.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        due
+    }
+
+    // `reading` is the true sensor value at injection, held by a frozen sensor
+    pub(crate) fn record_injection(&mut self, fault: InjectedFault, time_s: f64, mode: SatelliteOperationalMode, reading: f64) {
+        match fault {
+            InjectedFault::SensorFrozen(sensor) => self.frozen[sensor as usize] = Some(reading),
+            InjectedFault::SensorOffset(sensor, offset) => self.offsets[sensor as usize] = offset,
+            _ => {}
+        }
+        let _ = self.log.push(InjectionRecord { fault, time_s, mode, responses: Vec::new() });
+    }
+
+    // Adds the step's responses to every injection made up to the start of the step
+    pub(crate) fn record_responses(&mut self, record: &StepRecord) {
+        let mut responses: Vec<FaultResponse, MAX_FAULT_RESPONSES> = Vec::new();
+        for transition in record.mode_transitions.iter() {
+            let _ = responses.push(FaultResponse::ModeChanged(*transition));
+        }
+        for load_id in record.shed_loads() {
+            let _ = responses.push(FaultResponse::LoadShed { time_s: record.time_s, load_id });
+        }
+        for load_id in record.tripped_loads.iter().copied() {
+            let _ = responses.push(FaultResponse::ChannelTripped { time_s: record.time_s, load_id });
+        }
+        for fault in record.faults_raised.iter().copied() {
+            let _ = responses.push(FaultResponse::BatteryFaultRaised { time_s: record.time_s, fault });
+        }
+        for injection in self.log.iter_mut().filter(|injection| injection.time_s <= record.time_s) {
+            for response in responses.iter() {
+                // Mode changes commanded before the injection are not a response to it
+                if response.get_time_s() >= injection.time_s {
+                    let _ = injection.responses.push(*response);
+                }
+            }
+        }
+    }
+}
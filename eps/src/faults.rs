@@ -0,0 +1,79 @@
+// Latched battery faults: a fault stays active from the moment it is raised until it is cleared
+// by command, even if the condition that caused it goes away.
+// Page 25, Section 3.4
+use heapless::Vec;
+
+use crate::consts::MAX_LATCHED_FAULTS;
+use crate::enums::BatteryFault;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatchedFault {
+    pub fault: BatteryFault,
+    pub raised_at_s: f64,  // Mission elapsed time of the first occurrence
+    pub occurrences: u32,  // Times the condition was detected while latched
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FaultLog {
+    faults: Vec<LatchedFault, MAX_LATCHED_FAULTS>,
+}
+
+impl FaultLog {
+    pub fn new() -> Self {
+        FaultLog { faults: Vec::new() }
+    }
+
+    /// Latches `fault`. Returns true if it was not already active.
+    pub fn raise(&mut self, fault: BatteryFault, time_s: f64) -> bool {
+        if let Some(latched) = self.faults.iter_mut().find(|latched| latched.fault == fault) {
+            latched.occurrences += 1;
+            return false;
+        }
+        // One slot per fault type, so the log cannot overflow
+        let _ = self.faults.push(LatchedFault { fault, raised_at_s: time_s, occurrences: 1 });
+        true
+    }
+
+    /// Ground command: clears a latched fault. Returns false if it was not active.
+    pub fn clear(&mut self, fault: BatteryFault) -> bool {
+        let before = self.faults.len();
+        self.faults.retain(|latched| latched.fault != fault);
+        self.faults.len() != before
+    }
+
+    /// Clears a self-clearing fault whose condition has gone, see [`BatteryFault::clears_itself`].
+    /// Returns false for latched faults, which only a ground command clears.
+    pub fn release(&mut self, fault: BatteryFault) -> bool {
+        fault.clears_itself() && self.clear(fault)
+    }
+
+    pub fn clear_all(&mut self) {
+        self.faults.clear();
+    }
+
+    pub fn is_active(&self, fault: BatteryFault) -> bool {
+        self.faults.iter().any(|latched| latched.fault == fault)
+    }
+
+    pub fn get_active(&self) -> &[LatchedFault] {
+        &self.faults
+    }
+
+    pub fn charge_blocker(&self) -> Option<BatteryFault> {
+        self.faults.iter().map(|latched| latched.fault).find(|fault| fault.blocks_charge())
+    }
+
+    pub fn discharge_blocker(&self) -> Option<BatteryFault> {
+        self.faults.iter().map(|latched| latched.fault).find(|fault| fault.blocks_discharge())
+    }
+}
+
+// Detection thresholds, checked after every battery operation
+#[derive(Debug, Clone)]
+pub struct BatteryFaultLimits {
+    pub over_temperature_c: f64,
+    pub min_discharge_temperature_c: f64,
+    pub over_voltage_v: f64,
+    pub over_current_a: f64,
+    pub severe_degradation_health: f64,
+}
@@ -0,0 +1,220 @@
+// Launch configuration, following the CubeSat Design Specification inhibits. While the remove-before-flight
+// pin is in or any separation switch is depressed, the battery is isolated from the bus and the arrays from
+// the charger, so the EPS stays dark. Once they all release, the loads power up stage by stage; transmitters
+// stay off until the RF silence after separation is over, and the deployment hold-off starts from the same time.
+use heapless::Vec;
+
+use crate::consts::{
+    DEFAULT_BOOT_STAGES, DEFAULT_SEPARATION_SWITCHES, DEFAULT_TRANSMITTERS, MAX_BOOT_STAGES, MAX_SEPARATION_SWITCHES,
+    MAX_TRANSMITTERS, RF_SILENCE_S,
+};
+use crate::enums::{LaunchError, LaunchPhase};
+use crate::load_id::LoadId;
+use crate::load_profile::ScheduledSwitch;
+use crate::pdu::PowerDistributionUnit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BootStage {
+    load_id: LoadId,
+    delay_s: f64,                // After separation
+    powered_on_s: Option<f64>,   // None until switched on
+}
+
+impl BootStage {
+    pub fn get_load_id(&self) -> LoadId {
+        self.load_id
+    }
+
+    pub fn get_delay_s(&self) -> f64 {
+        self.delay_s
+    }
+
+    pub fn get_powered_on_s(&self) -> Option<f64> {
+        self.powered_on_s
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchSequencer {
+    phase: LaunchPhase,
+    rbf_pin_inserted: bool,
+    separation_switches: Vec<bool, MAX_SEPARATION_SWITCHES>, // True while depressed
+    scheduled_separation_s: Option<f64>, // The deployer ejects the spacecraft, releasing every switch
+    separation_time_s: Option<f64>,
+    rf_silence_s: f64,
+    transmitters: Vec<LoadId, MAX_TRANSMITTERS>,
+    stages: Vec<BootStage, MAX_BOOT_STAGES>,
+}
+
+impl Default for LaunchSequencer {
+    /// Flight configuration: pin removed, stowed on the standard switches, OBC, COM_RX and beacon stages.
+    fn default() -> Self {
+        let mut launch = LaunchSequencer::new(DEFAULT_SEPARATION_SWITCHES, RF_SILENCE_S);
+        for (load_id, delay_s) in DEFAULT_BOOT_STAGES {
+            let _ = launch.add_stage(load_id, delay_s); // Within capacity
+        }
+        launch
+    }
+}
+
+impl LaunchSequencer {
+    /// Stowed in the deployer with every switch depressed, no boot stages and the standard transmitters.
+    pub fn new(separation_switches: usize, rf_silence_s: f64) -> Self {
+        LaunchSequencer {
+            phase: LaunchPhase::Stowed,
+            rbf_pin_inserted: false,
+            separation_switches: (0..separation_switches.clamp(1, MAX_SEPARATION_SWITCHES)).map(|_| true).collect(),
+            scheduled_separation_s: None,
+            separation_time_s: None,
+            rf_silence_s: rf_silence_s.max(0.0),
+            transmitters: DEFAULT_TRANSMITTERS.into_iter().collect(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// Ejects the spacecraft at `time_s`, releasing every separation switch.
+    pub fn with_separation_at(mut self, time_s: f64) -> Self {
+        self.scheduled_separation_s = Some(time_s);
+        self
+    }
+
+    /// Switches `load_id` on `delay_s` after separation. Transmitters are held to the end of the RF silence.
+    pub fn add_stage(&mut self, load_id: LoadId, delay_s: f64) -> Result<(), LaunchError> {
+        let stage = BootStage { load_id, delay_s: delay_s.max(0.0), powered_on_s: None };
+        self.stages.push(stage).map_err(|_| LaunchError::TooManyStages)
+    }
+
+    /// Adds a load that must stay off during the RF silence.
+    pub fn add_transmitter(&mut self, load_id: LoadId) -> Result<(), LaunchError> {
+        if self.is_transmitter(load_id) {
+            return Ok(());
+        }
+        self.transmitters.push(load_id).map_err(|_| LaunchError::TooManyTransmitters)
+    }
+
+    pub fn insert_rbf_pin(&mut self) {
+        self.rbf_pin_inserted = true;
+    }
+
+    pub fn remove_rbf_pin(&mut self) {
+        self.rbf_pin_inserted = false;
+    }
+
+    /// Switch plunger state, e.g. a switch that sticks when the deployer opens. Ignored after separation.
+    pub fn set_separation_switch(&mut self, index: usize, depressed: bool) -> Result<(), LaunchError> {
+        let switch = self.separation_switches.get_mut(index).ok_or(LaunchError::UnknownSwitch)?;
+        *switch = depressed;
+        Ok(())
+    }
+
+    pub fn get_phase(&self) -> LaunchPhase {
+        self.phase
+    }
+
+    pub fn is_rbf_pin_inserted(&self) -> bool {
+        self.rbf_pin_inserted
+    }
+
+    pub fn get_separation_switches(&self) -> &[bool] {
+        &self.separation_switches
+    }
+
+    /// True while any inhibit holds the battery off the bus.
+    pub fn is_inhibited(&self) -> bool {
+        self.separation_time_s.is_none() && (self.rbf_pin_inserted || self.separation_switches.iter().any(|depressed| *depressed))
+    }
+
+    pub fn is_operational(&self) -> bool {
+        self.phase == LaunchPhase::Operational
+    }
+
+    pub fn get_separation_time_s(&self) -> Option<f64> {
+        self.separation_time_s
+    }
+
+    pub fn get_rf_silence_s(&self) -> f64 {
+        self.rf_silence_s
+    }
+
+    /// Earliest time a transmitter may be switched on, once separated.
+    pub fn get_rf_silence_end_s(&self) -> Option<f64> {
+        self.separation_time_s.map(|separation_time_s| separation_time_s + self.rf_silence_s)
+    }
+
+    pub fn get_transmitters(&self) -> &[LoadId] {
+        &self.transmitters
+    }
+
+    pub fn is_transmitter(&self, load_id: LoadId) -> bool {
+        self.transmitters.contains(&load_id)
+    }
+
+    pub fn get_stages(&self) -> &[BootStage] {
+        &self.stages
+    }
+
+    // Every boot stage must be a PDU load
+    pub(crate) fn validate(&self, pdu: &PowerDistributionUnit) -> Result<(), LaunchError> {
+        if self.stages.iter().any(|stage| pdu.get_load(stage.load_id).is_none()) {
+            return Err(LaunchError::UnknownLoad);
+        }
+        Ok(())
+    }
+
+    /// Holds the PDU dark while inhibited, then schedules the boot stages due before `end_s` and keeps the
+    /// transmitters off during the RF silence. Returns the separation time on the step that detects it.
+    pub(crate) fn update(&mut self, start_s: f64, end_s: f64, pdu: &mut PowerDistributionUnit) -> Option<f64> {
+        let mut separated_s = None;
+        if self.separation_time_s.is_none() {
+            let ejected = self.scheduled_separation_s.filter(|time_s| *time_s <= start_s);
+            if ejected.is_some() {
+                self.separation_switches.iter_mut().for_each(|depressed| *depressed = false);
+            }
+            if self.is_inhibited() {
+                // Unpowered, the PDU loses its queued commands too
+                for index in 0..pdu.loads.len() {
+                    let load_id = pdu.loads[index].get_id();
+                    pdu.loads[index].turn_off();
+                    pdu.cancel_schedule(load_id);
+                }
+                self.phase = if self.rbf_pin_inserted { LaunchPhase::Integration } else { LaunchPhase::Stowed };
+                return None;
+            }
+            let separation_time_s = ejected.unwrap_or(start_s);
+            self.separation_time_s = Some(separation_time_s);
+            self.phase = LaunchPhase::Booting;
+            separated_s = Some(separation_time_s);
+        }
+        let Some(silence_end_s) = self.get_rf_silence_end_s() else {
+            return separated_s;
+        };
+        if self.phase == LaunchPhase::Operational {
+            return separated_s;
+        }
+        if start_s < silence_end_s {
+            for load_id in self.transmitters.iter().copied() {
+                let _ = pdu.switch_load(load_id, false); // Transmitters the PDU does not have are fine
+                pdu.cancel_schedule(load_id);
+            }
+        }
+        let separation_time_s = silence_end_s - self.rf_silence_s;
+        for index in 0..self.stages.len() {
+            let stage = self.stages[index];
+            if stage.powered_on_s.is_some() {
+                continue;
+            }
+            let mut on_s = separation_time_s + stage.delay_s;
+            if self.is_transmitter(stage.load_id) {
+                on_s = on_s.max(silence_end_s);
+            }
+            let on_s = on_s.max(start_s);
+            if on_s < end_s && pdu.schedule_switch(ScheduledSwitch { time_s: on_s, load_id: stage.load_id, on: true }).is_ok() {
+                self.stages[index].powered_on_s = Some(on_s);
+            }
+        }
+        if start_s >= silence_end_s && self.stages.iter().all(|stage| stage.powered_on_s.is_some_and(|on_s| on_s <= start_s)) {
+            self.phase = LaunchPhase::Operational;
+        }
+        separated_s
+    }
+}
@@ -0,0 +1,131 @@
+// Latching current limiter (LCL) protecting one PDU load channel.
+// The channel opens once its current stays above the limit for the trip time. Removing power also
+// clears a single-event latch-up (SEL), so an auto-retry usually brings the load back healthy.
+use crate::consts::{
+    LCL_DEFAULT_LIMIT_FACTOR, LCL_DEFAULT_MAX_RETRIES, LCL_DEFAULT_RETRY_DELAY_S, LCL_DEFAULT_TRIP_TIME_S,
+    LCL_SIZING_BUS_VOLTAGE_V,
+};
+use crate::enums::LclRecovery;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatchingCurrentLimiter {
+    current_limit_a: f64,
+    trip_time_s: f64,
+    recovery: LclRecovery,
+    is_tripped: bool,
+    trip_count: u32,          // Trips since power-on, never reset
+    retries: u32,             // Auto-retries since the last command
+    over_current_time_s: f64, // Time spent above the limit before tripping
+    open_time_s: f64,         // Time since the last trip
+    latch_up_current_a: f64,  // Extra current drawn by an injected latch-up
+    bus_voltage_v: f64,
+}
+
+impl LatchingCurrentLimiter {
+    pub fn new(current_limit_a: f64, trip_time_s: f64, recovery: LclRecovery) -> Self {
+        LatchingCurrentLimiter {
+            current_limit_a,
+            trip_time_s,
+            recovery,
+            is_tripped: false,
+            trip_count: 0,
+            retries: 0,
+            over_current_time_s: 0.0,
+            open_time_s: 0.0,
+            latch_up_current_a: 0.0,
+            bus_voltage_v: LCL_SIZING_BUS_VOLTAGE_V,
+        }
+    }
+
+    /// Default limiter for a load of `power_consumption_w`: twice its nominal current, 10 ms trip, auto-retry.
+    pub fn for_load(power_consumption_w: f64) -> Self {
+        LatchingCurrentLimiter::for_load_at(power_consumption_w, LCL_SIZING_BUS_VOLTAGE_V)
+    }
+
+    /// [`LatchingCurrentLimiter::for_load`] for a channel on a rail at `voltage_v`.
+    pub fn for_load_at(power_consumption_w: f64, voltage_v: f64) -> Self {
+        let mut lcl = LatchingCurrentLimiter::new(
+            LCL_DEFAULT_LIMIT_FACTOR * power_consumption_w / voltage_v,
+            LCL_DEFAULT_TRIP_TIME_S,
+            LclRecovery::AutoRetry { retry_delay_s: LCL_DEFAULT_RETRY_DELAY_S, max_retries: LCL_DEFAULT_MAX_RETRIES },
+        );
+        lcl.bus_voltage_v = voltage_v;
+        lcl
+    }
+
+    pub fn get_current_limit_a(&self) -> f64 {
+        self.current_limit_a
+    }
+
+    pub fn get_recovery(&self) -> LclRecovery {
+        self.recovery
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.is_tripped
+    }
+
+    pub fn get_trip_count(&self) -> u32 {
+        self.trip_count
+    }
+
+    pub fn get_latch_up_current_a(&self) -> f64 {
+        self.latch_up_current_a
+    }
+
+    /// Power the channel passes on top of the load's nominal draw, capped at the limit.
+    pub fn get_extra_power_w(&self, nominal_power_w: f64) -> f64 {
+        let limit_w = self.current_limit_a * self.bus_voltage_v;
+        (nominal_power_w + self.latch_up_current_a * self.bus_voltage_v).min(limit_w.max(nominal_power_w)) - nominal_power_w
+    }
+
+    /// Moves the limiter to a channel at `voltage_v`, keeping the power it lets through.
+    pub fn rescale_to(&mut self, voltage_v: f64) {
+        self.current_limit_a *= self.bus_voltage_v / voltage_v;
+        self.bus_voltage_v = voltage_v;
+    }
+
+    pub fn inject_latch_up(&mut self, extra_current_a: f64) {
+        self.latch_up_current_a = extra_current_a;
+    }
+
+    /// Command: closes a tripped channel and re-arms auto-retry.
+    pub fn reset(&mut self) {
+        self.is_tripped = false;
+        self.retries = 0;
+        self.over_current_time_s = 0.0;
+    }
+
+    /// Advances the limiter by `duration_s` with the channel drawing `nominal_power_w` from `bus_voltage_v`.
+    /// Returns true if the channel tripped during this update.
+    pub fn update(&mut self, nominal_power_w: f64, bus_voltage_v: f64, duration_s: f64) -> bool {
+        self.bus_voltage_v = bus_voltage_v.max(f64::EPSILON);
+        if self.is_tripped {
+            self.open_time_s += duration_s;
+            if let LclRecovery::AutoRetry { retry_delay_s, max_retries } = self.recovery
+                && self.open_time_s >= retry_delay_s
+                && self.retries < max_retries
+            {
+                self.retries += 1;
+                self.is_tripped = false;
+                self.over_current_time_s = 0.0;
+            }
+            return false;
+        }
+
+        let current_a = nominal_power_w / self.bus_voltage_v + self.latch_up_current_a;
+        if current_a <= self.current_limit_a {
+            self.over_current_time_s = 0.0;
+            return false;
+        }
+        self.over_current_time_s += duration_s;
+        if self.over_current_time_s < self.trip_time_s {
+            return false;
+        }
+        self.is_tripped = true;
+        self.trip_count += 1;
+        self.open_time_s = 0.0;
+        self.latch_up_current_a = 0.0; // Power removed, the latch-up is gone
+        true
+    }
+}
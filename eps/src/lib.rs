@@ -0,0 +1,104 @@
+//! CubeSat Electrical Power System (EPS) model.
+//!
+//! Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars.
+//! The crate is `no_std` and allocation-free so the same model runs on the nRF52840 firmware
+//! and on the host, where it can be exercised with `cargo test`.
+#![no_std]
+
+pub mod ageing;
+pub mod attitude;
+pub mod battery;
+pub mod battery_thermal;
+pub mod cell_pack;
+pub mod consts;
+pub mod deployment;
+pub mod enums;
+pub mod eps;
+pub mod equivalent_circuit;
+pub mod events;
+pub mod fault_injection;
+pub mod faults;
+pub mod launch;
+pub mod lcl;
+pub mod load_id;
+pub mod load_profile;
+pub mod mode_config;
+pub mod mode_manager;
+pub mod orbit;
+pub mod pdu;
+pub mod radiation;
+pub mod rail;
+pub mod rainflow;
+pub mod regulator;
+pub mod shedding;
+pub mod solar_cell;
+pub mod solar_panel;
+pub mod stepper;
+pub mod vector;
+
+pub use ageing::BatteryAgeing;
+pub use attitude::{Attitude, AttitudeMode};
+pub use battery::Battery;
+pub use battery_thermal::BatteryThermal;
+pub use cell_pack::{Cell, CellPack, CellTelemetry};
+pub use deployment::{BurnAttempt, DeploymentChannel, DeploymentSequencer};
+pub use enums::{
+    BatteryFault, BatteryState, CellFailure, CellTechnology, CrossingKind, CubeSatFormFactor, DeploymentError, DeploymentFailure,
+    DeploymentPhase, FaultInjectionError, FaultTrigger, InjectedFault, LaunchError, LaunchPhase, LclRecovery, LoadSetting,
+    ModeConfigError, MpptAlgorithm, PackError, PduError, PowerRail, RegistryError, RegulatorKind, SatelliteOperationalMode, Sensor,
+    SheddingAction, SheddingError, SheddingReason, TransitionReason,
+};
+pub use eps::EPS;
+pub use equivalent_circuit::EquivalentCircuit;
+pub use events::{EpsEvent, EventSink, NullSink, StepRecord};
+pub use fault_injection::{FaultInjector, FaultResponse, InjectionRecord, ScheduledFault};
+pub use faults::{BatteryFaultLimits, FaultLog, LatchedFault};
+pub use launch::{BootStage, LaunchSequencer};
+pub use lcl::LatchingCurrentLimiter;
+pub use load_id::{LoadId, LoadRegistry};
+pub use load_profile::{Inrush, LoadProfile, PowerDemand, ScheduledSwitch};
+pub use mode_config::{ModeLoadRule, ModeLoadTable};
+pub use mode_manager::{ModeInputs, ModeManager, ModeThresholds, ModeTransition};
+pub use orbit::Orbit;
+pub use pdu::{Load, LoadTelemetry, PowerDistributionUnit};
+pub use radiation::{RadiationDegradation, RemainingFactors};
+pub use rail::{DcDcConverter, Rail, RailDemand, RailTelemetry};
+pub use rainflow::RainflowCounter;
+pub use regulator::{ChargeRegulator, MpptTracker};
+pub use shedding::{LoadSheddingPolicy, SheddingDecision, ShedTier};
+pub use solar_cell::{PvString, SolarCell};
+pub use solar_panel::SolarPanel;
+pub use stepper::{AdaptiveStep, AdaptiveStepper, Crossing};
+pub use vector::Vector3;
+
+/// Fixed-capacity identifier for panels, batteries and loads.
+pub type Id = heapless::String<{ consts::ID_MAX_LEN }>;
+
+/// Builds an [`Id`], truncating names longer than [`consts::ID_MAX_LEN`] bytes.
+pub(crate) fn make_id(name: &str) -> Id {
+    let mut id = Id::new();
+    for c in name.chars() {
+        if id.push(c).is_err() {
+            break;
+        }
+    }
+    id
+}
+
+/// Piecewise-linear lookup in a table sorted by `x`, held flat outside its range.
+pub(crate) fn interpolate(table: &[(f64, f64)], x: f64) -> f64 {
+    let Some(first) = table.first() else {
+        return 0.0;
+    };
+    if x <= first.0 {
+        return first.1;
+    }
+    for pair in table.windows(2) {
+        let ((x_a, y_a), (x_b, y_b)) = (pair[0], pair[1]);
+        if x <= x_b {
+            let t = if x_b > x_a { (x - x_a) / (x_b - x_a) } else { 1.0 };
+            return y_a + t * (y_b - y_a);
+        }
+    }
+    table.last().map_or(first.1, |last| last.1)
+}
@@ -0,0 +1,65 @@
+// Compact load identifiers shared by the flight software and the ground station.
+// A `LoadId` is one byte, so it goes into telecommands and telemetry as is; the registry
+// turns it back into a name for operators.
+use heapless::Vec;
+
+use crate::consts::{MAX_LOADS, STANDARD_LOAD_NAMES};
+use crate::enums::RegistryError;
+use crate::{Id, make_id};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoadId(pub u8);
+
+impl LoadId {
+    // Standard ID space, see `STANDARD_LOAD_NAMES`. Mission-specific loads use the remaining values.
+    pub const OBC: LoadId = LoadId(0);
+    pub const COM_RX: LoadId = LoadId(1);
+    pub const COM_TX: LoadId = LoadId(2);
+    pub const PAYLOAD_CAM: LoadId = LoadId(3);
+    pub const PAYLOAD_TX: LoadId = LoadId(4);
+    pub const HEATERS: LoadId = LoadId(5);
+    pub const ADCS: LoadId = LoadId(6);
+}
+
+/// Maps load IDs to human-readable names.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadRegistry {
+    entries: Vec<(LoadId, Id), MAX_LOADS>,
+}
+
+impl LoadRegistry {
+    pub fn new() -> Self {
+        LoadRegistry { entries: Vec::new() }
+    }
+
+    /// The standard loads, as used by the default mode configuration.
+    pub fn standard() -> Self {
+        let mut registry = LoadRegistry::new();
+        for (id, name) in STANDARD_LOAD_NAMES {
+            let _ = registry.register(id, name); // Unique and within capacity
+        }
+        registry
+    }
+
+    pub fn register(&mut self, id: LoadId, name: &str) -> Result<(), RegistryError> {
+        if self.get_name(id).is_some() {
+            return Err(RegistryError::DuplicateId);
+        }
+        if self.get_id(name).is_some() {
+            return Err(RegistryError::DuplicateName);
+        }
+        self.entries.push((id, make_id(name))).map_err(|_| RegistryError::Full)
+    }
+
+    pub fn get_name(&self, id: LoadId) -> Option<&str> {
+        self.entries.iter().find(|(entry_id, _)| *entry_id == id).map(|(_, name)| name.as_str())
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<LoadId> {
+        self.entries.iter().find(|(_, entry_name)| entry_name == name).map(|(id, _)| *id)
+    }
+
+    pub fn get_entries(&self) -> &[(LoadId, Id)] {
+        &self.entries
+    }
+}
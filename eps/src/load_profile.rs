@@ -0,0 +1,82 @@
+// How a switched-on load draws power over time. Periodic profiles are anchored to mission elapsed time,
+// so a beacon keeps its phase across steps; the inrush surge follows each switch-on.
+use crate::load_id::LoadId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LoadProfile {
+    /// Draws its nominal power the whole time it is on.
+    #[default]
+    Constant,
+    /// Nominal power for `on_s` out of every `period_s`, `standby_w` the rest of the time (e.g. a camera duty cycle).
+    DutyCycle { period_s: f64, on_s: f64, offset_s: f64, standby_w: f64 },
+    /// Bursts of `pulse_w` lasting `pulse_s` every `period_s`, nominal power in between (e.g. transmit bursts).
+    Pulsed { period_s: f64, pulse_s: f64, offset_s: f64, pulse_w: f64 },
+}
+
+impl LoadProfile {
+    /// Energy drawn between two mission times by a load of `nominal_w`.
+    pub fn get_energy_ws(&self, nominal_w: f64, start_s: f64, end_s: f64) -> f64 {
+        let (low_w, high_w) = self.get_levels_w(nominal_w);
+        let high_s = self.get_high_time_s(end_s) - self.get_high_time_s(start_s);
+        low_w * (end_s - start_s) + (high_w - low_w) * high_s
+    }
+
+    /// Highest power drawn between two mission times, or at `start_s` if they coincide.
+    pub fn get_peak_w(&self, nominal_w: f64, start_s: f64, end_s: f64) -> f64 {
+        let (low_w, high_w) = self.get_levels_w(nominal_w);
+        let reaches_high = if end_s > start_s {
+            self.get_high_time_s(end_s) - self.get_high_time_s(start_s) > 0.0
+        } else {
+            self.get_high_time_s(start_s + 1e-9) > self.get_high_time_s(start_s)
+        };
+        if reaches_high { high_w.max(low_w) } else { low_w }
+    }
+
+    // Power outside and inside the periodic window
+    fn get_levels_w(&self, nominal_w: f64) -> (f64, f64) {
+        match *self {
+            LoadProfile::Constant => (nominal_w, nominal_w),
+            LoadProfile::DutyCycle { standby_w, .. } => (standby_w, nominal_w),
+            LoadProfile::Pulsed { pulse_w, .. } => (nominal_w, pulse_w),
+        }
+    }
+
+    // Time spent inside the periodic window since mission time zero
+    fn get_high_time_s(&self, time_s: f64) -> f64 {
+        let (period_s, window_s, offset_s) = match *self {
+            LoadProfile::Constant => return time_s,
+            LoadProfile::DutyCycle { period_s, on_s, offset_s, .. } => (period_s, on_s, offset_s),
+            LoadProfile::Pulsed { period_s, pulse_s, offset_s, .. } => (period_s, pulse_s, offset_s),
+        };
+        if period_s <= 0.0 {
+            return 0.0;
+        }
+        let window_s = window_s.clamp(0.0, period_s);
+        let periods = libm::floor((time_s - offset_s) / period_s);
+        let into_period_s = time_s - offset_s - periods * period_s;
+        periods * window_s + into_period_s.min(window_s)
+    }
+}
+
+/// Surge drawn right after switch-on, e.g. while input capacitors charge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inrush {
+    pub peak_w: f64,      // Drawn instead of the profile
+    pub duration_s: f64,
+}
+
+/// Average and peak power over a step. PDU totals add the per-load peaks as if they coincided,
+/// as a worst-case power budget does.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PowerDemand {
+    pub average_w: f64,
+    pub peak_w: f64,
+}
+
+/// Time-tagged switch command, executed by the PDU when mission time reaches `time_s`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduledSwitch {
+    pub time_s: f64,
+    pub load_id: LoadId,
+    pub on: bool,
+}
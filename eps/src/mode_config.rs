@@ -0,0 +1,68 @@
+// Declarative mode-to-load configuration. Page 12, Section 1.3: Mode Configuration.
+// Each mode starts from a baseline where critical loads are on and the rest are off; rules then set
+// individual loads on, off or don't-care. Rules are checked against the PDU when the table is built,
+// so a rule for a load this mission does not fly is an error up front rather than a silently ignored switch.
+// The table is keyed by load ID and looked up when applied, so loads added to the PDU later are covered too.
+use crate::enums::{LoadSetting, ModeConfigError, SatelliteOperationalMode};
+use crate::load_id::LoadId;
+use crate::pdu::PowerDistributionUnit;
+
+pub type ModeLoadRule = (SatelliteOperationalMode, LoadId, LoadSetting);
+
+const MODE_COUNT: usize = SatelliteOperationalMode::ALL.len();
+const LOAD_ID_COUNT: usize = u8::MAX as usize + 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeLoadTable {
+    settings: [[Option<LoadSetting>; LOAD_ID_COUNT]; MODE_COUNT], // None: baseline, indexed by load ID
+}
+
+impl ModeLoadTable {
+    /// Builds the table, rejecting rules for unknown loads and rules contradicting an earlier one.
+    pub fn new(rules: &[ModeLoadRule], pdu: &PowerDistributionUnit) -> Result<Self, ModeConfigError> {
+        let mut table = ModeLoadTable::baseline();
+        for (rule, (mode, load_id, setting)) in rules.iter().enumerate() {
+            if pdu.get_load(*load_id).is_none() {
+                return Err(ModeConfigError::UnknownLoad { rule });
+            }
+            let entry = &mut table.settings[mode.index()][load_id.0 as usize];
+            if entry.is_some_and(|existing| existing != *setting) {
+                return Err(ModeConfigError::ConflictingRule { rule });
+            }
+            *entry = Some(*setting);
+        }
+        Ok(table)
+    }
+
+    /// Critical loads on and everything else off, in every mode.
+    pub fn baseline() -> Self {
+        ModeLoadTable { settings: [[None; LOAD_ID_COUNT]; MODE_COUNT] }
+    }
+
+    /// Like [`ModeLoadTable::new`], but not checked against a PDU: a rule for a load the PDU does not have
+    /// takes effect once the load is added. Later rules override earlier ones.
+    pub fn from_rules(rules: &[ModeLoadRule]) -> Self {
+        let mut table = ModeLoadTable::baseline();
+        for (mode, load_id, setting) in rules {
+            table.settings[mode.index()][load_id.0 as usize] = Some(*setting);
+        }
+        table
+    }
+
+    /// Setting of load `load_id` in `mode`, baseline included.
+    pub fn get_setting(&self, mode: SatelliteOperationalMode, load_id: LoadId, is_critical: bool) -> LoadSetting {
+        let explicit = self.settings[mode.index()][load_id.0 as usize];
+        explicit.unwrap_or(if is_critical { LoadSetting::On } else { LoadSetting::Off })
+    }
+
+    /// Switches the PDU loads as configured for `mode`. Burn wires are left alone while their burn is under way.
+    pub fn apply(&self, mode: SatelliteOperationalMode, pdu: &mut PowerDistributionUnit) {
+        for load in pdu.loads.iter_mut().filter(|load| !load.burning) {
+            match self.get_setting(mode, load.get_id(), load.is_critical()) {
+                LoadSetting::On => load.turn_on(),
+                LoadSetting::Off => load.turn_off(),
+                LoadSetting::DontCare => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Autonomous operational mode state machine. Page 12, Section 1.3: power modes.
+// Entering SafeMode is immediate; every other autonomous transition waits for the minimum dwell time.
+// SafeMode is left only by ground command or after a sustained positive energy balance.
+use heapless::Vec;
+
+use crate::consts::{
+    MAX_MODE_TRANSITIONS, MODE_MIN_DWELL_S, PAYLOAD_EXIT_SOC, SAFE_MODE_ENTRY_SOC, SAFE_MODE_EXIT_SOC,
+    SAFE_MODE_RECOVERY_TIME_S,
+};
+use crate::enums::{SatelliteOperationalMode, TransitionReason};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeThresholds {
+    pub safe_entry_soc: f64,          // Percent
+    pub safe_entry_bus_voltage_v: f64,
+    pub safe_on_battery_fault: bool,  // A fault blocking charge or discharge forces SafeMode
+    pub safe_exit_soc: f64,           // Percent
+    pub safe_recovery_time_s: f64,    // SafeMode time with a positive energy balance before leaving it
+    pub payload_exit_soc: f64,        // Percent
+    pub min_dwell_s: f64,
+}
+
+impl Default for ModeThresholds {
+    /// Bus voltage guard disabled; [`crate::EPS::new`] sets it from the battery.
+    fn default() -> Self {
+        ModeThresholds {
+            safe_entry_soc: SAFE_MODE_ENTRY_SOC,
+            safe_entry_bus_voltage_v: 0.0,
+            safe_on_battery_fault: true,
+            safe_exit_soc: SAFE_MODE_EXIT_SOC,
+            safe_recovery_time_s: SAFE_MODE_RECOVERY_TIME_S,
+            payload_exit_soc: PAYLOAD_EXIT_SOC,
+            min_dwell_s: MODE_MIN_DWELL_S,
+        }
+    }
+}
+
+/// What the guards look at, sampled at the start of a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeInputs {
+    pub time_s: f64,
+    pub soc_percentage: f64,
+    pub bus_voltage_v: f64,
+    pub battery_fault: bool,  // A latched fault blocks charging or discharging
+    pub sunlit: Option<bool>, // None without an orbit: sunlight is then commanded through the mode
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeTransition {
+    pub time_s: f64,
+    pub from: SatelliteOperationalMode,
+    pub to: SatelliteOperationalMode,
+    pub reason: TransitionReason,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModeManager {
+    thresholds: ModeThresholds,
+    mode_entered_at_s: f64,
+    safe_energy_balance_wh: f64, // Net energy since entering SafeMode
+    log: Vec<ModeTransition, MAX_MODE_TRANSITIONS>,
+    transition_count: u32,       // Including entries dropped from the log
+}
+
+impl ModeManager {
+    pub fn new(thresholds: ModeThresholds) -> Self {
+        ModeManager { thresholds, mode_entered_at_s: 0.0, safe_energy_balance_wh: 0.0, log: Vec::new(), transition_count: 0 }
+    }
+
+    pub fn get_thresholds(&self) -> &ModeThresholds {
+        &self.thresholds
+    }
+
+    pub fn set_thresholds(&mut self, thresholds: ModeThresholds) {
+        self.thresholds = thresholds;
+    }
+
+    pub fn get_log(&self) -> &[ModeTransition] {
+        &self.log
+    }
+
+    pub fn get_transition_count(&self) -> u32 {
+        self.transition_count
+    }
+
+    pub fn get_time_in_mode_s(&self, time_s: f64) -> f64 {
+        time_s - self.mode_entered_at_s
+    }
+
+    pub fn get_safe_energy_balance_wh(&self) -> f64 {
+        self.safe_energy_balance_wh
+    }
+
+    /// Accumulates the net energy of a step spent in `mode`.
+    pub fn record_energy(&mut self, mode: SatelliteOperationalMode, net_energy_wh: f64) {
+        if mode == SatelliteOperationalMode::SafeMode {
+            self.safe_energy_balance_wh += net_energy_wh;
+        }
+    }
+
+    /// The transition the guards call for from `mode`, if any.
+    pub fn evaluate(&self, mode: SatelliteOperationalMode, inputs: &ModeInputs) -> Option<(SatelliteOperationalMode, TransitionReason)> {
+        let thresholds = &self.thresholds;
+        let nominal = match inputs.sunlit {
+            Some(false) => SatelliteOperationalMode::NominalEclipse,
+            _ => SatelliteOperationalMode::NominalSunlit,
+        };
+        let dwell_elapsed = self.get_time_in_mode_s(inputs.time_s) >= thresholds.min_dwell_s;
+
+        if mode == SatelliteOperationalMode::SafeMode {
+            let recovered = dwell_elapsed
+                && !inputs.battery_fault
+                && inputs.soc_percentage >= thresholds.safe_exit_soc
+                && inputs.bus_voltage_v >= thresholds.safe_entry_bus_voltage_v
+                && self.get_time_in_mode_s(inputs.time_s) >= thresholds.safe_recovery_time_s
+                && self.safe_energy_balance_wh > 0.0;
+            return recovered.then_some((nominal, TransitionReason::SafeModeRecovered));
+        }
+
+        if inputs.battery_fault && thresholds.safe_on_battery_fault {
+            return Some((SatelliteOperationalMode::SafeMode, TransitionReason::BatteryFault));
+        }
+        if inputs.soc_percentage < thresholds.safe_entry_soc {
+            return Some((SatelliteOperationalMode::SafeMode, TransitionReason::LowSoc));
+        }
+        if inputs.bus_voltage_v < thresholds.safe_entry_bus_voltage_v {
+            return Some((SatelliteOperationalMode::SafeMode, TransitionReason::LowBusVoltage));
+        }
+
+        match (mode, inputs.sunlit) {
+            (SatelliteOperationalMode::PayloadOperation, _) if dwell_elapsed && inputs.soc_percentage < thresholds.payload_exit_soc => {
+                Some((nominal, TransitionReason::PayloadLowSoc))
+            }
+            // Sunlight changes follow the orbit without waiting for the dwell time
+            (SatelliteOperationalMode::NominalSunlit, Some(false)) => Some((nominal, TransitionReason::EnteredEclipse)),
+            (SatelliteOperationalMode::NominalEclipse, Some(true)) => Some((nominal, TransitionReason::LeftEclipse)),
+            _ => None,
+        }
+    }
+
+    pub fn record_transition(&mut self, transition: ModeTransition) {
+        if self.log.is_full() {
+            self.log.remove(0);
+        }
+        let _ = self.log.push(transition); // Room was made above
+        self.transition_count += 1;
+        self.mode_entered_at_s = transition.time_s;
+        self.safe_energy_balance_wh = 0.0;
+    }
+}
+
+impl Default for ModeManager {
+    fn default() -> Self {
+        ModeManager::new(ModeThresholds::default())
+    }
+}
@@ -0,0 +1,126 @@
+// Circular orbit model deciding sunlight or umbra from geometry.
+// Beta angle and eclipse fraction relations from NASA's On-Orbit_Thermal_Environments_TFAWS_2014.pdf
+use core::f64::consts::PI;
+
+use crate::consts::{EARTH_MU_KM3_S2, EARTH_OBLIQUITY_DEG, EARTH_RADIUS_KM};
+use crate::vector::Vector3;
+
+#[derive(Debug, Clone)]
+pub struct Orbit {
+    altitude_km: f64,
+    inclination_deg: f64,
+    raan_deg: f64,           // Right ascension of the ascending node
+    beta_angle_deg: f64,     // Angle between the sun vector and the orbit plane
+    epoch_s: f64,            // Mission elapsed time of an ascending node crossing
+}
+
+impl Orbit {
+    /// The in-plane component of the sun vector points at the ascending node, so orbit noon
+    /// happens at `epoch_s` and the middle of the eclipse (if any) half a period later.
+    pub fn new(altitude_km: f64, inclination_deg: f64, raan_deg: f64, beta_angle_deg: f64, epoch_s: f64) -> Self {
+        Orbit {
+            altitude_km,
+            inclination_deg,
+            raan_deg,
+            beta_angle_deg: beta_angle_deg.clamp(-90.0, 90.0),
+            epoch_s,
+        }
+    }
+
+    /// Beta angle of an orbit plane for a given sun ecliptic longitude (0 deg at the March equinox).
+    pub fn beta_angle_for_season(inclination_deg: f64, raan_deg: f64, sun_ecliptic_longitude_deg: f64) -> f64 {
+        let normal = orbit_normal(inclination_deg.to_radians(), raan_deg.to_radians());
+        let (sin_l, cos_l) = libm::sincos(sun_ecliptic_longitude_deg.to_radians());
+        let (sin_e, cos_e) = libm::sincos(EARTH_OBLIQUITY_DEG.to_radians());
+        let sun_eci = Vector3::new(cos_l, cos_e * sin_l, sin_e * sin_l);
+        libm::asin(normal.dot(&sun_eci).clamp(-1.0, 1.0)).to_degrees()
+    }
+
+    pub fn get_altitude_km(&self) -> f64 {
+        self.altitude_km
+    }
+
+    pub fn get_beta_angle_deg(&self) -> f64 {
+        self.beta_angle_deg
+    }
+
+    pub fn get_semi_major_axis_km(&self) -> f64 {
+        EARTH_RADIUS_KM + self.altitude_km
+    }
+
+    pub fn get_period_s(&self) -> f64 {
+        let a = self.get_semi_major_axis_km();
+        2.0 * PI * libm::sqrt(a * a * a / EARTH_MU_KM3_S2)
+    }
+
+    pub fn get_orbit_normal_eci(&self) -> Vector3 {
+        orbit_normal(self.inclination_deg.to_radians(), self.raan_deg.to_radians())
+    }
+
+    /// Argument of latitude (angle from the ascending node) in radians at mission time `time_s`.
+    pub fn get_argument_of_latitude_rad(&self, time_s: f64) -> f64 {
+        let mean_motion_rad_s = 2.0 * PI / self.get_period_s();
+        libm::fmod(mean_motion_rad_s * (time_s - self.epoch_s), 2.0 * PI)
+    }
+
+    pub fn get_position_eci_km(&self, time_s: f64) -> Vector3 {
+        let (node, in_plane_normal) = self.in_plane_axes();
+        let (sin_u, cos_u) = libm::sincos(self.get_argument_of_latitude_rad(time_s));
+        (node * cos_u + in_plane_normal * sin_u) * self.get_semi_major_axis_km()
+    }
+
+    /// Unit vector along the orbital velocity (circular orbit, so perpendicular to the position).
+    pub fn get_velocity_direction_eci(&self, time_s: f64) -> Vector3 {
+        let (node, in_plane_normal) = self.in_plane_axes();
+        let (sin_u, cos_u) = libm::sincos(self.get_argument_of_latitude_rad(time_s));
+        node * -sin_u + in_plane_normal * cos_u
+    }
+
+    /// Unit vector from the Earth to the Sun. The orbit propagation is short compared to a
+    /// year, so the sun is held fixed with respect to the orbit plane.
+    pub fn get_sun_vector_eci(&self) -> Vector3 {
+        let (node, _) = self.in_plane_axes();
+        let (sin_b, cos_b) = libm::sincos(self.beta_angle_deg.to_radians());
+        node * cos_b + self.get_orbit_normal_eci() * sin_b
+    }
+
+    /// Cylindrical Earth shadow: umbra when behind the Earth and within one Earth radius of the
+    /// Earth-Sun line.
+    pub fn is_sunlit(&self, time_s: f64) -> bool {
+        let position = self.get_position_eci_km(time_s);
+        let sun = self.get_sun_vector_eci();
+        let along_sun_km = position.dot(&sun);
+        if along_sun_km >= 0.0 {
+            return true;
+        }
+        (position - sun * along_sun_km).norm() > EARTH_RADIUS_KM
+    }
+
+    /// Analytic fraction of the orbit spent in eclipse for the current beta angle.
+    pub fn get_eclipse_fraction(&self) -> f64 {
+        let r = self.get_semi_major_axis_km();
+        let beta_rad = self.beta_angle_deg.to_radians();
+        let beta_star_rad = libm::asin(EARTH_RADIUS_KM / r);
+        if libm::fabs(beta_rad) >= beta_star_rad {
+            return 0.0;
+        }
+        let h = self.altitude_km;
+        libm::acos(libm::sqrt(h * h + 2.0 * EARTH_RADIUS_KM * h) / (r * libm::cos(beta_rad))) / PI
+    }
+
+    // Unit vectors towards the ascending node and 90 deg ahead of it in the orbit plane
+    fn in_plane_axes(&self) -> (Vector3, Vector3) {
+        let (sin_i, cos_i) = libm::sincos(self.inclination_deg.to_radians());
+        let (sin_raan, cos_raan) = libm::sincos(self.raan_deg.to_radians());
+        (
+            Vector3::new(cos_raan, sin_raan, 0.0),
+            Vector3::new(-cos_i * sin_raan, cos_i * cos_raan, sin_i),
+        )
+    }
+}
+
+fn orbit_normal(inclination_rad: f64, raan_rad: f64) -> Vector3 {
+    let (sin_i, cos_i) = libm::sincos(inclination_rad);
+    let (sin_raan, cos_raan) = libm::sincos(raan_rad);
+    Vector3::new(sin_i * sin_raan, -sin_i * cos_raan, cos_i)
+}
@@ -0,0 +1,523 @@
+// Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars
+use heapless::Vec;
+
+use crate::consts::{LCL_SIZING_BUS_VOLTAGE_V, MAX_LOADS, MAX_RAILS, MAX_SCHEDULED_SWITCHES};
+use crate::enums::{PduError, PowerRail};
+use crate::lcl::LatchingCurrentLimiter;
+use crate::load_id::LoadId;
+use crate::load_profile::{Inrush, LoadProfile, PowerDemand, ScheduledSwitch};
+use crate::rail::{Rail, RailDemand, RailTelemetry};
+
+// A.k.a. subsystems/payloads
+#[derive(Debug, Clone)]
+pub struct Load {
+    id: LoadId,
+    power_consumption_w: f64,
+    is_critical: bool,        // Page 33, Section 4.2 (e.g. telecomm, attitude control etc.)
+    is_on: bool,              // Page 12, Section 1.3, constant power-ON components and ON/OFF controllable.
+    lcl: LatchingCurrentLimiter, // Over-current protection of the load's channel
+    shed_priority: u8,        // Non-critical loads with lower priority are shed first
+    profile: LoadProfile,     // Power drawn while on, relative to `power_consumption_w`
+    inrush: Option<Inrush>,
+    on_time_s: f64,           // Since the load was last powered up, as of the PDU's time
+    rail: PowerRail,          // Power is drawn from the battery bus through this rail's converter
+    stuck_on: bool,           // Channel switch welded closed
+    pub(crate) held_off: bool, // Its shedding tier is shed: switching on is ignored until the tier is restored
+    pub(crate) burning: bool,  // Burn wire during a deployment burn: shedding tiers and mode baselines leave it alone
+}
+
+impl Load {
+    pub fn new(id: LoadId, power_consumption_w: f64, is_critical: bool) -> Self {
+        Load {
+            id,
+            power_consumption_w,
+            is_critical,
+            is_on: false,
+            lcl: LatchingCurrentLimiter::for_load(power_consumption_w),
+            shed_priority: 0,
+            profile: LoadProfile::Constant,
+            inrush: None,
+            on_time_s: 0.0,
+            rail: PowerRail::Raw,
+            stuck_on: false,
+            held_off: false,
+            burning: false,
+        }
+    }
+
+    /// Moves the load to a regulated rail. Its current limiter, default or given to [`Load::with_lcl`],
+    /// is rescaled to the rail voltage; a limiter given afterwards is taken as is.
+    pub fn with_rail(mut self, rail: PowerRail) -> Self {
+        self.rail = rail;
+        self.lcl.rescale_to(rail.get_nominal_voltage_v().unwrap_or(LCL_SIZING_BUS_VOLTAGE_V));
+        self
+    }
+
+    pub fn get_rail(&self) -> PowerRail {
+        self.rail
+    }
+
+    pub fn with_profile(mut self, profile: LoadProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn get_profile(&self) -> &LoadProfile {
+        &self.profile
+    }
+
+    pub fn with_inrush(mut self, peak_w: f64, duration_s: f64) -> Self {
+        self.inrush = Some(Inrush { peak_w, duration_s });
+        self
+    }
+
+    pub fn get_inrush(&self) -> Option<&Inrush> {
+        self.inrush.as_ref()
+    }
+
+    pub fn with_shed_priority(mut self, shed_priority: u8) -> Self {
+        self.shed_priority = shed_priority;
+        self
+    }
+
+    pub fn get_shed_priority(&self) -> u8 {
+        self.shed_priority
+    }
+
+    pub fn with_lcl(mut self, lcl: LatchingCurrentLimiter) -> Self {
+        self.lcl = lcl;
+        self
+    }
+
+    pub fn get_lcl(&self) -> &LatchingCurrentLimiter {
+        &self.lcl
+    }
+
+    /// Switched on and not isolated by its current limiter.
+    pub fn is_powered(&self) -> bool {
+        self.is_on && !self.lcl.is_tripped()
+    }
+
+    pub fn get_id(&self) -> LoadId {
+        self.id
+    }
+
+    pub fn get_telemetry(&self) -> LoadTelemetry {
+        LoadTelemetry {
+            id: self.id,
+            is_on: self.is_on,
+            is_powered: self.is_powered(),
+            is_tripped: self.lcl.is_tripped(),
+            is_critical: self.is_critical,
+            trip_count: self.lcl.get_trip_count().min(u8::MAX as u32) as u8,
+        }
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.is_critical
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.is_on
+    }
+
+    /// No effect while held off by load shedding, unless a deployment burn is under way. A tripped current limiter
+    /// is left to its recovery; only a ground command re-closes it, see [`PowerDistributionUnit::switch_load`].
+    /// Powering up restarts the inrush.
+    pub fn turn_on(&mut self) {
+        if self.is_held() {
+            return;
+        }
+        if !self.is_powered() {
+            self.on_time_s = 0.0;
+        }
+        self.is_on = true;
+    }
+
+    fn is_held(&self) -> bool {
+        self.held_off && !self.stuck_on && !self.burning
+    }
+
+    // Whether turn_on would power the load: held-off loads ignore it and a tripped limiter stays open
+    fn can_switch_on(&self) -> bool {
+        !self.is_held() && !self.lcl.is_tripped()
+    }
+
+    // Ground command: also re-closes a tripped current limiter and re-arms its auto-retry
+    fn command_on(&mut self) {
+        if !self.is_on || self.lcl.is_tripped() {
+            self.on_time_s = 0.0;
+            self.lcl.reset();
+        }
+        self.is_on = true;
+    }
+
+    /// No effect on a channel stuck on.
+    pub fn turn_off(&mut self) {
+        if !self.stuck_on {
+            self.is_on = false;
+        }
+    }
+
+    pub fn is_stuck_on(&self) -> bool {
+        self.stuck_on
+    }
+
+    pub fn is_held_off(&self) -> bool {
+        self.held_off
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.burning
+    }
+
+    pub fn get_on_time_s(&self) -> f64 {
+        self.on_time_s
+    }
+
+    /// Nominal power while powered, latch-up current included. Profiles and inrush are left to
+    /// [`PowerDistributionUnit::get_total_demand_w`], which knows the time.
+    pub fn get_power_demand_w(&self) -> f64 {
+        if self.is_powered() {
+            self.power_consumption_w + self.lcl.get_extra_power_w(self.power_consumption_w)
+        } else {
+            0.0
+        }
+    }
+
+    // Energy and peak power between two mission times, powered up at `on_since_s`
+    fn get_segment_demand(&self, on_since_s: f64, start_s: f64, end_s: f64) -> (f64, f64) {
+        let mut energy_ws = 0.0;
+        let mut peak_w = f64::NEG_INFINITY;
+        let mut profile_start_s = start_s;
+        if let Some(inrush) = self.inrush {
+            let inrush_end_s = on_since_s + inrush.duration_s;
+            if start_s < inrush_end_s {
+                energy_ws += inrush.peak_w * (end_s.min(inrush_end_s) - start_s);
+                peak_w = inrush.peak_w;
+                profile_start_s = inrush_end_s;
+            }
+        }
+        if profile_start_s < end_s || peak_w == f64::NEG_INFINITY {
+            let profile_start_s = profile_start_s.min(end_s);
+            energy_ws += self.profile.get_energy_ws(self.power_consumption_w, profile_start_s, end_s);
+            peak_w = peak_w.max(self.profile.get_peak_w(self.power_consumption_w, profile_start_s, end_s));
+        }
+        (energy_ws, peak_w)
+    }
+}
+
+// Actual PDU
+#[derive(Debug, Clone)]
+pub struct PowerDistributionUnit {
+    pub(crate) loads: Vec<Load, MAX_LOADS>,
+    schedule: Vec<ScheduledSwitch, MAX_SCHEDULED_SWITCHES>, // Pending commands, in time order
+    time_s: f64,              // Mission elapsed time the loads' state refers to
+    rails: Vec<Rail, MAX_RAILS>, // One per PowerRail
+    bus_voltage_v: f64,       // As of the last current limiter update
+}
+
+impl Default for PowerDistributionUnit {
+    fn default() -> Self {
+        PowerDistributionUnit::new()
+    }
+}
+
+impl PowerDistributionUnit {
+    /// Starts with the standard rails, see [`Rail::standard`].
+    pub fn new() -> Self {
+        PowerDistributionUnit {
+            loads: Vec::new(),
+            schedule: Vec::new(),
+            time_s: 0.0,
+            rails: Rail::standard().into_iter().collect(),
+            bus_voltage_v: LCL_SIZING_BUS_VOLTAGE_V,
+        }
+    }
+
+    /// Replaces the rail of the same kind, e.g. with a different converter.
+    pub fn set_rail(&mut self, rail: Rail) {
+        match self.rails.iter_mut().find(|existing| existing.get_kind() == rail.get_kind()) {
+            Some(existing) => *existing = rail,
+            None => {
+                let _ = self.rails.push(rail); // At most one rail per kind
+            }
+        }
+    }
+
+    pub fn get_rails(&self) -> &[Rail] {
+        &self.rails
+    }
+
+    pub fn get_rail(&self, kind: PowerRail) -> Option<&Rail> {
+        self.rails.iter().find(|rail| rail.get_kind() == kind)
+    }
+
+    pub fn add_load(&mut self, load: Load) -> Result<(), PduError> {
+        if self.get_load(load.id).is_some() {
+            return Err(PduError::DuplicateLoad);
+        }
+        self.loads.push(load).map_err(|_| PduError::TooManyLoads)
+    }
+
+    pub fn get_loads(&self) -> &[Load] {
+        &self.loads
+    }
+
+    pub fn get_load(&self, load_id: LoadId) -> Option<&Load> {
+        self.loads.iter().find(|l| l.id == load_id)
+    }
+
+    // Page 12, Section 1.3 and page 34, Section 4.3: ability to toggle subsystems (loads) on/off
+    /// Ground command: switching a load on re-closes its tripped current limiter.
+    pub fn switch_load(&mut self, load_id: LoadId, new_state: bool) -> Result<(), PduError> {
+        if let Some(load) = self.loads.iter_mut().find(|l| l.id == load_id) {
+            if new_state {
+                load.command_on();
+            } else {
+                load.turn_off();
+            }
+            Ok(())
+        } else {
+            Err(PduError::LoadNotFound)
+        }
+    }
+
+    /// Queues a time-tagged switch command. Commands at the same time run in the order they were queued.
+    pub fn schedule_switch(&mut self, switch: ScheduledSwitch) -> Result<(), PduError> {
+        if self.get_load(switch.load_id).is_none() {
+            return Err(PduError::LoadNotFound);
+        }
+        let index = self.schedule.iter().position(|queued| queued.time_s > switch.time_s).unwrap_or(self.schedule.len());
+        self.schedule.insert(index, switch).map_err(|_| PduError::ScheduleFull)
+    }
+
+    /// Switches a load on for a window, e.g. COM_TX during a predicted ground pass.
+    pub fn schedule_window(&mut self, load_id: LoadId, start_s: f64, end_s: f64) -> Result<(), PduError> {
+        if end_s < start_s {
+            return Err(PduError::InvalidWindow);
+        }
+        if self.schedule.len() + 2 > self.schedule.capacity() {
+            return Err(PduError::ScheduleFull);
+        }
+        self.schedule_switch(ScheduledSwitch { time_s: start_s, load_id, on: true })?;
+        self.schedule_switch(ScheduledSwitch { time_s: end_s, load_id, on: false })
+    }
+
+    pub fn get_schedule(&self) -> &[ScheduledSwitch] {
+        &self.schedule
+    }
+
+    /// Drops every pending command for a load. Returns how many were dropped.
+    pub fn cancel_schedule(&mut self, load_id: LoadId) -> usize {
+        let pending = self.schedule.len();
+        self.schedule.retain(|switch| switch.load_id != load_id);
+        pending - self.schedule.len()
+    }
+
+    pub fn get_time_s(&self) -> f64 {
+        self.time_s
+    }
+
+    /// Moves the PDU clock forward: powered loads accumulate on-time and the commands due before
+    /// `time_s` are executed. Scheduled switch-ons go through [`Load::turn_on`], so they neither
+    /// override a shed tier nor re-close a tripped current limiter.
+    pub fn advance_to(&mut self, time_s: f64) {
+        let elapsed_s = (time_s - self.time_s).max(0.0);
+        for load in self.loads.iter_mut().filter(|load| load.is_powered()) {
+            load.on_time_s += elapsed_s;
+        }
+        while let Some(switch) = self.schedule.first().copied()
+            && switch.time_s < time_s
+        {
+            self.schedule.remove(0);
+            let Some(load) = self.loads.iter_mut().find(|load| load.id == switch.load_id) else {
+                continue;
+            };
+            if switch.on {
+                let was_powered = load.is_powered();
+                load.turn_on();
+                if !was_powered && load.is_powered() {
+                    load.on_time_s = time_s - switch.time_s.max(self.time_s);
+                }
+            } else {
+                load.turn_off();
+            }
+        }
+        self.time_s = self.time_s.max(time_s);
+    }
+
+    /// Single-event latch-up on a load: it draws `extra_current_a` until its channel is power-cycled.
+    pub fn inject_latch_up(&mut self, load_id: LoadId, extra_current_a: f64) -> Result<(), PduError> {
+        let load = self.loads.iter_mut().find(|l| l.id == load_id).ok_or(PduError::LoadNotFound)?;
+        load.lcl.inject_latch_up(extra_current_a);
+        Ok(())
+    }
+
+    /// Welds a load's channel switch closed: the load turns on and stays on whatever is commanded.
+    pub fn inject_stuck_on(&mut self, load_id: LoadId) -> Result<(), PduError> {
+        let load = self.loads.iter_mut().find(|l| l.id == load_id).ok_or(PduError::LoadNotFound)?;
+        load.stuck_on = true;
+        load.turn_on();
+        Ok(())
+    }
+
+    /// Advances every channel's current limiter and returns the loads whose channel tripped.
+    /// Channels on a regulated rail see the rail voltage instead of `bus_voltage_v`.
+    pub fn update_current_limiters(&mut self, bus_voltage_v: f64, duration_s: f64) -> Vec<LoadId, MAX_LOADS> {
+        self.bus_voltage_v = bus_voltage_v;
+        let mut tripped = Vec::new();
+        for load in self.loads.iter_mut().filter(|load| load.is_on) {
+            let voltage_v = load.rail.get_nominal_voltage_v().unwrap_or(bus_voltage_v);
+            if load.lcl.update(load.power_consumption_w, voltage_v, duration_s) {
+                let _ = tripped.push(load.id); // At most one entry per load
+            }
+        }
+        tripped
+    }
+
+    pub fn get_tripped_loads(&self) -> impl Iterator<Item = &Load> {
+        self.loads.iter().filter(|load| load.lcl.is_tripped())
+    }
+
+    pub fn get_total_trip_count(&self) -> u32 {
+        self.loads.iter().map(|load| load.lcl.get_trip_count()).sum()
+    }
+
+    pub fn get_telemetry(&self) -> Vec<LoadTelemetry, MAX_LOADS> {
+        self.loads.iter().map(|load| load.get_telemetry()).collect()
+    }
+
+    /// Average and peak demand on the battery bus over the next `duration_s` from the PDU's time,
+    /// following the load profiles, inrush surges, the commands scheduled within the step and the
+    /// rail converter losses.
+    pub fn get_total_demand_w(&self, duration_s: f64) -> PowerDemand {
+        self.get_rail_demand(duration_s).iter().fold(PowerDemand::default(), |total, rail| add_demand(total, rail.input))
+    }
+
+    /// [`PowerDistributionUnit::get_total_demand_w`] broken down per rail.
+    pub fn get_rail_demand(&self, duration_s: f64) -> Vec<RailDemand, MAX_RAILS> {
+        self.rails
+            .iter()
+            .map(|rail| {
+                let output = self
+                    .loads
+                    .iter()
+                    .filter(|load| load.rail == rail.get_kind())
+                    .fold(PowerDemand::default(), |total, load| add_demand(total, self.get_step_demand(load, duration_s)));
+                rail.get_demand(output)
+            })
+            .collect()
+    }
+
+    /// Present rail voltages, currents and losses.
+    pub fn get_rail_telemetry(&self) -> Vec<RailTelemetry, MAX_RAILS> {
+        self.rails
+            .iter()
+            .zip(self.get_rail_demand(0.0))
+            .map(|(rail, demand)| RailTelemetry::new(rail.get_kind(), rail.get_voltage_v(self.bus_voltage_v), self.bus_voltage_v, &demand))
+            .collect()
+    }
+
+    pub fn get_load_demand_w(&self, load_id: LoadId, duration_s: f64) -> Option<PowerDemand> {
+        self.get_load(load_id).map(|load| self.get_step_demand(load, duration_s))
+    }
+
+    /// Time the load is powered over the next `duration_s` from the PDU's time, scheduled commands included.
+    pub fn get_load_powered_s(&self, load_id: LoadId, duration_s: f64) -> Option<f64> {
+        let load = self.get_load(load_id)?;
+        let mut powered_s = 0.0;
+        self.for_each_powered_segment(load, duration_s, |_, _, segment_start_s, segment_end_s| powered_s += segment_end_s - segment_start_s);
+        Some(powered_s)
+    }
+
+    fn get_step_demand(&self, load: &Load, duration_s: f64) -> PowerDemand {
+        let mut energy_ws = 0.0;
+        let mut peak_w: f64 = 0.0;
+        self.for_each_powered_segment(load, duration_s, |on_since_s, latch_up_w, segment_start_s, segment_end_s| {
+            let (segment_energy_ws, segment_peak_w) = load.get_segment_demand(on_since_s, segment_start_s, segment_end_s);
+            energy_ws += segment_energy_ws + latch_up_w * (segment_end_s - segment_start_s);
+            peak_w = peak_w.max(segment_peak_w + latch_up_w);
+        });
+        let average_w = if duration_s > 0.0 { energy_ws / duration_s } else { peak_w };
+        PowerDemand { average_w, peak_w }
+    }
+
+    // Splits the step at the load's scheduled switches and passes each powered segment to `add_segment`
+    // as (powered up at, latch-up power, segment start, segment end)
+    fn for_each_powered_segment(&self, load: &Load, duration_s: f64, mut add_segment: impl FnMut(f64, f64, f64, f64)) {
+        let (start_s, end_s) = (self.time_s, self.time_s + duration_s.max(0.0));
+        let mut powered = load.is_powered();
+        let mut on_since_s = start_s - load.on_time_s;
+        let mut latch_up_w = load.lcl.get_extra_power_w(load.power_consumption_w); // Cleared by a power cycle
+        let mut segment_start_s = start_s;
+        let switches = self.schedule.iter().filter(|switch| switch.load_id == load.id && switch.time_s < end_s);
+        for switch in switches {
+            let switch_s = switch.time_s.max(start_s);
+            // Mirrors advance_to: held or tripped channels ignore a switch-on, welded ones a switch-off
+            if switch.on == powered || (switch.on && !load.can_switch_on()) || (!switch.on && load.stuck_on) {
+                continue;
+            }
+            if powered {
+                add_segment(on_since_s, latch_up_w, segment_start_s, switch_s);
+            } else {
+                on_since_s = switch_s;
+                latch_up_w = 0.0;
+            }
+            segment_start_s = switch_s;
+            powered = switch.on;
+        }
+        if powered {
+            add_segment(on_since_s, latch_up_w, segment_start_s, end_s);
+        }
+    }
+
+    // Page 12, Section 1.3: turn off non-critical loads if needed
+    pub fn shed_non_critical_loads(&mut self) -> f64 {
+        let mut shed_power = 0.0;
+        for load in self.loads.iter_mut() {
+            if load.is_on && !load.is_critical && !load.stuck_on {
+                load.turn_off();
+                shed_power += load.power_consumption_w;
+            }
+        }
+        shed_power
+    }
+}
+
+// Peaks are added as if they coincided
+fn add_demand(total: PowerDemand, demand: PowerDemand) -> PowerDemand {
+    PowerDemand { average_w: total.average_w + demand.average_w, peak_w: total.peak_w + demand.peak_w }
+}
+
+/// Status of one load channel as sent to the ground: ID, flags and trip counter in three bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadTelemetry {
+    pub id: LoadId,
+    pub is_on: bool,
+    pub is_powered: bool,
+    pub is_tripped: bool,
+    pub is_critical: bool,
+    pub trip_count: u8, // Saturates at 255
+}
+
+impl LoadTelemetry {
+    pub const SIZE: usize = 3;
+
+    pub fn encode(&self) -> [u8; Self::SIZE] {
+        let flags = self.is_on as u8 | (self.is_powered as u8) << 1 | (self.is_tripped as u8) << 2 | (self.is_critical as u8) << 3;
+        [self.id.0, flags, self.trip_count]
+    }
+
+    pub fn decode(bytes: [u8; Self::SIZE]) -> Self {
+        let [id, flags, trip_count] = bytes;
+        LoadTelemetry {
+            id: LoadId(id),
+            is_on: flags & 0b0001 != 0,
+            is_powered: flags & 0b0010 != 0,
+            is_tripped: flags & 0b0100 != 0,
+            is_critical: flags & 0b1000 != 0,
+            trip_count,
+        }
+    }
+}
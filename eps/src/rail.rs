@@ -0,0 +1,159 @@
+// Power conditioning: each regulated rail feeds its loads through a DC-DC converter drawing from the
+// battery bus. Converter efficiency depends on load, so a lightly loaded rail wastes a larger share.
+use heapless::Vec;
+
+use crate::consts::{
+    BOOST_EFFICIENCY_CURVE, BUCK_EFFICIENCY_CURVE, MAX_EFFICIENCY_POINTS, RAIL_12V_RATED_POWER_W, RAIL_3V3_RATED_POWER_W,
+    RAIL_5V_RATED_POWER_W,
+};
+use crate::enums::PowerRail;
+use crate::interpolate;
+use crate::load_profile::PowerDemand;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcDcConverter {
+    rated_power_w: f64,
+    efficiency_curve: Vec<(f64, f64), MAX_EFFICIENCY_POINTS>, // (fraction of rated power, efficiency)
+}
+
+impl DcDcConverter {
+    /// `efficiency_curve` pairs the output as a fraction of `rated_power_w` with the efficiency there.
+    /// Points beyond [`MAX_EFFICIENCY_POINTS`] are ignored.
+    pub fn new(rated_power_w: f64, efficiency_curve: &[(f64, f64)]) -> Self {
+        let efficiency_curve = efficiency_curve.iter().copied().take(MAX_EFFICIENCY_POINTS).collect();
+        DcDcConverter { rated_power_w, efficiency_curve }
+    }
+
+    /// Step-down converter, e.g. battery bus to 3.3 V or 5 V.
+    pub fn buck(rated_power_w: f64) -> Self {
+        DcDcConverter::new(rated_power_w, &BUCK_EFFICIENCY_CURVE)
+    }
+
+    /// Step-up converter, e.g. battery bus to 12 V.
+    pub fn boost(rated_power_w: f64) -> Self {
+        DcDcConverter::new(rated_power_w, &BOOST_EFFICIENCY_CURVE)
+    }
+
+    pub fn get_rated_power_w(&self) -> f64 {
+        self.rated_power_w
+    }
+
+    pub fn get_efficiency(&self, output_w: f64) -> f64 {
+        let load_fraction = if self.rated_power_w > 0.0 { output_w / self.rated_power_w } else { 1.0 };
+        interpolate(&self.efficiency_curve, load_fraction).clamp(f64::EPSILON, 1.0)
+    }
+
+    /// Power drawn from the bus to deliver `output_w`. An unloaded converter is assumed to idle at no cost.
+    pub fn get_input_power_w(&self, output_w: f64) -> f64 {
+        if output_w <= 0.0 { 0.0 } else { output_w / self.get_efficiency(output_w) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rail {
+    kind: PowerRail,
+    converter: Option<DcDcConverter>, // None for the battery bus
+}
+
+impl Rail {
+    pub fn new(kind: PowerRail, converter: Option<DcDcConverter>) -> Self {
+        Rail { kind, converter }
+    }
+
+    /// Battery bus passed straight through to the loads.
+    pub fn raw() -> Self {
+        Rail::new(PowerRail::Raw, None)
+    }
+
+    /// The standard rails: battery bus, 3.3 V and 5 V bucks and a 12 V boost.
+    pub fn standard() -> [Rail; 4] {
+        [
+            Rail::raw(),
+            Rail::new(PowerRail::V3_3, Some(DcDcConverter::buck(RAIL_3V3_RATED_POWER_W))),
+            Rail::new(PowerRail::V5, Some(DcDcConverter::buck(RAIL_5V_RATED_POWER_W))),
+            Rail::new(PowerRail::V12, Some(DcDcConverter::boost(RAIL_12V_RATED_POWER_W))),
+        ]
+    }
+
+    pub fn get_kind(&self) -> PowerRail {
+        self.kind
+    }
+
+    pub fn get_converter(&self) -> Option<&DcDcConverter> {
+        self.converter.as_ref()
+    }
+
+    /// Output voltage: the regulated value, or the bus voltage for the raw rail.
+    pub fn get_voltage_v(&self, bus_voltage_v: f64) -> f64 {
+        self.kind.get_nominal_voltage_v().unwrap_or(bus_voltage_v)
+    }
+
+    pub fn get_input_power_w(&self, output_w: f64) -> f64 {
+        self.converter.as_ref().map_or(output_w.max(0.0), |converter| converter.get_input_power_w(output_w))
+    }
+
+    /// Bus-side demand for an output demand. The average is converted at the average output, so a
+    /// pulsed rail is charged at the efficiency of its mean load.
+    pub fn get_demand(&self, output: PowerDemand) -> RailDemand {
+        let input = PowerDemand { average_w: self.get_input_power_w(output.average_w), peak_w: self.get_input_power_w(output.peak_w) };
+        RailDemand { rail: self.kind, output, input, loss_w: input.average_w - output.average_w }
+    }
+}
+
+/// One rail's share of a step's demand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RailDemand {
+    pub rail: PowerRail,
+    pub output: PowerDemand, // Delivered to the loads
+    pub input: PowerDemand,  // Drawn from the battery bus
+    pub loss_w: f64,         // Average converter loss
+}
+
+/// Rail housekeeping in the units and byte order of a GomSpace P31u: millivolts and milliamps as
+/// big-endian `u16`, saturating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RailTelemetry {
+    pub rail: PowerRail,
+    pub voltage_mv: u16,
+    pub current_ma: u16,       // Output current to the loads (P31u `curout`)
+    pub input_current_ma: u16, // Drawn from the battery bus (the rail's share of `cursys`)
+    pub loss_mw: u16,
+}
+
+impl RailTelemetry {
+    pub const SIZE: usize = 9;
+
+    pub(crate) fn new(rail: PowerRail, voltage_v: f64, bus_voltage_v: f64, demand: &RailDemand) -> Self {
+        let milli = |value: f64| libm::round(value * 1000.0).clamp(0.0, u16::MAX as f64) as u16;
+        let per_volt = |power_w: f64, voltage_v: f64| if voltage_v > 0.0 { power_w / voltage_v } else { 0.0 };
+        RailTelemetry {
+            rail,
+            voltage_mv: milli(voltage_v),
+            current_ma: milli(per_volt(demand.output.average_w, voltage_v)),
+            input_current_ma: milli(per_volt(demand.input.average_w, bus_voltage_v)),
+            loss_mw: milli(demand.loss_w),
+        }
+    }
+
+    pub fn encode(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0] = self.rail as u8;
+        for (chunk, value) in bytes[1..].chunks_exact_mut(2).zip([self.voltage_mv, self.current_ma, self.input_current_ma, self.loss_mw]) {
+            chunk.copy_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// `None` for an unknown rail number.
+    pub fn decode(bytes: [u8; Self::SIZE]) -> Option<Self> {
+        let rail = match bytes[0] {
+            0 => PowerRail::Raw,
+            1 => PowerRail::V3_3,
+            2 => PowerRail::V5,
+            3 => PowerRail::V12,
+            _ => return None,
+        };
+        let word = |index: usize| u16::from_be_bytes([bytes[index], bytes[index + 1]]);
+        Some(RailTelemetry { rail, voltage_mv: word(1), current_ma: word(3), input_current_ma: word(5), loss_mw: word(7) })
+    }
+}
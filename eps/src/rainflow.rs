@@ -0,0 +1,113 @@
+// Streaming rainflow cycle counting (ASTM E1049 three-point method) over a signal such as SoC.
+// Samples are reduced to turning points; every closed cycle or start-point half cycle is
+// reported with its range as soon as it is extracted.
+use heapless::Vec;
+
+use crate::consts::MAX_RAINFLOW_REVERSALS;
+
+#[derive(Debug, Clone)]
+pub struct RainflowCounter {
+    reversals: Vec<f64, MAX_RAINFLOW_REVERSALS>, // Residue of turning points not yet counted
+    extreme: Option<f64>,                        // Most extreme value since the last turning point
+    rising: Option<bool>,
+    hysteresis: f64,                             // Ignores wiggles smaller than this
+    full_cycles: u32,
+    half_cycles: u32,
+}
+
+impl RainflowCounter {
+    pub fn new(hysteresis: f64) -> Self {
+        RainflowCounter {
+            reversals: Vec::new(),
+            extreme: None,
+            rising: None,
+            hysteresis,
+            full_cycles: 0,
+            half_cycles: 0,
+        }
+    }
+
+    pub fn get_full_cycles(&self) -> u32 {
+        self.full_cycles
+    }
+
+    pub fn get_half_cycles(&self) -> u32 {
+        self.half_cycles
+    }
+
+    /// Counted cycles, half cycles weighing 0.5. The residue still on the stack is not included.
+    pub fn get_cycle_count(&self) -> f64 {
+        self.full_cycles as f64 + 0.5 * self.half_cycles as f64
+    }
+
+    /// Ranges of the residue half cycles that have not closed yet, up to the latest extreme.
+    pub fn residual_ranges(&self) -> impl Iterator<Item = f64> + '_ {
+        let pending = match (self.reversals.last(), self.extreme) {
+            (Some(last), Some(extreme)) if self.rising.is_some() => Some(libm::fabs(extreme - last)),
+            _ => None,
+        };
+        self.reversals.windows(2).map(|pair| libm::fabs(pair[1] - pair[0])).chain(pending)
+    }
+
+    /// Feeds one sample. `on_cycle(range, count)` is called for every extracted cycle, with
+    /// `count` 1.0 for a full cycle and 0.5 for a half cycle.
+    pub fn push_sample(&mut self, value: f64, mut on_cycle: impl FnMut(f64, f64)) {
+        let Some(extreme) = self.extreme else {
+            // The first sample is the starting point of the history
+            self.push_reversal(value, &mut on_cycle);
+            self.extreme = Some(value);
+            return;
+        };
+
+        match self.rising {
+            None => {
+                if libm::fabs(value - extreme) >= self.hysteresis {
+                    self.rising = Some(value > extreme);
+                    self.extreme = Some(value);
+                }
+            }
+            Some(rising) => {
+                let continues = if rising { value >= extreme } else { value <= extreme };
+                if continues {
+                    self.extreme = Some(value);
+                } else if libm::fabs(value - extreme) >= self.hysteresis {
+                    // The previous extreme was a turning point
+                    self.push_reversal(extreme, &mut on_cycle);
+                    self.rising = Some(!rising);
+                    self.extreme = Some(value);
+                }
+            }
+        }
+    }
+
+    fn push_reversal(&mut self, value: f64, on_cycle: &mut impl FnMut(f64, f64)) {
+        if self.reversals.is_full() {
+            // Out of room: retire the oldest range as a half cycle
+            let range = libm::fabs(self.reversals[1] - self.reversals[0]);
+            self.reversals.remove(0);
+            self.half_cycles += 1;
+            on_cycle(range, 0.5);
+        }
+        let _ = self.reversals.push(value);
+
+        while self.reversals.len() >= 3 {
+            let n = self.reversals.len();
+            let x = libm::fabs(self.reversals[n - 1] - self.reversals[n - 2]);
+            let y = libm::fabs(self.reversals[n - 2] - self.reversals[n - 3]);
+            if x < y {
+                break;
+            }
+            if n == 3 {
+                // Y contains the starting point: count it as a half cycle
+                self.reversals.remove(0);
+                self.half_cycles += 1;
+                on_cycle(y, 0.5);
+            } else {
+                self.reversals.remove(n - 2);
+                self.reversals.remove(n - 3);
+                self.full_cycles += 1;
+                on_cycle(y, 1.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Solar array charge regulation.
+// A direct-energy-transfer (DET) regulator ties the array to the battery bus through a blocking diode,
+// so the array works wherever the battery voltage puts it. An MPPT regulator moves the array voltage
+// towards the maximum power point through a DC-DC converter, paying the converter losses.
+use heapless::Vec;
+
+use crate::consts::{
+    DET_BLOCKING_DIODE_DROP_V, MAX_SOLAR_PANELS, MPPT_CONVERTER_EFFICIENCY, MPPT_INITIAL_VOC_FRACTION,
+    MPPT_ITERATIONS_PER_STEP, MPPT_PERTURBATION_FRACTION,
+};
+use crate::enums::{MpptAlgorithm, RegulatorKind};
+use crate::solar_cell::PvString;
+use crate::solar_panel::SolarPanel;
+
+/// Operating point memory of one tracker, one per panel string.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MpptTracker {
+    voltage_v: f64,
+    last_voltage_v: f64,
+    last_current_a: f64,
+    last_power_w: f64,
+    increasing: bool,
+}
+
+impl MpptTracker {
+    pub fn get_voltage_v(&self) -> f64 {
+        self.voltage_v
+    }
+
+    /// Runs `iterations` tracker updates on `string` and returns the final array voltage.
+    pub fn track(
+        &mut self,
+        algorithm: MpptAlgorithm,
+        string: &PvString,
+        irradiance_w_m2: f64,
+        temperature_c: f64,
+        iterations: usize,
+    ) -> f64 {
+        let voc_v = string.get_open_circuit_voltage_v(irradiance_w_m2, temperature_c);
+        if voc_v <= 0.0 {
+            return self.voltage_v;
+        }
+        let step_v = voc_v * MPPT_PERTURBATION_FRACTION;
+        if self.voltage_v <= 0.0 || self.voltage_v >= voc_v {
+            // Restart from a fraction of Voc, e.g. on leaving eclipse
+            self.voltage_v = voc_v * MPPT_INITIAL_VOC_FRACTION;
+            self.last_voltage_v = self.voltage_v;
+            self.last_current_a = string.get_current_a(self.voltage_v, irradiance_w_m2, temperature_c);
+            self.last_power_w = self.voltage_v * self.last_current_a;
+            self.voltage_v -= step_v;
+            self.increasing = false;
+        }
+
+        for _ in 0..iterations {
+            let current_a = string.get_current_a(self.voltage_v, irradiance_w_m2, temperature_c);
+            let power_w = self.voltage_v * current_a;
+            let increase = match algorithm {
+                MpptAlgorithm::PerturbAndObserve => {
+                    // Keep going while power rises, reverse when it falls
+                    if power_w < self.last_power_w { !self.increasing } else { self.increasing }
+                }
+                MpptAlgorithm::IncrementalConductance => {
+                    // At the MPP dP/dV = 0, i.e. dI/dV = -I/V
+                    let delta_v = self.voltage_v - self.last_voltage_v;
+                    let delta_i = current_a - self.last_current_a;
+                    if delta_v == 0.0 {
+                        if delta_i == 0.0 { self.increasing } else { delta_i > 0.0 }
+                    } else {
+                        delta_i / delta_v > -current_a / self.voltage_v
+                    }
+                }
+            };
+            self.last_voltage_v = self.voltage_v;
+            self.last_current_a = current_a;
+            self.last_power_w = power_w;
+            self.increasing = increase;
+            let next_v = if increase { self.voltage_v + step_v } else { self.voltage_v - step_v };
+            self.voltage_v = next_v.clamp(step_v, voc_v - step_v);
+        }
+        self.voltage_v
+    }
+}
+
+/// Power delivered to the bus by a DET regulator at `battery_voltage_v`.
+pub fn direct_energy_transfer_power_w(string: &PvString, irradiance_w_m2: f64, temperature_c: f64, battery_voltage_v: f64) -> f64 {
+    let array_voltage_v = battery_voltage_v + DET_BLOCKING_DIODE_DROP_V;
+    battery_voltage_v * string.get_current_a(array_voltage_v, irradiance_w_m2, temperature_c)
+}
+
+/// Extra bus power an ideally converged MPPT regulator delivers over DET at `battery_voltage_v`.
+/// Negative when the battery sits close to the maximum power voltage and the converter losses dominate.
+pub fn mppt_gain_w(string: &PvString, irradiance_w_m2: f64, temperature_c: f64, battery_voltage_v: f64) -> f64 {
+    let (_, mpp_power_w) = string.get_maximum_power_point(irradiance_w_m2, temperature_c);
+    mpp_power_w * MPPT_CONVERTER_EFFICIENCY - direct_energy_transfer_power_w(string, irradiance_w_m2, temperature_c, battery_voltage_v)
+}
+
+#[derive(Debug, Clone)]
+pub struct ChargeRegulator {
+    kind: RegulatorKind,
+    converter_efficiency: f64,
+    trackers: Vec<MpptTracker, MAX_SOLAR_PANELS>,
+    failed: bool, // Delivers nothing
+}
+
+impl ChargeRegulator {
+    pub fn new(kind: RegulatorKind) -> Self {
+        let mut trackers = Vec::new();
+        trackers.resize(MAX_SOLAR_PANELS, MpptTracker::default()).ok();
+        ChargeRegulator { kind, converter_efficiency: MPPT_CONVERTER_EFFICIENCY, trackers, failed: false }
+    }
+
+    pub fn with_converter_efficiency(mut self, converter_efficiency: f64) -> Self {
+        self.converter_efficiency = converter_efficiency;
+        self
+    }
+
+    pub fn get_kind(&self) -> RegulatorKind {
+        self.kind
+    }
+
+    /// The regulator stops delivering array power, e.g. a failed converter or blocking switch.
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    pub fn get_tracker(&self, panel_index: usize) -> Option<&MpptTracker> {
+        self.trackers.get(panel_index)
+    }
+
+    /// Power `panel` delivers to the bus. Panels without an I-V model keep their ideal output.
+    pub fn regulate(&mut self, panel_index: usize, panel: &SolarPanel, battery_voltage_v: f64) -> f64 {
+        if self.failed {
+            return 0.0;
+        }
+        let Some(string) = panel.get_string() else {
+            return panel.get_power_output_w();
+        };
+        let irradiance_w_m2 = panel.get_effective_irradiance_w_m2();
+        let temperature_c = panel.get_temperature_c();
+        match self.kind {
+            RegulatorKind::DirectEnergyTransfer => {
+                direct_energy_transfer_power_w(string, irradiance_w_m2, temperature_c, battery_voltage_v)
+            }
+            RegulatorKind::Mppt(algorithm) => {
+                let Some(tracker) = self.trackers.get_mut(panel_index) else {
+                    return 0.0;
+                };
+                let voltage_v = tracker.track(algorithm, string, irradiance_w_m2, temperature_c, MPPT_ITERATIONS_PER_STEP);
+                string.get_power_w(voltage_v, irradiance_w_m2, temperature_c) * self.converter_efficiency
+            }
+        }
+    }
+}
+
+impl Default for ChargeRegulator {
+    fn default() -> Self {
+        ChargeRegulator::new(RegulatorKind::Mppt(MpptAlgorithm::PerturbAndObserve))
+    }
+}
@@ -0,0 +1,165 @@
+// Tiered load shedding. Page 12, Section 1.3: turn off non-critical loads as energy runs low.
+// Non-critical loads are grouped by shedding priority; the lowest priority tier goes first as SoC falls,
+// and tiers come back in reverse order once SoC recovers past a higher restore threshold.
+use heapless::Vec;
+
+use crate::consts::{DEFAULT_SHED_TIERS, MAX_LOADS, MAX_SHED_TIERS};
+use crate::enums::{SheddingAction, SheddingError, SheddingReason};
+use crate::load_id::LoadId;
+use crate::pdu::PowerDistributionUnit;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShedTier {
+    priority: u8,           // Loads with this shedding priority belong to the tier
+    shed_below_soc: f64,    // Percent
+    restore_above_soc: f64, // Percent
+    is_shed: bool,
+    shed_loads: u32,        // Bit mask of the PDU loads this tier switched off
+}
+
+impl ShedTier {
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub fn get_shed_below_soc(&self) -> f64 {
+        self.shed_below_soc
+    }
+
+    pub fn get_restore_above_soc(&self) -> f64 {
+        self.restore_above_soc
+    }
+
+    pub fn is_shed(&self) -> bool {
+        self.is_shed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheddingDecision {
+    pub action: SheddingAction,
+    pub priority: u8,
+    pub reason: SheddingReason,
+    pub soc_percentage: f64,
+    pub threshold_soc: f64,
+    pub power_w: f64,               // Demand removed or added back
+    pub loads: Vec<LoadId, MAX_LOADS>,
+}
+
+pub type SheddingDecisions = Vec<SheddingDecision, { 2 * MAX_SHED_TIERS }>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadSheddingPolicy {
+    tiers: Vec<ShedTier, MAX_SHED_TIERS>, // Sorted by decreasing shed threshold
+}
+
+impl LoadSheddingPolicy {
+    /// A policy without tiers never sheds on SoC.
+    pub fn new() -> Self {
+        LoadSheddingPolicy { tiers: Vec::new() }
+    }
+
+    pub fn add_tier(&mut self, priority: u8, shed_below_soc: f64, restore_above_soc: f64) -> Result<(), SheddingError> {
+        if restore_above_soc <= shed_below_soc {
+            return Err(SheddingError::NoHysteresis);
+        }
+        let tier = ShedTier { priority, shed_below_soc, restore_above_soc, is_shed: false, shed_loads: 0 };
+        let index = self.tiers.iter().position(|t| t.shed_below_soc < shed_below_soc).unwrap_or(self.tiers.len());
+        self.tiers.insert(index, tier).map_err(|_| SheddingError::TooManyTiers)
+    }
+
+    pub fn get_tiers(&self) -> &[ShedTier] {
+        &self.tiers
+    }
+
+    /// Sheds every tier SoC has fallen through, then restores tiers SoC has recovered past,
+    /// deepest first. A tier only comes back once all deeper tiers are back.
+    pub fn update(&mut self, pdu: &mut PowerDistributionUnit, soc_percentage: f64) -> SheddingDecisions {
+        let mut decisions = SheddingDecisions::new();
+        for tier in self.tiers.iter_mut() {
+            if !tier.is_shed && soc_percentage < tier.shed_below_soc {
+                let decision = shed_tier(tier, pdu, soc_percentage, SheddingReason::SocBelowShedThreshold);
+                let _ = decisions.push(decision); // At most one decision per tier and action
+            }
+        }
+        for index in (0..self.tiers.len()).rev() {
+            let deeper_shed = self.tiers[index + 1..].iter().any(|tier| tier.is_shed);
+            let tier = &mut self.tiers[index];
+            if tier.is_shed && !deeper_shed && soc_percentage > tier.restore_above_soc {
+                let _ = decisions.push(restore_tier(tier, pdu, soc_percentage));
+            }
+        }
+        decisions
+    }
+
+    /// Emergency: sheds every tier still powered, e.g. when the battery cannot cover the deficit.
+    pub fn shed_all(&mut self, pdu: &mut PowerDistributionUnit, soc_percentage: f64) -> SheddingDecisions {
+        let mut decisions = SheddingDecisions::new();
+        for tier in self.tiers.iter_mut().filter(|tier| !tier.is_shed) {
+            let _ = decisions.push(shed_tier(tier, pdu, soc_percentage, SheddingReason::DeficitNotCovered));
+        }
+        decisions
+    }
+}
+
+impl Default for LoadSheddingPolicy {
+    fn default() -> Self {
+        let mut policy = LoadSheddingPolicy::new();
+        for (priority, shed_below_soc, restore_above_soc) in DEFAULT_SHED_TIERS {
+            let _ = policy.add_tier(priority, shed_below_soc, restore_above_soc); // Valid and within capacity
+        }
+        policy
+    }
+}
+
+fn shed_tier(tier: &mut ShedTier, pdu: &mut PowerDistributionUnit, soc_percentage: f64, reason: SheddingReason) -> SheddingDecision {
+    let mut decision = SheddingDecision {
+        action: SheddingAction::Shed,
+        priority: tier.priority,
+        reason,
+        soc_percentage,
+        threshold_soc: tier.shed_below_soc,
+        power_w: 0.0,
+        loads: Vec::new(),
+    };
+    // Loads of the tier that are off stay off too, whatever the mode logic asks for
+    for (index, load) in pdu.loads.iter_mut().enumerate() {
+        if load.is_critical() || load.get_shed_priority() != tier.priority {
+            continue;
+        }
+        load.held_off = true;
+        if load.is_on() && !load.burning {
+            decision.power_w += load.get_power_demand_w();
+            load.turn_off();
+            tier.shed_loads |= 1 << index;
+            let _ = decision.loads.push(load.get_id());
+        }
+    }
+    tier.is_shed = true;
+    decision
+}
+
+fn restore_tier(tier: &mut ShedTier, pdu: &mut PowerDistributionUnit, soc_percentage: f64) -> SheddingDecision {
+    let mut decision = SheddingDecision {
+        action: SheddingAction::Restore,
+        priority: tier.priority,
+        reason: SheddingReason::SocAboveRestoreThreshold,
+        soc_percentage,
+        threshold_soc: tier.restore_above_soc,
+        power_w: 0.0,
+        loads: Vec::new(),
+    };
+    for (index, load) in pdu.loads.iter_mut().enumerate() {
+        if !load.is_critical() && load.get_shed_priority() == tier.priority {
+            load.held_off = false;
+        }
+        if tier.shed_loads & (1 << index) != 0 {
+            load.turn_on();
+            decision.power_w += load.get_power_demand_w();
+            let _ = decision.loads.push(load.get_id());
+        }
+    }
+    tier.is_shed = false;
+    tier.shed_loads = 0;
+    decision
+}
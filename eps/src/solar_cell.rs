@@ -0,0 +1,214 @@
+// Single-diode solar cell model:
+// I = Iph - I0 * (exp((V + I*Rs) / (n*Vt)) - 1) - (V + I*Rs) / Rsh
+// Iph follows irradiance and temperature; I0 is chosen so the model reproduces the rated Voc.
+use crate::consts::{
+    BOLTZMANN_J_K, ELEMENTARY_CHARGE_C, SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2, SOLAR_CELL_REFERENCE_TEMPERATURE_C,
+    ZERO_CELSIUS_K,
+};
+use crate::enums::CellTechnology;
+use crate::radiation::RemainingFactors;
+
+const MAX_EXPONENT: f64 = 200.0;
+const NEWTON_ITERATIONS: usize = 50;
+const CURRENT_TOLERANCE_A: f64 = 1e-9;
+const MPP_SEARCH_ITERATIONS: usize = 60;
+const PMAX_FIT_ITERATIONS: usize = 20;
+const PMAX_FIT_TOLERANCE: f64 = 1e-6; // Relative to the target power
+
+/// Datasheet parameters of one cell at the reference irradiance and temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarCell {
+    pub short_circuit_current_a: f64,
+    pub open_circuit_voltage_v: f64,
+    pub current_temperature_coeff_per_c: f64, // Relative change of Isc per degC
+    pub voltage_temperature_coeff_v_per_c: f64, // Change of Voc per degC
+    pub ideality_factor: f64,                 // Sum over the junctions of a multi-junction cell
+    pub series_resistance_ohm: f64,
+    pub shunt_resistance_ohm: f64,
+}
+
+impl SolarCell {
+    /// 30% class GaAs triple-junction cell (30.18 cm^2), beginning of life.
+    pub fn triple_junction_gaas() -> Self {
+        SolarCell {
+            short_circuit_current_a: 0.52,
+            open_circuit_voltage_v: 2.69,
+            current_temperature_coeff_per_c: 0.0007,
+            voltage_temperature_coeff_v_per_c: -0.0062,
+            ideality_factor: 3.0,
+            series_resistance_ohm: 0.02,
+            shunt_resistance_ohm: 1000.0,
+        }
+    }
+
+    /// Space-grade silicon cell of the same area, beginning of life.
+    pub fn silicon() -> Self {
+        SolarCell {
+            short_circuit_current_a: 1.27,
+            open_circuit_voltage_v: 0.62,
+            current_temperature_coeff_per_c: 0.0005,
+            voltage_temperature_coeff_v_per_c: -0.0021,
+            ideality_factor: 1.2,
+            series_resistance_ohm: 0.01,
+            shunt_resistance_ohm: 200.0,
+        }
+    }
+
+    pub fn for_technology(technology: CellTechnology) -> Self {
+        match technology {
+            CellTechnology::TripleJunctionGaAs => SolarCell::triple_junction_gaas(),
+            CellTechnology::Silicon => SolarCell::silicon(),
+        }
+    }
+
+    pub fn get_short_circuit_current_a(&self, irradiance_w_m2: f64, temperature_c: f64) -> f64 {
+        let delta_t = temperature_c - SOLAR_CELL_REFERENCE_TEMPERATURE_C;
+        (self.short_circuit_current_a * (irradiance_w_m2 / SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2) * (1.0 + self.current_temperature_coeff_per_c * delta_t)).max(0.0)
+    }
+
+    // Open-circuit voltage at the reference irradiance
+    fn get_rated_open_circuit_voltage_v(&self, temperature_c: f64) -> f64 {
+        (self.open_circuit_voltage_v + self.voltage_temperature_coeff_v_per_c * (temperature_c - SOLAR_CELL_REFERENCE_TEMPERATURE_C)).max(0.0)
+    }
+
+    // n * k * T / q
+    fn get_modified_thermal_voltage_v(&self, temperature_c: f64) -> f64 {
+        self.ideality_factor * BOLTZMANN_J_K * (temperature_c + ZERO_CELSIUS_K) / ELEMENTARY_CHARGE_C
+    }
+}
+
+/// Cells wired `series_cells` in series per string and `parallel_strings` strings in parallel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PvString {
+    cell: SolarCell,
+    series_cells: u32,
+    parallel_strings: u32,
+}
+
+impl PvString {
+    pub fn new(cell: SolarCell, series_cells: u32, parallel_strings: u32) -> Self {
+        PvString { cell, series_cells: series_cells.max(1), parallel_strings: parallel_strings.max(1) }
+    }
+
+    pub fn get_cell(&self) -> &SolarCell {
+        &self.cell
+    }
+
+    /// The same string after irradiation: the cells' Isc and Voc are scaled, then their series resistance is
+    /// refitted so that the maximum power at the reference irradiance and temperature is `factors.pmax` times
+    /// the undamaged string's, the fill factor loss the Isc and Voc curves alone do not capture.
+    pub fn with_remaining_factors(&self, factors: &RemainingFactors) -> Self {
+        let mut cell = SolarCell {
+            short_circuit_current_a: self.cell.short_circuit_current_a * factors.isc,
+            open_circuit_voltage_v: self.cell.open_circuit_voltage_v * factors.voc,
+            ..self.cell
+        };
+        let target_w = factors.pmax * get_reference_maximum_power_point(&self.cell).1;
+        for _ in 0..PMAX_FIT_ITERATIONS {
+            let (voltage_v, power_w) = get_reference_maximum_power_point(&cell);
+            let error_w = power_w - target_w;
+            if libm::fabs(error_w) <= PMAX_FIT_TOLERANCE * target_w || voltage_v <= 0.0 {
+                break;
+            }
+            // The power lost in the series resistance is about Imp^2 * Rs
+            let current_a = power_w / voltage_v;
+            let resistance_ohm = (cell.series_resistance_ohm + error_w / (current_a * current_a)).max(0.0);
+            if resistance_ohm == cell.series_resistance_ohm {
+                break;
+            }
+            cell.series_resistance_ohm = resistance_ohm;
+        }
+        PvString { cell, ..*self }
+    }
+
+    /// The same string with only `parallel_strings` of its strings connected; with none it delivers no current.
+    pub(crate) fn with_parallel_strings(&self, parallel_strings: u32) -> Self {
+        PvString { parallel_strings, ..*self }
+    }
+
+    pub fn get_series_cells(&self) -> u32 {
+        self.series_cells
+    }
+
+    pub fn get_parallel_strings(&self) -> u32 {
+        self.parallel_strings
+    }
+
+    pub fn get_short_circuit_current_a(&self, irradiance_w_m2: f64, temperature_c: f64) -> f64 {
+        self.parallel_strings as f64 * self.cell.get_short_circuit_current_a(irradiance_w_m2, temperature_c)
+    }
+
+    /// Open-circuit voltage, including its logarithmic drop at low irradiance.
+    pub fn get_open_circuit_voltage_v(&self, irradiance_w_m2: f64, temperature_c: f64) -> f64 {
+        let photo_current_a = self.cell.get_short_circuit_current_a(irradiance_w_m2, temperature_c);
+        let saturation_current_a = self.get_cell_saturation_current_a(temperature_c);
+        if photo_current_a <= 0.0 || saturation_current_a <= 0.0 {
+            return 0.0;
+        }
+        let cell_voc_v = self.cell.get_modified_thermal_voltage_v(temperature_c) * libm::log(photo_current_a / saturation_current_a + 1.0);
+        self.series_cells as f64 * cell_voc_v
+    }
+
+    /// Terminal current at `voltage_v`. Negative values (reverse current above Voc) are
+    /// clamped to zero, as the string is isolated from the bus by a diode.
+    pub fn get_current_a(&self, voltage_v: f64, irradiance_w_m2: f64, temperature_c: f64) -> f64 {
+        let photo_current_a = self.cell.get_short_circuit_current_a(irradiance_w_m2, temperature_c);
+        if photo_current_a <= 0.0 {
+            return 0.0;
+        }
+        let saturation_current_a = self.get_cell_saturation_current_a(temperature_c);
+        let thermal_voltage_v = self.cell.get_modified_thermal_voltage_v(temperature_c);
+        let rs = self.cell.series_resistance_ohm;
+        let rsh = self.cell.shunt_resistance_ohm;
+
+        // Solve for one cell of one string; f(I) is decreasing and concave, so Newton from Iph converges monotonically
+        let cell_voltage_v = voltage_v / self.series_cells as f64;
+        let mut current_a = photo_current_a;
+        for _ in 0..NEWTON_ITERATIONS {
+            let exponent = ((cell_voltage_v + current_a * rs) / thermal_voltage_v).min(MAX_EXPONENT);
+            let diode_exp = libm::exp(exponent);
+            let f = photo_current_a - saturation_current_a * (diode_exp - 1.0) - (cell_voltage_v + current_a * rs) / rsh - current_a;
+            let df = -saturation_current_a * diode_exp * rs / thermal_voltage_v - rs / rsh - 1.0;
+            let step_a = f / df;
+            current_a -= step_a;
+            if libm::fabs(step_a) < CURRENT_TOLERANCE_A {
+                break;
+            }
+        }
+        current_a.max(0.0) * self.parallel_strings as f64
+    }
+
+    pub fn get_power_w(&self, voltage_v: f64, irradiance_w_m2: f64, temperature_c: f64) -> f64 {
+        voltage_v * self.get_current_a(voltage_v, irradiance_w_m2, temperature_c)
+    }
+
+    /// Maximum power point as `(voltage_v, power_w)`, found by golden-section search between 0 and Voc.
+    pub fn get_maximum_power_point(&self, irradiance_w_m2: f64, temperature_c: f64) -> (f64, f64) {
+        let inv_phi = (libm::sqrt(5.0) - 1.0) / 2.0;
+        let (mut low_v, mut high_v) = (0.0, self.get_open_circuit_voltage_v(irradiance_w_m2, temperature_c));
+        for _ in 0..MPP_SEARCH_ITERATIONS {
+            let a_v = high_v - inv_phi * (high_v - low_v);
+            let b_v = low_v + inv_phi * (high_v - low_v);
+            if self.get_power_w(a_v, irradiance_w_m2, temperature_c) < self.get_power_w(b_v, irradiance_w_m2, temperature_c) {
+                low_v = a_v;
+            } else {
+                high_v = b_v;
+            }
+        }
+        let voltage_v = (low_v + high_v) / 2.0;
+        (voltage_v, self.get_power_w(voltage_v, irradiance_w_m2, temperature_c))
+    }
+
+    // Saturation current that reproduces the rated Voc at the reference irradiance (shunt neglected)
+    fn get_cell_saturation_current_a(&self, temperature_c: f64) -> f64 {
+        let rated_isc_a = self.cell.get_short_circuit_current_a(SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2, temperature_c);
+        let rated_voc_v = self.cell.get_rated_open_circuit_voltage_v(temperature_c);
+        let exponent = (rated_voc_v / self.cell.get_modified_thermal_voltage_v(temperature_c)).min(MAX_EXPONENT);
+        rated_isc_a / (libm::exp(exponent) - 1.0)
+    }
+}
+
+// Maximum power point of a single cell at the reference irradiance and temperature
+fn get_reference_maximum_power_point(cell: &SolarCell) -> (f64, f64) {
+    PvString::new(*cell, 1, 1).get_maximum_power_point(SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2, SOLAR_CELL_REFERENCE_TEMPERATURE_C)
+}
@@ -0,0 +1,239 @@
+// Referencing Introduction to CubeSat Power Control System.pdf from KiboCUBE Academy Webinars
+use heapless::Vec;
+
+use crate::consts::{
+    BODY_CELLS_PER_1U_FACE, BODY_CELLS_PER_3U_LONG_FACE, SOLAR_CELL_AREA_M2, SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2,
+    SOLAR_CELL_REFERENCE_TEMPERATURE_C,
+};
+use crate::enums::CubeSatFormFactor;
+use crate::radiation::{RadiationDegradation, RemainingFactors};
+use crate::solar_cell::PvString;
+use crate::vector::Vector3;
+use crate::{Id, make_id};
+
+#[derive(Debug, Clone)]
+pub struct SolarPanel {
+    id: Id,
+    area_m2: f64,                // Page 14, Section 2.1
+    efficiency: f64,             // Conversion efficiency (0.0 to 1.0), page 14, Section 2.1
+    normal_body: Vector3,        // Outward unit normal of the cell side, body frame
+    current_power_output_w: f64,
+    is_deployed: bool,
+    deployed_fraction: f64,      // Share of the full output a partly opened panel still produces
+    degradation_factor: f64,     // Starts at 1.0, decreases over time. Page 15, Section 2.2
+    string: Option<PvString>,    // When set, output follows the I-V curve instead of area * efficiency
+    bol_string: Option<PvString>, // The string before radiation damage
+    radiation: Option<RadiationDegradation>,
+    remaining: RemainingFactors, // Radiation damage at the last update
+    open_strings: u32,           // Parallel strings lost to open circuits
+    temperature_c: f64,
+    effective_irradiance_w_m2: f64, // Sun intensity on the cells after incidence and degradation
+}
+
+impl SolarPanel {
+    pub fn new(id: &str, area_m2: f64, efficiency: f64, normal_body: Vector3) -> Self {
+        SolarPanel {
+            id: make_id(id),
+            area_m2, // Page 14, Section 2.1
+            efficiency, // Page 14, Section 2.1
+            normal_body: normal_body.normalized(),
+            current_power_output_w: 0.0,
+            is_deployed: false,
+            deployed_fraction: 0.0,
+            degradation_factor: 1.0, // Page 15, Section 2.2
+            string: None,
+            bol_string: None,
+            radiation: None,
+            remaining: RemainingFactors::default(),
+            open_strings: 0,
+            temperature_c: SOLAR_CELL_REFERENCE_TEMPERATURE_C,
+            effective_irradiance_w_m2: 0.0,
+        }
+    }
+
+    /// Models the panel's cells with a single-diode I-V curve. The power available is then the
+    /// maximum power point; what reaches the bus depends on the charge regulator.
+    pub fn with_string(mut self, string: PvString) -> Self {
+        self.bol_string = Some(string);
+        self.update_string();
+        self
+    }
+
+    /// Degrades the cells with the fluence collected by mission time, updated every EPS step.
+    pub fn with_radiation(mut self, radiation: RadiationDegradation) -> Self {
+        self.radiation = Some(radiation);
+        self.update_radiation_damage(0.0);
+        self
+    }
+
+    pub fn get_radiation(&self) -> Option<&RadiationDegradation> {
+        self.radiation.as_ref()
+    }
+
+    pub fn get_remaining_factors(&self) -> RemainingFactors {
+        self.remaining
+    }
+
+    /// Brings the radiation damage up to mission time `time_s`. Without a radiation model nothing changes.
+    pub fn update_radiation_damage(&mut self, time_s: f64) {
+        let Some(radiation) = &self.radiation else {
+            return;
+        };
+        self.remaining = radiation.get_remaining_factors(time_s);
+        self.update_string();
+    }
+
+    /// Opens one more parallel string, e.g. a broken interconnect. A panel without an I-V model is a single string.
+    pub fn open_string(&mut self) {
+        let strings = self.bol_string.map_or(1, |string| string.get_parallel_strings());
+        self.open_strings = (self.open_strings + 1).min(strings);
+        self.update_string();
+    }
+
+    pub fn get_open_strings(&self) -> u32 {
+        self.open_strings
+    }
+
+    // Every parallel string open
+    fn is_open_circuit(&self) -> bool {
+        self.open_strings >= self.bol_string.map_or(1, |string| string.get_parallel_strings())
+    }
+
+    // The working strings after radiation damage
+    fn update_string(&mut self) {
+        self.string = self.bol_string.map(|string| {
+            let working = string.get_parallel_strings().saturating_sub(self.open_strings);
+            string.with_parallel_strings(working).with_remaining_factors(&self.remaining)
+        });
+    }
+
+    /// Output at normal incidence to the reference sun and cell temperature after `mission_years`
+    /// of radiation, with the other degradation as it is now. Without a radiation model, the
+    /// output as it is now.
+    pub fn get_end_of_life_power_w(&self, mission_years: f64) -> f64 {
+        let factors = self.radiation.map_or(self.remaining, |radiation| radiation.get_end_of_life_factors(mission_years));
+        let irradiance_w_m2 = SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2 * self.degradation_factor;
+        match &self.bol_string {
+            Some(string) => {
+                string.with_remaining_factors(&factors).get_maximum_power_point(irradiance_w_m2, SOLAR_CELL_REFERENCE_TEMPERATURE_C).1
+            }
+            None => self.area_m2 * self.efficiency * irradiance_w_m2 * factors.pmax,
+        }
+    }
+
+    pub fn get_string(&self) -> Option<&PvString> {
+        self.string.as_ref()
+    }
+
+    pub fn get_temperature_c(&self) -> f64 {
+        self.temperature_c
+    }
+
+    pub fn set_temperature_c(&mut self, temperature_c: f64) {
+        self.temperature_c = temperature_c;
+    }
+
+    pub fn get_effective_irradiance_w_m2(&self) -> f64 {
+        self.effective_irradiance_w_m2
+    }
+
+    /// Body-mounted panels on the six faces of a CubeSat, long axis along body Z.
+    /// They need no deployment mechanism, so they are returned already deployed.
+    pub fn body_mounted_faces(form_factor: CubeSatFormFactor, efficiency: f64) -> Vec<SolarPanel, 6> {
+        let side_cells = match form_factor {
+            CubeSatFormFactor::OneU => BODY_CELLS_PER_1U_FACE,
+            CubeSatFormFactor::ThreeU => BODY_CELLS_PER_3U_LONG_FACE,
+        };
+        let side_area_m2 = side_cells as f64 * SOLAR_CELL_AREA_M2;
+        let end_area_m2 = BODY_CELLS_PER_1U_FACE as f64 * SOLAR_CELL_AREA_M2;
+
+        let faces = [
+            ("+X", side_area_m2, Vector3::X),
+            ("-X", side_area_m2, -Vector3::X),
+            ("+Y", side_area_m2, Vector3::Y),
+            ("-Y", side_area_m2, -Vector3::Y),
+            ("+Z", end_area_m2, Vector3::Z),
+            ("-Z", end_area_m2, -Vector3::Z),
+        ];
+        let mut panels = Vec::new();
+        for (id, area_m2, normal) in faces {
+            let mut panel = SolarPanel::new(id, area_m2, efficiency, normal);
+            panel.deploy();
+            let _ = panels.push(panel); // Capacity matches the six faces
+        }
+        panels
+    }
+
+    /// A pair of deployable wings whose cell sides face `normal_body` once deployed.
+    /// Wings start stowed and produce nothing until [`SolarPanel::deploy`] is called.
+    pub fn deployable_wings(area_m2_per_wing: f64, efficiency: f64, normal_body: Vector3) -> Vec<SolarPanel, 2> {
+        let mut wings = Vec::new();
+        for id in ["WING1", "WING2"] {
+            let _ = wings.push(SolarPanel::new(id, area_m2_per_wing, efficiency, normal_body));
+        }
+        wings
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_normal_body(&self) -> Vector3 {
+        self.normal_body
+    }
+
+    pub fn deploy(&mut self) {
+        self.deploy_partially(1.0);
+    }
+
+    /// Opens the panel short of its stop, e.g. a hinge caught on a harness. `fraction` of the full
+    /// output remains; a panel never folds back, so a smaller fraction than the present one is ignored.
+    pub fn deploy_partially(&mut self, fraction: f64) {
+        self.is_deployed = true;
+        self.deployed_fraction = self.deployed_fraction.max(fraction.clamp(0.0, 1.0));
+    }
+
+    pub fn is_deployed(&self) -> bool {
+        self.is_deployed
+    }
+
+    pub fn get_deployed_fraction(&self) -> f64 {
+        self.deployed_fraction
+    }
+
+    /// Cosine of the sun incidence angle, clamped at zero for faces pointing away from the sun.
+    /// Dependence on Satellite Attitude (Angle to Sun): page 12, Section 1.3
+    pub fn get_incidence_cosine(&self, sun_vector_body: &Vector3) -> f64 {
+        self.normal_body.dot(&sun_vector_body.normalized()).max(0.0)
+    }
+
+    // Page 12, Section 1.3
+    pub fn update_power_output(&mut self, sun_intensity_w_m2: f64, sun_vector_body: &Vector3) {
+        if self.is_deployed && !self.is_open_circuit() {
+            // sun_intensity_w_m2: Can vary based on orbit position relative to Earth's shadow
+            let cos_incidence = self.get_incidence_cosine(sun_vector_body);
+            self.effective_irradiance_w_m2 = sun_intensity_w_m2 * cos_incidence * self.degradation_factor * self.deployed_fraction;
+            self.current_power_output_w = match &self.string {
+                Some(string) => string.get_maximum_power_point(self.effective_irradiance_w_m2, self.temperature_c).1,
+                None => self.area_m2 * self.efficiency * self.effective_irradiance_w_m2 * self.remaining.pmax,
+            };
+        } else {
+            self.effective_irradiance_w_m2 = 0.0;
+            self.current_power_output_w = 0.0;
+        }
+    }
+
+    // Power actually extracted by the charge regulator
+    pub(crate) fn set_power_output_w(&mut self, power_w: f64) {
+        self.current_power_output_w = power_w;
+    }
+
+    pub fn get_power_output_w(&self) -> f64 {
+        self.current_power_output_w
+    }
+
+    // Simplified degradation over time
+    pub fn apply_degradation(&mut self, factor_decrease: f64) {
+        self.degradation_factor = (self.degradation_factor - factor_decrease).max(0.0);
+    }
+}
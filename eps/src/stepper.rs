@@ -0,0 +1,228 @@
+// Adaptive stepping. The EPS samples its guards at the start of every step, so a fixed step notices a
+// threshold or an eclipse boundary up to a whole step late. The stepper runs sub-steps on a copy of
+// the EPS and, when one crosses a watched boundary, bisects it until the crossing is located within the
+// resolution. The next sub-step then starts right at the crossing, whatever step size the caller uses.
+use heapless::Vec;
+
+use crate::consts::{
+    ADAPTIVE_MAX_STEP_S, ADAPTIVE_MIN_STEP_S, ADAPTIVE_RESOLUTION_S, MAX_STEP_CROSSINGS, MAX_WATCHED_THRESHOLDS, VOLTAGE_LIMIT_TOLERANCE_V,
+};
+use crate::enums::CrossingKind;
+use crate::eps::EPS;
+use crate::events::{EpsEvent, EventSink, NullSink, StepRecord};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossing {
+    pub time_s: f64, // Mission elapsed time, within the resolution after the actual crossing
+    pub kind: CrossingKind,
+}
+
+/// What [`AdaptiveStepper::advance`] did over one call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveStep {
+    pub record: StepRecord, // The sub-steps merged into one
+    pub crossings: Vec<Crossing, MAX_STEP_CROSSINGS>,
+    pub substeps: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveStepper {
+    max_step_s: f64,
+    resolution_s: f64,
+    soc_thresholds: Vec<f64, MAX_WATCHED_THRESHOLDS>,     // Percent
+    voltage_thresholds: Vec<f64, MAX_WATCHED_THRESHOLDS>, // Battery terminal voltage
+    watch_eclipse: bool,                                  // Needs an orbit
+}
+
+// Watched quantities at one instant
+#[derive(Debug, Clone, Copy)]
+struct Probe {
+    soc_percentage: f64,
+    voltage_v: f64,
+    sunlit: Option<bool>,
+}
+
+impl Probe {
+    fn of(eps: &EPS) -> Self {
+        let battery = eps.get_battery();
+        Probe {
+            soc_percentage: battery.get_soc_percentage(),
+            voltage_v: battery.get_terminal_voltage_v(),
+            sunlit: eps.get_orbit().map(|orbit| orbit.is_sunlit(eps.get_elapsed_time_s())),
+        }
+    }
+}
+
+impl Default for AdaptiveStepper {
+    fn default() -> Self {
+        AdaptiveStepper::new(ADAPTIVE_MAX_STEP_S, ADAPTIVE_RESOLUTION_S)
+    }
+}
+
+impl AdaptiveStepper {
+    /// Watches the eclipse boundaries only; add thresholds with the `with_*` methods.
+    /// See [`AdaptiveStepper::with_step_limits`] for how the limits are bounded.
+    pub fn new(max_step_s: f64, resolution_s: f64) -> Self {
+        AdaptiveStepper {
+            max_step_s: ADAPTIVE_MAX_STEP_S,
+            resolution_s: ADAPTIVE_RESOLUTION_S,
+            soc_thresholds: Vec::new(),
+            voltage_thresholds: Vec::new(),
+            watch_eclipse: true,
+        }
+        .with_step_limits(max_step_s, resolution_s)
+    }
+
+    /// Watches the thresholds `eps` acts on: shedding tiers, mode SoC guards, the SafeMode bus voltage
+    /// guard and the battery voltage limits.
+    pub fn for_eps(eps: &EPS) -> Self {
+        let thresholds = eps.get_mode_manager().get_thresholds();
+        let circuit = eps.get_battery().get_equivalent_circuit();
+        let mut stepper = AdaptiveStepper::default();
+        for tier in eps.get_shedding_policy().get_tiers() {
+            stepper = stepper.with_soc_threshold(tier.get_shed_below_soc()).with_soc_threshold(tier.get_restore_above_soc());
+        }
+        stepper
+            .with_soc_threshold(thresholds.safe_entry_soc)
+            .with_soc_threshold(thresholds.safe_exit_soc)
+            .with_soc_threshold(thresholds.payload_exit_soc)
+            .with_voltage_threshold(thresholds.safe_entry_bus_voltage_v)
+            // The limits clamp the terminal voltage onto them up to rounding
+            .with_voltage_threshold(circuit.get_min_voltage_v() + VOLTAGE_LIMIT_TOLERANCE_V)
+            .with_voltage_threshold(circuit.get_max_voltage_v() - VOLTAGE_LIMIT_TOLERANCE_V)
+    }
+
+    /// Limits below [`ADAPTIVE_MIN_STEP_S`], or NaN, are raised to it. The resolution is at most the maximum step.
+    pub fn with_step_limits(mut self, max_step_s: f64, resolution_s: f64) -> Self {
+        self.max_step_s = max_step_s.max(ADAPTIVE_MIN_STEP_S);
+        self.resolution_s = resolution_s.max(ADAPTIVE_MIN_STEP_S).min(self.max_step_s);
+        self
+    }
+
+    /// Thresholds already watched, and any beyond [`MAX_WATCHED_THRESHOLDS`], are ignored.
+    pub fn with_soc_threshold(mut self, soc_percentage: f64) -> Self {
+        if !self.soc_thresholds.contains(&soc_percentage) {
+            let _ = self.soc_thresholds.push(soc_percentage);
+        }
+        self
+    }
+
+    /// Thresholds already watched, and any beyond [`MAX_WATCHED_THRESHOLDS`], are ignored.
+    pub fn with_voltage_threshold(mut self, voltage_v: f64) -> Self {
+        if voltage_v > 0.0 && !self.voltage_thresholds.contains(&voltage_v) {
+            let _ = self.voltage_thresholds.push(voltage_v);
+        }
+        self
+    }
+
+    pub fn without_eclipse(mut self) -> Self {
+        self.watch_eclipse = false;
+        self
+    }
+
+    pub fn get_max_step_s(&self) -> f64 {
+        self.max_step_s
+    }
+
+    pub fn get_resolution_s(&self) -> f64 {
+        self.resolution_s
+    }
+
+    pub fn get_soc_thresholds(&self) -> &[f64] {
+        &self.soc_thresholds
+    }
+
+    pub fn get_voltage_thresholds(&self) -> &[f64] {
+        &self.voltage_thresholds
+    }
+
+    /// Advances `eps` by `duration_s` in sub-steps of at most the maximum step, stopping each one at the
+    /// first watched crossing.
+    pub fn advance(&self, eps: &mut EPS, duration_s: f64) -> AdaptiveStep {
+        self.advance_with(eps, duration_s, &mut NullSink)
+    }
+
+    /// [`AdaptiveStepper::advance`], publishing every sub-step's events and each crossing to `sink`.
+    pub fn advance_with(&self, eps: &mut EPS, duration_s: f64, sink: &mut dyn EventSink) -> AdaptiveStep {
+        let end_s = eps.get_elapsed_time_s() + duration_s;
+        let mut merged: Option<StepRecord> = None;
+        let mut crossings = Vec::new();
+        let mut substeps = 0;
+        loop {
+            let step_s = (end_s - eps.get_elapsed_time_s()).clamp(0.0, self.max_step_s);
+            let before = Probe::of(eps);
+            let (mut trial, mut record) = trial_step(eps, step_s);
+            let mut crossed = self.get_crossings(&before, &Probe::of(&trial));
+            if !crossed.is_empty() {
+                // Shortest sub-step still ending past a crossing
+                let (mut low_s, mut high_s) = (0.0, step_s);
+                while high_s - low_s > self.resolution_s {
+                    let mid_s = 0.5 * (low_s + high_s);
+                    let (mid_trial, mid_record) = trial_step(eps, mid_s);
+                    let mid_crossed = self.get_crossings(&before, &Probe::of(&mid_trial));
+                    if mid_crossed.is_empty() {
+                        low_s = mid_s;
+                    } else {
+                        (high_s, trial, record, crossed) = (mid_s, mid_trial, mid_record, mid_crossed);
+                    }
+                }
+            }
+
+            *eps = trial;
+            record.publish(sink);
+            for kind in crossed {
+                let crossing = Crossing { time_s: eps.get_elapsed_time_s(), kind };
+                sink.on_event(&EpsEvent::ThresholdCrossed(crossing));
+                let _ = crossings.push(crossing); // The sink sees them all
+            }
+            match merged.as_mut() {
+                Some(merged) => merged.absorb(&record),
+                None => merged = Some(record),
+            }
+            substeps += 1;
+            if end_s - eps.get_elapsed_time_s() <= 1e-9 {
+                break;
+            }
+        }
+        let record = merged.expect("at least one sub-step runs");
+        AdaptiveStep { record, crossings, substeps }
+    }
+
+    fn get_crossings(&self, before: &Probe, after: &Probe) -> Vec<CrossingKind, MAX_STEP_CROSSINGS> {
+        let mut crossed = Vec::new();
+        for threshold in self.soc_thresholds.iter().copied() {
+            if let Some(falling) = crossing(before.soc_percentage, after.soc_percentage, threshold) {
+                let _ = crossed.push(if falling { CrossingKind::SocBelow(threshold) } else { CrossingKind::SocAbove(threshold) });
+            }
+        }
+        for threshold in self.voltage_thresholds.iter().copied() {
+            if let Some(falling) = crossing(before.voltage_v, after.voltage_v, threshold) {
+                let _ = crossed.push(if falling { CrossingKind::VoltageBelow(threshold) } else { CrossingKind::VoltageAbove(threshold) });
+            }
+        }
+        if self.watch_eclipse
+            && let (Some(was_sunlit), Some(is_sunlit)) = (before.sunlit, after.sunlit)
+            && was_sunlit != is_sunlit
+        {
+            let _ = crossed.push(if is_sunlit { CrossingKind::LeftEclipse } else { CrossingKind::EnteredEclipse });
+        }
+        crossed
+    }
+}
+
+// Some(true) when falling onto or below `threshold`, Some(false) when rising onto or above it
+fn crossing(before: f64, after: f64, threshold: f64) -> Option<bool> {
+    if before > threshold && after <= threshold {
+        Some(true)
+    } else if before < threshold && after >= threshold {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn trial_step(eps: &EPS, step_s: f64) -> (EPS, StepRecord) {
+    let mut trial = eps.clone();
+    let record = trial.manage_power(step_s / 3600.0);
+    (trial, record)
+}
@@ -0,0 +1,103 @@
+use eps::consts::HOURS_PER_YEAR;
+use eps::{Battery, BatteryAgeing, BatteryFault, EPS, Load, LoadId, PowerDistributionUnit, RainflowCounter};
+
+#[test]
+fn rainflow_matches_the_astm_e1049_example() {
+    let mut counter = RainflowCounter::new(0.0);
+    let mut cycles = Vec::new();
+    for value in [-2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -2.0] {
+        counter.push_sample(value, |range, count| cycles.push((range, count)));
+    }
+    cycles.extend(counter.residual_ranges().map(|range| (range, 0.5)));
+
+    let count_of = |range: f64| cycles.iter().filter(|(r, _)| *r == range).map(|(_, c)| c).sum::<f64>();
+    assert_eq!(count_of(3.0), 0.5);
+    assert_eq!(count_of(4.0), 1.5);
+    assert_eq!(count_of(6.0), 0.5);
+    assert_eq!(count_of(8.0), 1.0);
+    assert_eq!(count_of(9.0), 0.5);
+    assert_eq!(counter.get_full_cycles(), 1);
+}
+
+#[test]
+fn partial_cycles_are_counted() {
+    let mut counter = RainflowCounter::new(0.005);
+    // Small wiggles below the hysteresis are not cycles
+    for i in 0..100 {
+        counter.push_sample(0.8 + 0.001 * (i % 2) as f64, |_, _| {});
+    }
+    assert_eq!(counter.get_cycle_count(), 0.0);
+    for _ in 0..10 {
+        for soc in [0.8, 0.7, 0.75, 0.7, 0.8] {
+            counter.push_sample(soc, |_, _| {});
+        }
+    }
+    // The 0.7-0.8 swings share their peaks, so they pair up as half cycles
+    assert_eq!(counter.get_full_cycles(), 10);
+    assert_eq!(counter.get_cycle_count(), 19.0);
+}
+
+fn fade_after_leo_year(depth_of_discharge: f64) -> f64 {
+    let mut ageing = BatteryAgeing::new().with_calendar_fade(0.0);
+    // ~5500 orbits per year, sampled ten times per orbit
+    for orbit in 0..5500 {
+        for i in 0..10 {
+            let phase = (orbit * 10 + i) as f64 / 10.0 * core::f64::consts::TAU;
+            ageing.update(1.0 - depth_of_discharge * 0.5 * (1.0 - phase.cos()), 25.0, 0.0);
+        }
+    }
+    1.0 - ageing.get_health()
+}
+
+#[test]
+fn cycle_fade_grows_with_depth_of_discharge() {
+    let shallow = fade_after_leo_year(0.2);
+    let deep = fade_after_leo_year(0.4);
+    assert!(shallow > 0.01 && shallow < 0.1, "shallow fade {shallow}");
+    assert!((deep / shallow - 2f64.powf(1.8)).abs() < 0.05, "ratio {}", deep / shallow);
+}
+
+#[test]
+fn calendar_fade_follows_square_root_of_time_and_temperature() {
+    let mut ageing = BatteryAgeing::new();
+    ageing.update(0.8, 25.0, HOURS_PER_YEAR);
+    assert!((ageing.get_calendar_fade() - 0.02).abs() < 1e-9);
+    ageing.update(0.8, 25.0, 3.0 * HOURS_PER_YEAR);
+    assert!((ageing.get_calendar_fade() - 0.04).abs() < 1e-9);
+
+    let mut hot = BatteryAgeing::new();
+    hot.update(0.8, 35.0, HOURS_PER_YEAR);
+    assert!((hot.get_calendar_fade() - 0.04).abs() < 1e-9);
+}
+
+#[test]
+fn battery_ages_from_its_own_charge_history() {
+    let mut battery = Battery::new("BAT0", 40.0, 40.0, 7.4, 40.0, 40.0);
+    for _ in 0..20 {
+        while battery.get_soc_percentage() > 70.0 {
+            battery.discharge(10.0, 0.05);
+        }
+        while battery.get_soc_percentage() < 95.0 {
+            battery.charge(20.0, 0.05);
+        }
+    }
+    assert!(battery.get_cycles() >= 18.0);
+    assert!(battery.get_health() < 1.0);
+    assert!(battery.get_effective_capacity_wh() < 40.0);
+}
+
+#[test]
+fn a_step_that_sheds_loads_ages_the_battery_once() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    let battery = Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0);
+    let mut reference = battery.clone();
+    let mut eps = EPS::new(heapless::Vec::new(), battery, pdu);
+    // Blocks discharge, so the critical load is still short after shedding
+    eps.raise_battery_fault(BatteryFault::UnderVoltage);
+    let record = eps.manage_power(1.0);
+    assert_eq!(record.battery_energy_out_wh, 0.0);
+    reference.rest(1.0);
+    assert_eq!(eps.get_battery().get_ageing().get_calendar_fade(), reference.get_ageing().get_calendar_fade());
+}
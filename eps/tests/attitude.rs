@@ -0,0 +1,83 @@
+use eps::{
+    Attitude, AttitudeMode, Battery, CubeSatFormFactor, EPS, Load, LoadId, Orbit, PowerDistributionUnit, SatelliteOperationalMode,
+    SolarPanel, Vector3,
+};
+use heapless::Vec;
+
+fn assert_close(a: Vector3, b: Vector3) {
+    assert!((a - b).norm() < 1e-6, "{a:?} != {b:?}");
+}
+
+fn orbit_average_power_w(attitude: Attitude) -> f64 {
+    let mut panels = Vec::new();
+    for panel in SolarPanel::body_mounted_faces(CubeSatFormFactor::ThreeU, 0.28) {
+        panels.push(panel).unwrap();
+    }
+    for mut wing in SolarPanel::deployable_wings(0.06, 0.28, Vector3::X) {
+        wing.deploy();
+        panels.push(wing).unwrap();
+    }
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    let orbit = Orbit::new(500.0, 97.4, 30.0, 20.0, 0.0);
+    let period_h = orbit.get_period_s() / 3600.0;
+    let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 20.0, 8.2, 20.0, 20.0), pdu)
+        .with_orbit(orbit)
+        .with_attitude(attitude);
+
+    let steps = 600;
+    let mut energy_wh = 0.0;
+    for _ in 0..steps {
+        eps.manage_power(period_h / steps as f64);
+        energy_wh += eps.get_total_generated_power_w() * period_h / steps as f64;
+    }
+    energy_wh / period_h
+}
+
+#[test]
+fn sun_pointing_beats_tumbling_on_orbit_average() {
+    let sun_pointing_w = orbit_average_power_w(Attitude::new(AttitudeMode::SunPointing { axis_body: Vector3::X }, Vector3::X));
+    let tumbling_w = orbit_average_power_w(Attitude::new(
+        AttitudeMode::Tumbling { body_rates_deg_s: Vector3::new(2.0, -3.0, 5.0) },
+        Vector3::X,
+    ));
+    assert!(tumbling_w > 0.0);
+    assert!(sun_pointing_w > 1.5 * tumbling_w, "sun pointing {sun_pointing_w} W, tumbling {tumbling_w} W");
+}
+
+#[test]
+fn nadir_pointing_sees_the_sun_overhead_at_orbit_noon() {
+    let orbit = Orbit::new(500.0, 51.6, 0.0, 0.0, 0.0);
+    let mut attitude = Attitude::new(AttitudeMode::NadirPointing, Vector3::X);
+    assert_close(attitude.update(Some(&orbit), 0.0), -Vector3::Z);
+    // A quarter orbit later the sun is behind the spacecraft, against the velocity
+    assert_close(attitude.update(Some(&orbit), orbit.get_period_s() / 4.0), -Vector3::X);
+}
+
+#[test]
+fn tumbling_rotates_the_sun_vector_against_the_body_rates() {
+    let mut attitude = Attitude::new(AttitudeMode::Tumbling { body_rates_deg_s: Vector3::new(0.0, 0.0, 1.0) }, Vector3::X);
+    attitude.update(None, 0.0);
+    assert_close(attitude.update(None, 90.0), -Vector3::Y);
+}
+
+#[test]
+fn safe_mode_points_the_safe_axis_at_the_sun() {
+    let mut attitude = Attitude::new(AttitudeMode::NadirPointing, Vector3::Z).with_safe_pointing_axis(Vector3::X);
+    attitude.enter_safe_mode();
+    assert_close(attitude.update(None, 0.0), Vector3::X);
+    attitude.exit_safe_mode();
+    assert_eq!(attitude.get_mode(), &AttitudeMode::NadirPointing);
+
+    // The EPS leaves the survival attitude with SafeMode, unless the ground commanded another one meanwhile
+    let mut eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 20.0, 8.2, 20.0, 20.0), PowerDistributionUnit::new())
+        .with_attitude(Attitude::new(AttitudeMode::NadirPointing, Vector3::Z));
+    eps.set_satellite_mode(SatelliteOperationalMode::SafeMode);
+    assert_eq!(eps.get_attitude().get_mode(), &AttitudeMode::SunPointing { axis_body: Vector3::Z });
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalSunlit);
+    assert_eq!(eps.get_attitude().get_mode(), &AttitudeMode::NadirPointing);
+    eps.set_satellite_mode(SatelliteOperationalMode::SafeMode);
+    eps.set_attitude_mode(AttitudeMode::InertialHold);
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalSunlit);
+    assert_eq!(eps.get_attitude().get_mode(), &AttitudeMode::InertialHold);
+}
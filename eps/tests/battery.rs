@@ -0,0 +1,158 @@
+use eps::{
+    Battery, BatteryFault, BatteryState, BatteryThermal, EPS, EpsEvent, EquivalentCircuit, Load, LoadId, PowerDistributionUnit, SatelliteOperationalMode,
+    SolarPanel, Vector3,
+};
+
+fn battery_at(soc: f64) -> Battery {
+    // 2S Li-ion, 40 Wh
+    Battery::new("BAT0", 40.0, 40.0 * soc, 7.4, 40.0, 40.0)
+}
+
+#[test]
+fn open_circuit_voltage_follows_soc() {
+    let circuit = EquivalentCircuit::li_ion(2);
+    assert!((circuit.get_open_circuit_voltage_v(0.0) - 6.0).abs() < 1e-9);
+    assert!((circuit.get_open_circuit_voltage_v(1.0) - 8.4).abs() < 1e-9);
+    assert!((circuit.get_open_circuit_voltage_v(0.55) - 7.54).abs() < 1e-9);
+    assert!(battery_at(0.8).get_terminal_voltage_v() > battery_at(0.3).get_terminal_voltage_v());
+}
+
+#[test]
+fn terminal_voltage_sags_under_discharge_and_recovers_at_rest() {
+    let mut battery = battery_at(0.6);
+    let ocv_v = battery.get_open_circuit_voltage_v();
+    let delivered_w = battery.discharge(10.0, 60.0 / 3600.0);
+    assert!((delivered_w - 10.0).abs() < 1e-6);
+    let current_a = battery.get_current_a();
+    assert!(current_a > 0.0);
+    // Immediate drop over R0 plus part of the RC polarisation
+    let sag_v = ocv_v - battery.get_terminal_voltage_v();
+    assert!(sag_v > current_a * 0.08, "sag {sag_v} V");
+
+    battery.rest(1.0);
+    assert!((battery.get_terminal_voltage_v() - battery.get_open_circuit_voltage_v()).abs() < 1e-3);
+}
+
+#[test]
+fn terminal_voltage_rises_under_charge() {
+    let mut battery = battery_at(0.5);
+    let ocv_v = battery.get_open_circuit_voltage_v();
+    let accepted_a = battery.charge_current(2.0, 0.1);
+    assert_eq!(accepted_a, 2.0);
+    assert!(battery.get_terminal_voltage_v() > battery.get_open_circuit_voltage_v());
+    assert!(battery.get_open_circuit_voltage_v() > ocv_v);
+    assert_eq!(battery.get_status(), &BatteryState::Charging);
+}
+
+#[test]
+fn over_voltage_limit_tapers_the_charge_current() {
+    let mut battery = battery_at(0.98);
+    let accepted_a = battery.charge_current(5.0, 0.01);
+    assert!(accepted_a < 5.0);
+    assert!(battery.get_terminal_voltage_v() <= battery.get_equivalent_circuit().get_max_voltage_v() + 1e-9);
+}
+
+#[test]
+fn charge_at_voltage_stops_at_the_open_circuit_voltage() {
+    let mut battery = battery_at(0.5);
+    let ocv_v = battery.get_open_circuit_voltage_v();
+    assert_eq!(battery.charge_at_voltage(ocv_v - 0.1, 3.0, 0.01), 0.0);
+    assert!(battery.charge_at_voltage(ocv_v + 0.5, 3.0, 0.01) > 0.0);
+}
+
+#[test]
+fn under_voltage_limit_cuts_off_discharge() {
+    let mut battery = battery_at(0.02);
+    let delivered_a = battery.discharge_current(10.0, 0.01);
+    assert!(delivered_a < 10.0);
+    assert!(battery.get_terminal_voltage_v() >= battery.get_equivalent_circuit().get_min_voltage_v() - 1e-9);
+    for _ in 0..100 {
+        battery.discharge(20.0, 0.01);
+    }
+    assert_eq!(battery.get_status(), &BatteryState::Fault(BatteryFault::UnderVoltage));
+    assert!(battery.get_faults().is_active(BatteryFault::UnderVoltage));
+}
+
+#[test]
+fn charging_is_inhibited_below_the_temperature_limit() {
+    let mut battery = battery_at(0.5).with_thermal(BatteryThermal::new(-5.0));
+    assert_eq!(battery.charge_current(2.0, 0.1), 0.0);
+    assert_eq!(battery.get_status(), &BatteryState::Fault(BatteryFault::ChargeUnderTemperature));
+    assert!(battery.get_faults().is_active(BatteryFault::ChargeUnderTemperature));
+    // Discharge is unaffected, and the fault clears itself once the cells are warm
+    assert!(battery.discharge_current(1.0, 0.01) > 0.0);
+    assert!(!BatteryFault::UnderVoltage.clears_itself());
+    battery.update_temperature(true, 2.0, 10.0);
+    assert!(!battery.get_thermal().is_charge_inhibited());
+    assert!(battery.charge_current(2.0, 0.1) > 0.0);
+    assert!(battery.get_faults().get_active().is_empty());
+
+    let mut battery = battery_at(0.5).with_thermal(BatteryThermal::new(-5.0).with_charge_inhibit_below(-10.0));
+    assert_eq!(battery.charge_current(2.0, 0.1), 2.0);
+
+    // The EPS refuses the surplus and publishes the fault when it is raised
+    let mut panel = SolarPanel::new("+Z", 0.03, 0.3, Vector3::Z);
+    panel.deploy();
+    let mut panels = heapless::Vec::new();
+    panels.push(panel).unwrap();
+    let cold = battery_at(0.5).with_thermal(BatteryThermal::new(-5.0));
+    let mut eps = EPS::new(panels, cold, PowerDistributionUnit::new());
+    eps.set_sun_vector_body(Vector3::Z);
+    let mut raised = std::vec::Vec::new();
+    let record = eps.manage_power_with(1.0 / 60.0, &mut |event: &EpsEvent| {
+        if let EpsEvent::FaultRaised(fault) = event {
+            raised.push(*fault);
+        }
+    });
+    assert!(record.charge_inhibited);
+    assert_eq!(record.faults_raised.as_slice(), [BatteryFault::ChargeUnderTemperature]);
+    assert_eq!(raised, [BatteryFault::ChargeUnderTemperature]);
+    assert_eq!(record.battery_energy_in_wh, 0.0);
+}
+
+#[test]
+fn cold_battery_sags_more_and_holds_back_capacity() {
+    let mut warm = battery_at(0.5);
+    let mut cold = battery_at(0.5).with_thermal(BatteryThermal::new(-20.0));
+    warm.discharge_current(2.0, 0.01);
+    cold.discharge_current(2.0, 0.01);
+    assert!(cold.get_terminal_voltage_v() < warm.get_terminal_voltage_v() - 0.1);
+    assert!(cold.get_available_energy_wh() < warm.get_available_energy_wh() - 10.0);
+}
+
+#[test]
+fn losses_and_heater_warm_the_battery() {
+    let mut idle = battery_at(0.5);
+    let mut loaded = battery_at(0.5);
+    loaded.discharge_current(4.0, 0.5);
+    assert!(loaded.get_heat_generated_w() > 1.0);
+    idle.update_temperature(false, 0.0, 0.5);
+    loaded.update_temperature(false, 0.0, 0.5);
+    assert!(loaded.get_temperature_c() > idle.get_temperature_c());
+
+    // Long eclipse soak: the environment pulls the battery towards -5 degC, 1 W of heater adds 5 K
+    let mut heated = battery_at(0.5);
+    idle.update_temperature(false, 0.0, 10.0);
+    heated.update_temperature(false, 1.0, 10.0);
+    assert!((idle.get_temperature_c() + 5.0).abs() < 0.01);
+    assert!((heated.get_temperature_c() - 0.0).abs() < 0.01);
+}
+
+#[test]
+fn heaters_load_keeps_the_battery_chargeable_through_eclipse() {
+    let soak = |heaters_on: bool| {
+        let mut pdu = PowerDistributionUnit::new();
+        pdu.add_load(Load::new(LoadId::OBC, 0.2, true)).unwrap();
+        pdu.add_load(Load::new(LoadId::HEATERS, 1.5, false)).unwrap();
+        let mut eps = EPS::new(heapless::Vec::new(), battery_at(0.8), pdu);
+        // Nominal modes switch the heaters on
+        eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+        eps.get_pdu_mut().switch_load(LoadId::HEATERS, heaters_on).unwrap();
+        for _ in 0..60 {
+            eps.manage_power(0.05);
+        }
+        eps.get_battery().get_thermal().is_charge_inhibited()
+    };
+    assert!(!soak(true));
+    assert!(soak(false));
+}
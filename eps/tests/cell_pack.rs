@@ -0,0 +1,128 @@
+use eps::{Battery, BatteryFault, BatteryState, Cell, CellFailure, CellPack, PackError};
+
+const STEP_H: f64 = 60.0 / 3600.0;
+
+#[test]
+fn pack_figures_follow_the_configuration() {
+    let pack = CellPack::li_ion(2, 4, 0.5).unwrap();
+    assert_eq!((pack.get_series(), pack.get_parallel()), (2, 4));
+    assert!((pack.get_capacity_wh() - 2.0 * 4.0 * 2.6 * 3.7).abs() < 1e-9);
+    assert!((pack.get_nominal_voltage_v() - 7.4).abs() < 1e-9);
+    assert_eq!(pack.get_cell_telemetry().len(), 8);
+    assert!(matches!(CellPack::li_ion(9, 1, 0.5), Err(PackError::TooManyCells)));
+    assert!(matches!(CellPack::li_ion(2, 0, 0.5), Err(PackError::TooManyCells)));
+}
+
+#[test]
+fn spread_is_reproducible_per_seed() {
+    let pack = |seed| CellPack::li_ion(4, 2, 0.5).unwrap().with_spread(0.05, 0.1, seed);
+    assert_eq!(pack(3), pack(3));
+    assert_ne!(pack(3), pack(4));
+    let capacities: Vec<f64> = pack(3).get_cell_telemetry().iter().map(|cell| cell.capacity_ah).collect();
+    assert!(capacities.iter().all(|capacity_ah| (capacity_ah - 2.6).abs() <= 2.6 * 0.05));
+    assert!(capacities.windows(2).any(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn weakest_group_limits_capacity_and_discharge() {
+    let mut pack = CellPack::li_ion(2, 1, 0.5).unwrap().without_balancing();
+    pack.set_cell(1, 0, Cell::new(2.0, 0.05, 0.5)).unwrap();
+    assert!((pack.get_capacity_wh() - 2.0 * 2.0 * 3.7).abs() < 1e-9);
+
+    let mut battery = Battery::from_pack("BAT0", pack, 40.0, 40.0);
+    for _ in 0..600 {
+        battery.discharge(5.0, STEP_H);
+        if battery.get_status() == &BatteryState::Empty {
+            break;
+        }
+    }
+    let pack = battery.get_pack().unwrap();
+    let telemetry = pack.get_cell_telemetry();
+    // The small cell runs out while the healthy one still holds charge
+    assert!(telemetry[1].soc_percentage < 5.0, "{telemetry:?}");
+    assert!(telemetry[0].soc_percentage > 10.0, "{telemetry:?}");
+    assert!(battery.get_soc_percentage() < 5.0);
+}
+
+#[test]
+fn available_energy_is_the_weakest_group_charge() {
+    // A resistive cell limits the current, not the energy it still holds
+    let mut pack = CellPack::li_ion(2, 1, 0.5).unwrap().without_balancing();
+    pack.set_cell(1, 0, Cell::new(2.0, 2.0, 0.5)).unwrap();
+    assert!((pack.get_available_charge_ah(1.0) - 1.0).abs() < 1e-9);
+    assert!((pack.get_available_charge_ah(0.8) - 0.6).abs() < 1e-9);
+    let battery = Battery::from_pack("BAT0", pack, 40.0, 40.0);
+    assert!((battery.get_available_energy_wh() - 1.0 * 7.4).abs() < 1e-9);
+}
+
+#[test]
+fn passive_balancing_converges() {
+    let mut pack = CellPack::li_ion(3, 1, 0.4).unwrap();
+    pack.set_group_soc(2, 0.6).unwrap();
+    let mut battery = Battery::from_pack("BAT0", pack.clone(), 40.0, 40.0);
+    let mut unbalanced = Battery::from_pack("BAT0", pack.without_balancing(), 40.0, 40.0);
+
+    // Only a charging pack is bled
+    battery.rest(STEP_H);
+    battery.discharge(1.0, STEP_H);
+    assert!(!battery.get_pack().unwrap().is_balancing());
+    battery.charge(1.0, STEP_H);
+    assert!(battery.get_pack().unwrap().is_balancing());
+    assert!(battery.get_pack().unwrap().get_cell_telemetry()[2].is_bleeding);
+    assert!(battery.get_heat_generated_w() > 0.0);
+    for _ in 0..(8 * 60) {
+        battery.charge(1.0, STEP_H);
+        unbalanced.charge(1.0, STEP_H);
+    }
+    let spread = |battery: &Battery| {
+        let pack = battery.get_pack().unwrap();
+        pack.get_max_soc() - pack.get_soc()
+    };
+    assert!(spread(&battery) < 0.03, "spread {}", spread(&battery));
+    assert!((spread(&unbalanced) - 0.2).abs() < 1e-3);
+    assert!(!battery.get_pack().unwrap().is_balancing());
+}
+
+#[test]
+fn fullest_group_ends_the_charge() {
+    let mut pack = CellPack::li_ion(2, 1, 0.8).unwrap().without_balancing();
+    pack.set_group_soc(0, 0.95).unwrap();
+    let mut battery = Battery::from_pack("BAT0", pack, 40.0, 40.0);
+    for _ in 0..120 {
+        battery.charge(10.0, STEP_H);
+    }
+    let pack = battery.get_pack().unwrap();
+    assert!(pack.get_max_cell_voltage_v() <= 4.2 + 1e-6);
+    assert!(pack.get_soc() < 0.9);
+    assert!(!battery.get_faults().is_active(BatteryFault::OverVoltage));
+}
+
+#[test]
+fn open_cell_reduces_capacity_and_an_open_string_blocks_the_pack() {
+    let mut battery = Battery::from_pack("BAT0", CellPack::li_ion(2, 2, 0.5).unwrap(), 40.0, 40.0);
+    let capacity_wh = battery.get_effective_capacity_wh();
+    battery.fail_cell(0, 1, CellFailure::Open).unwrap();
+    assert!((battery.get_effective_capacity_wh() - capacity_wh / 2.0).abs() < 1e-9);
+    assert!(!battery.get_faults().is_active(BatteryFault::OpenCircuit));
+    assert!(battery.discharge(5.0, STEP_H) > 0.0);
+
+    battery.fail_cell(0, 0, CellFailure::Open).unwrap();
+    assert!(battery.get_faults().is_active(BatteryFault::OpenCircuit));
+    assert_eq!(battery.discharge(5.0, STEP_H), 0.0);
+    assert_eq!(battery.get_status(), &BatteryState::Fault(BatteryFault::OpenCircuit));
+    assert!(matches!(battery.fail_cell(2, 0, CellFailure::Open), Err(PackError::CellNotFound)));
+}
+
+#[test]
+fn shorted_cell_drops_its_group_from_the_string() {
+    let mut battery = Battery::from_pack("BAT0", CellPack::li_ion(4, 2, 0.5).unwrap(), 40.0, 40.0);
+    let ocv_v = battery.get_open_circuit_voltage_v();
+    battery.fail_cell(1, 0, CellFailure::Short).unwrap();
+    assert!(battery.get_faults().is_active(BatteryFault::CellShort));
+    assert!((battery.get_open_circuit_voltage_v() - ocv_v * 3.0 / 4.0).abs() < 1e-9);
+    assert_eq!(battery.get_pack().unwrap().get_active_series(), 3);
+    let telemetry = battery.get_pack().unwrap().get_cell_telemetry();
+    assert_eq!(telemetry[2].failure, Some(CellFailure::Short));
+    assert_eq!(telemetry[3].soc_percentage, 0.0);
+    assert!(Battery::new("BAT1", 40.0, 20.0, 7.4, 40.0, 40.0).fail_cell(0, 0, CellFailure::Short).is_err());
+}
@@ -0,0 +1,26 @@
+// Fixtures shared by the integration tests; each test crate uses its own subset
+#![allow(dead_code)]
+
+use eps::{Battery, EPS, Load, LoadId, PowerDistributionUnit, SatelliteOperationalMode, StepRecord};
+use heapless::Vec;
+
+pub const MINUTE_H: f64 = 1.0 / 60.0;
+
+// Battery-only spacecraft draining 6.5 W from 20 % charge
+pub fn draining_eps() -> EPS {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 6.5, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    let mut eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 8.0, 7.4, 20.0, 20.0), pdu);
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    eps
+}
+
+// Steps `eps` by `step_h` up to `time_s` and returns the step records
+pub fn run_until(eps: &mut EPS, time_s: f64, step_h: f64) -> std::vec::Vec<StepRecord> {
+    let mut records = std::vec::Vec::new();
+    while eps.get_elapsed_time_s() < time_s - 1e-9 {
+        records.push(eps.manage_power(step_h));
+    }
+    records
+}
@@ -0,0 +1,183 @@
+use eps::{
+    Battery, DeploymentError, DeploymentFailure, DeploymentPhase, DeploymentSequencer, EPS, LatchingCurrentLimiter, LclRecovery, Load,
+    LoadId, PowerDistributionUnit, SatelliteOperationalMode, SolarPanel, Vector3,
+};
+use heapless::Vec;
+
+mod common;
+use common::{MINUTE_H, run_until};
+
+const BURN_WIRE_1: LoadId = LoadId(8);
+const BURN_WIRE_2: LoadId = LoadId(9);
+
+// Body-mounted +Z panel and two stowed wings facing the sun once open
+fn stowed_eps(failures: &[(usize, DeploymentFailure)]) -> EPS {
+    stowed_eps_with(Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), LatchingCurrentLimiter::for_load(3.0), failures)
+}
+
+fn stowed_eps_with(battery: Battery, first_wire_lcl: LatchingCurrentLimiter, failures: &[(usize, DeploymentFailure)]) -> EPS {
+    let mut panels = Vec::new();
+    let mut body = SolarPanel::new("+Z", 0.01, 0.3, Vector3::Z);
+    body.deploy();
+    panels.push(body).unwrap();
+    for wing in SolarPanel::deployable_wings(0.06, 0.3, Vector3::Z) {
+        panels.push(wing).unwrap();
+    }
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.add_load(Load::new(BURN_WIRE_1, 3.0, false).with_lcl(first_wire_lcl)).unwrap();
+    pdu.add_load(Load::new(BURN_WIRE_2, 3.0, false)).unwrap();
+
+    let mut deployment = DeploymentSequencer::default();
+    deployment.add_panel(1, BURN_WIRE_1).unwrap();
+    deployment.add_panel(2, BURN_WIRE_2).unwrap();
+    for (panel_index, failure) in failures.iter().copied() {
+        deployment.inject_failure(panel_index, failure).unwrap();
+    }
+    let mut eps = EPS::new(panels, battery, pdu).with_deployment(deployment).unwrap();
+    eps.set_sun_vector_body(Vector3::Z);
+    eps
+}
+
+#[test]
+fn wings_wait_for_the_hold_off_and_burn_one_at_a_time() {
+    let mut eps = stowed_eps(&[]);
+    run_until(&mut eps, 1800.0, MINUTE_H);
+    let stowed_w = eps.get_total_generated_power_w();
+    assert!(eps.get_solar_panels()[1..].iter().all(|wing| !wing.is_deployed()));
+    assert!(eps.get_pdu().get_schedule().is_empty());
+
+    // The first burn runs for 20 s of the step at 3 W
+    let burn = eps.manage_power(MINUTE_H);
+    assert!((burn.demanded_power_w - (0.5 + 3.0 * 20.0 / 60.0)).abs() < 1e-9);
+    assert!(burn.burn_attempts.is_empty());
+    let read = eps.manage_power(MINUTE_H);
+    assert_eq!(read.burn_attempts.len(), 1);
+    assert_eq!((read.burn_attempts[0].panel_index, read.burn_attempts[0].attempt), (1, 1));
+    assert!(read.burn_attempts[0].switch_closed);
+    // The second wing's burn starts as soon as the first switch was read
+    assert!((read.demanded_power_w - (0.5 + 3.0 * 20.0 / 60.0)).abs() < 1e-9);
+
+    eps.manage_power(MINUTE_H);
+    let deployment = eps.get_deployment().unwrap();
+    assert!(deployment.is_complete());
+    assert!(deployment.get_channels().iter().all(|channel| channel.is_switch_closed()));
+    assert_eq!(deployment.get_channel(2).unwrap().get_phase(), DeploymentPhase::Deployed);
+    // Each wing has six times the body panel's area
+    assert!((eps.get_total_generated_power_w() - 13.0 * stowed_w).abs() < 1e-6 * stowed_w);
+}
+
+#[test]
+fn burns_are_retried_after_a_cool_down() {
+    let mut eps = stowed_eps(&[(1, DeploymentFailure::ReleasesOnAttempt(2))]);
+    let mut attempts = std::vec::Vec::new();
+    while eps.get_elapsed_time_s() < 3600.0 {
+        attempts.extend(eps.manage_power(MINUTE_H).burn_attempts);
+    }
+    let wing1: std::vec::Vec<_> = attempts.iter().filter(|attempt| attempt.panel_index == 1).collect();
+    assert_eq!(wing1.len(), 2);
+    assert!(!wing1[0].switch_closed && wing1[1].switch_closed);
+    assert_eq!(wing1[1].start_s, 1800.0 + 20.0 + 300.0);
+    assert_eq!(eps.get_deployment().unwrap().get_channel(1).unwrap().get_attempts(), 2);
+    assert!(eps.get_deployment().unwrap().is_complete());
+}
+
+#[test]
+fn stuck_wing_fails_and_the_next_one_still_deploys() {
+    let mut eps = stowed_eps(&[(1, DeploymentFailure::Stuck)]);
+    run_until(&mut eps, 3.0 * 3600.0, MINUTE_H);
+    let deployment = eps.get_deployment().unwrap();
+    let stuck = deployment.get_channel(1).unwrap();
+    assert_eq!((stuck.get_phase(), stuck.get_attempts()), (DeploymentPhase::Failed, 3));
+    assert!(!eps.get_solar_panels()[1].is_deployed());
+    assert_eq!(deployment.get_channel(2).unwrap().get_phase(), DeploymentPhase::Deployed);
+    assert_eq!(eps.get_solar_panels()[1].get_power_output_w(), 0.0);
+}
+
+#[test]
+fn partial_deployment_and_a_failed_switch_are_told_apart_by_power() {
+    let mut eps = stowed_eps(&[(1, DeploymentFailure::Partial(0.4)), (2, DeploymentFailure::SwitchStuckOpen)]);
+    run_until(&mut eps, 3.0 * 3600.0, MINUTE_H);
+    let deployment = eps.get_deployment().unwrap();
+    assert!(deployment.get_channels().iter().all(|channel| channel.get_phase() == DeploymentPhase::Failed));
+    assert!(deployment.get_channels().iter().all(|channel| !channel.is_switch_closed()));
+    let [_, partial, full] = [0, 1, 2].map(|index| eps.get_solar_panels()[index].get_power_output_w());
+    assert!(full > 0.0);
+    assert!((partial - 0.4 * full).abs() < 1e-9);
+    assert_eq!(eps.get_solar_panels()[1].get_deployed_fraction(), 0.4);
+}
+
+#[test]
+fn burns_go_ahead_below_a_shed_threshold() {
+    // At 25 % the first tier is shed and its loads, the burn wires among them, are held off
+    let mut eps = stowed_eps_with(Battery::new("BAT0", 40.0, 10.0, 7.4, 20.0, 20.0), LatchingCurrentLimiter::for_load(3.0), &[]);
+    run_until(&mut eps, 1800.0, MINUTE_H);
+    assert!(eps.get_pdu().get_load(BURN_WIRE_1).unwrap().is_held_off());
+
+    let burn = eps.manage_power(MINUTE_H);
+    assert!(eps.get_pdu().get_load(BURN_WIRE_1).unwrap().is_burning());
+    assert!((burn.demanded_power_w - (0.5 + 3.0 * 20.0 / 60.0)).abs() < 1e-9);
+    // A mode transition during the burn leaves the wire to the sequencer
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    run_until(&mut eps, 2040.0, MINUTE_H);
+    let deployment = eps.get_deployment().unwrap();
+    assert!(deployment.is_complete());
+    assert!(deployment.get_channels().iter().all(|channel| channel.get_attempts() == 1 && channel.is_switch_closed()));
+    assert!(!eps.get_pdu().get_load(BURN_WIRE_1).unwrap().is_burning());
+}
+
+#[test]
+fn an_unpowered_burn_is_not_an_attempt() {
+    let lcl = LatchingCurrentLimiter::new(1.0, 0.01, LclRecovery::LatchOff);
+    let mut eps = stowed_eps_with(Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), lcl, &[]);
+    // A latch-up trips the first wire's channel before its burn
+    run_until(&mut eps, 1740.0, MINUTE_H);
+    eps.get_pdu_mut().inject_latch_up(BURN_WIRE_1, 5.0).unwrap();
+    eps.get_pdu_mut().switch_load(BURN_WIRE_1, true).unwrap();
+    let records = run_until(&mut eps, 1920.0, MINUTE_H);
+    assert!(records.iter().all(|record| record.burn_attempts.is_empty()));
+    let channel = eps.get_deployment().unwrap().get_channel(1).unwrap();
+    assert_eq!(channel.get_attempts(), 0);
+    assert!(matches!(channel.get_phase(), DeploymentPhase::Retrying { .. }));
+    assert!(!eps.get_solar_panels()[1].is_deployed());
+
+    // Once the ground has re-closed the channel, the repeated burn is the first attempt
+    eps.get_pdu_mut().switch_load(BURN_WIRE_1, true).unwrap();
+    eps.get_pdu_mut().switch_load(BURN_WIRE_1, false).unwrap();
+    run_until(&mut eps, 2400.0, MINUTE_H);
+    let channel = eps.get_deployment().unwrap().get_channel(1).unwrap();
+    assert_eq!((channel.get_phase(), channel.get_attempts()), (DeploymentPhase::Deployed, 1));
+}
+
+#[test]
+fn hold_off_restarts_when_armed() {
+    let mut eps = stowed_eps(&[]);
+    eps.get_deployment_mut().unwrap().arm(600.0);
+    run_until(&mut eps, 2400.0, MINUTE_H);
+    assert!(eps.get_pdu().get_schedule().is_empty());
+    assert_eq!(eps.get_deployment().unwrap().get_channel(1).unwrap().get_attempts(), 0);
+    let record = eps.manage_power(MINUTE_H);
+    assert!(record.demanded_power_w > 0.5);
+}
+
+#[test]
+fn deployment_needs_known_panels_and_burn_wires() {
+    let mut deployment = DeploymentSequencer::default();
+    deployment.add_panel(1, BURN_WIRE_1).unwrap();
+    assert_eq!(deployment.add_panel(1, BURN_WIRE_2), Err(DeploymentError::DuplicatePanel));
+    assert_eq!(deployment.inject_failure(5, DeploymentFailure::Stuck), Err(DeploymentError::UnknownPanel));
+    for panel_index in 2..5 {
+        deployment.add_panel(panel_index, BURN_WIRE_1).unwrap();
+    }
+    assert_eq!(deployment.add_panel(5, BURN_WIRE_1), Err(DeploymentError::TooManyPanels));
+
+    let eps = || EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), PowerDistributionUnit::new());
+    assert_eq!(eps().with_deployment(deployment).err(), Some(DeploymentError::UnknownPanel));
+    let mut panels = Vec::new();
+    panels.push(SolarPanel::new("WING1", 0.06, 0.3, Vector3::Z)).unwrap();
+    let mut deployment = DeploymentSequencer::default();
+    deployment.add_panel(0, BURN_WIRE_1).unwrap();
+    let eps = EPS::new(panels, Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), PowerDistributionUnit::new());
+    assert_eq!(eps.with_deployment(deployment).err(), Some(DeploymentError::UnknownLoad));
+}
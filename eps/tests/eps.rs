@@ -0,0 +1,87 @@
+use eps::{Battery, CubeSatFormFactor, EPS, Load, LoadId, PowerDistributionUnit, SatelliteOperationalMode, SolarPanel, Vector3};
+use heapless::Vec;
+
+fn build_eps() -> EPS {
+    let mut panels = Vec::new();
+    for panel in SolarPanel::body_mounted_faces(CubeSatFormFactor::ThreeU, 0.28) {
+        panels.push(panel).unwrap();
+    }
+    let battery = Battery::new("BAT0", 40.0, 20.0, 8.2, 20.0, 20.0);
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_RX, 0.3, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 2.0, false)).unwrap();
+    for load in [LoadId::OBC, LoadId::COM_RX] {
+        pdu.switch_load(load, true).unwrap();
+    }
+    EPS::new(panels, battery, pdu)
+}
+
+#[test]
+fn sunlit_surplus_charges_battery() {
+    let mut eps = build_eps();
+    let soc_before = eps.get_battery().get_soc_percentage();
+    eps.manage_power(0.1);
+    assert!(eps.get_total_generated_power_w() > 0.0);
+    assert!(eps.get_battery().get_soc_percentage() > soc_before);
+}
+
+#[test]
+fn eclipse_deficit_discharges_battery() {
+    let mut eps = build_eps();
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    let soc_before = eps.get_battery().get_soc_percentage();
+    eps.manage_power(0.1);
+    assert_eq!(eps.get_total_generated_power_w(), 0.0);
+    assert!(eps.get_battery().get_soc_percentage() < soc_before);
+}
+
+#[test]
+fn generation_follows_sun_incidence_angle() {
+    let mut eps = build_eps();
+    eps.set_sun_vector_body(Vector3::X);
+    eps.manage_power(0.01);
+    let face_on_w = eps.get_total_generated_power_w();
+
+    // Sun over the +X/+Y edge lights two long faces at 45 deg each: sqrt(2) more than face-on
+    eps.set_sun_vector_body(Vector3::new(1.0, 1.0, 0.0));
+    eps.manage_power(0.01);
+    let edge_on_w = eps.get_total_generated_power_w();
+    assert!((edge_on_w / face_on_w - 2f64.sqrt()).abs() < 1e-9);
+
+    // Sun along the long axis only lights the small +Z end face
+    eps.set_sun_vector_body(Vector3::Z);
+    eps.manage_power(0.01);
+    assert!(eps.get_total_generated_power_w() < face_on_w / 3.0);
+}
+
+#[test]
+fn panels_facing_away_from_the_sun_produce_nothing() {
+    let mut panel = SolarPanel::new("-X", 0.02, 0.28, -Vector3::X);
+    panel.deploy();
+    panel.update_power_output(1367.0, &Vector3::X);
+    assert_eq!(panel.get_power_output_w(), 0.0);
+    panel.update_power_output(1367.0, &Vector3::new(-1.0, 0.0, 1.0));
+    assert!((panel.get_power_output_w() - 0.02 * 0.28 * 1367.0 / 2f64.sqrt()).abs() < 1e-9);
+}
+
+#[test]
+fn deployable_wings_add_generation_once_deployed() {
+    let mut panels: Vec<SolarPanel, 8> = Vec::new();
+    for panel in SolarPanel::body_mounted_faces(CubeSatFormFactor::ThreeU, 0.28) {
+        panels.push(panel).unwrap();
+    }
+    for wing in SolarPanel::deployable_wings(0.06, 0.28, Vector3::X) {
+        panels.push(wing).unwrap();
+    }
+    for panel in panels.iter_mut() {
+        panel.update_power_output(1367.0, &Vector3::X);
+    }
+    let stowed_w: f64 = panels.iter().map(|p| p.get_power_output_w()).sum();
+    for panel in panels.iter_mut() {
+        panel.deploy();
+        panel.update_power_output(1367.0, &Vector3::X);
+    }
+    let deployed_w: f64 = panels.iter().map(|p| p.get_power_output_w()).sum();
+    assert!((deployed_w - stowed_w - 2.0 * 0.06 * 0.28 * 1367.0).abs() < 1e-9);
+}
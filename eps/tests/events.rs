@@ -0,0 +1,97 @@
+use eps::{
+    Battery, BatteryFault, BatteryThermal, EPS, EpsEvent, LatchingCurrentLimiter, LclRecovery, Load, LoadId, PowerDistributionUnit,
+    SatelliteOperationalMode, SolarPanel, TransitionReason, Vector3,
+};
+use heapless::Vec;
+
+mod common;
+use common::MINUTE_H;
+
+fn build_eps(soc: f64, panel_area_m2: f64) -> EPS {
+    let mut panels = Vec::new();
+    panels.push(SolarPanel::new("+Z", panel_area_m2, 0.3, Vector3::Z)).unwrap();
+    panels[0].deploy();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 2.0, false)).unwrap();
+    pdu.add_load(
+        Load::new(LoadId::PAYLOAD_TX, 4.0, false).with_lcl(LatchingCurrentLimiter::new(1.0, 0.01, LclRecovery::LatchOff)),
+    )
+    .unwrap();
+    for load in [LoadId::OBC, LoadId::PAYLOAD_CAM, LoadId::PAYLOAD_TX] {
+        pdu.switch_load(load, true).unwrap();
+    }
+    let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 40.0 * soc, 7.4, 20.0, 20.0), pdu);
+    eps.set_sun_vector_body(Vector3::Z);
+    eps
+}
+
+#[test]
+fn step_record_balances_power_and_energy() {
+    let mut eps = build_eps(0.5, 0.06);
+    let record = eps.manage_power(0.1);
+    assert_eq!(record.time_s, 0.0);
+    assert_eq!(record.duration_h, 0.1);
+    assert!((record.demanded_power_w - 6.5).abs() < 1e-9);
+    assert!((record.net_power_w - (record.generated_power_w - record.demanded_power_w)).abs() < 1e-9);
+    assert!(record.net_power_w > 0.0);
+    assert!(record.battery_energy_in_wh > 0.0 && record.battery_energy_in_wh <= record.net_power_w * 0.1 + 1e-9);
+    assert_eq!(record.battery_energy_out_wh, 0.0);
+    assert_eq!(record.soc_percentage, eps.get_battery().get_soc_percentage());
+    assert_eq!(record.mode, SatelliteOperationalMode::NominalSunlit);
+    assert!(record.mode_transitions.is_empty() && record.faults_raised.is_empty());
+
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    let record = eps.manage_power(0.1);
+    assert_eq!(record.time_s, 360.0);
+    assert!(record.battery_energy_out_wh > 0.0);
+    assert_eq!(record.battery_energy_in_wh, 0.0);
+    // The command issued between the steps is reported with the next step
+    assert_eq!(record.mode_transitions[0].reason, TransitionReason::GroundCommand);
+    assert!(eps.manage_power(0.1).mode_transitions.is_empty());
+}
+
+#[test]
+fn step_record_lists_shed_loads_trips_and_faults() {
+    let mut eps = build_eps(0.295, 0.0);
+    eps.get_pdu_mut().inject_latch_up(LoadId::PAYLOAD_TX, 1.0).unwrap();
+    let record = eps.manage_power(MINUTE_H);
+    assert_eq!(record.tripped_loads.as_slice(), [LoadId::PAYLOAD_TX]);
+    let shed: std::vec::Vec<LoadId> = record.shed_loads().collect();
+    assert_eq!(shed, [LoadId::PAYLOAD_CAM, LoadId::PAYLOAD_TX]);
+    assert!((record.demanded_power_w - 0.5).abs() < 1e-9);
+
+    let mut battery = Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0);
+    battery.raise_fault(BatteryFault::OverVoltage);
+    let mut eps = EPS::new(Vec::new(), battery, PowerDistributionUnit::new());
+    let record = eps.manage_power(MINUTE_H);
+    assert!(record.faults_raised.is_empty()); // Already latched before the step
+    assert_eq!(record.mode_transitions[0].reason, TransitionReason::BatteryFault);
+
+    let battery = Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0).with_thermal(BatteryThermal::new(70.0));
+    let mut eps = EPS::new(Vec::new(), battery, build_eps(0.5, 0.0).get_pdu().clone());
+    assert_eq!(eps.manage_power(MINUTE_H).faults_raised.as_slice(), [BatteryFault::OverTemperature]);
+}
+
+#[test]
+fn sink_receives_the_same_events_in_order() {
+    let mut eps = build_eps(0.14, 0.0);
+    let mut events: std::vec::Vec<std::string::String> = std::vec::Vec::new();
+    let mut sink = |event: &EpsEvent| {
+        let name = match event {
+            EpsEvent::FaultInjected(fault) => format!("inject {fault:?}"),
+            EpsEvent::ModeChanged(transition) => format!("mode {:?}", transition.to),
+            EpsEvent::Shedding(decision) => format!("shed {}", decision.priority),
+            EpsEvent::ChannelTripped(load_id) => format!("trip {}", load_id.0),
+            EpsEvent::FaultRaised(fault) => format!("fault {fault:?}"),
+            EpsEvent::BurnCompleted(attempt) => format!("burn {}", attempt.panel_index),
+            EpsEvent::ThresholdCrossed(crossing) => format!("crossing {:?}", crossing.kind),
+            EpsEvent::StepCompleted(record) => format!("step {}", record.time_s),
+        };
+        events.push(name);
+    };
+    let record = eps.manage_power_with(MINUTE_H, &mut sink);
+    eps.manage_power_with(MINUTE_H, &mut sink);
+    assert_eq!(record.mode, SatelliteOperationalMode::SafeMode);
+    assert_eq!(events, ["mode SafeMode", "shed 0", "shed 1", "step 0", "step 60"]);
+}
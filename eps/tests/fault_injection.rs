@@ -0,0 +1,190 @@
+use eps::{
+    Battery, BatteryFault, CellPack, EPS, EpsEvent, FaultInjectionError, FaultInjector, FaultResponse, FaultTrigger, InjectedFault,
+    Load, LoadId, PowerDistributionUnit, PvString, SatelliteOperationalMode, Sensor, SolarCell, SolarPanel, TransitionReason,
+    Vector3,
+};
+use heapless::Vec;
+
+mod common;
+use common::{MINUTE_H, draining_eps, run_until};
+
+// Sunlit +Z panel with two parallel strings, a critical OBC and a payload camera on shedding tier 0
+fn sunlit_eps(battery: Battery) -> EPS {
+    let string = PvString::new(SolarCell::triple_junction_gaas(), 4, 2);
+    let mut panel = SolarPanel::new("+Z", 8.0 * 0.003018, 0.3, Vector3::Z).with_string(string);
+    panel.deploy();
+    let mut panels = Vec::new();
+    panels.push(panel).unwrap();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 2.0, false)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    let mut eps = EPS::new(panels, battery, pdu);
+    eps.set_sun_vector_body(Vector3::Z);
+    eps
+}
+
+fn battery(soc: f64) -> Battery {
+    Battery::new("BAT0", 40.0, 40.0 * soc, 7.4, 20.0, 20.0)
+}
+
+#[test]
+fn open_string_halves_the_panel_and_is_reported_at_its_time() {
+    let mut injector = FaultInjector::new();
+    injector.schedule(FaultTrigger::At(600.0), InjectedFault::StringOpen { panel_index: 0 }).unwrap();
+    let mut eps = sunlit_eps(battery(0.5)).with_fault_injector(injector).unwrap();
+    run_until(&mut eps, 540.0, MINUTE_H);
+    let healthy_w = eps.manage_power(MINUTE_H).generated_power_w;
+    let mut injected = std::vec::Vec::new();
+    let record = eps.manage_power_with(MINUTE_H, &mut |event: &EpsEvent| {
+        if let EpsEvent::FaultInjected(fault) = event {
+            injected.push(*fault);
+        }
+    });
+    assert_eq!(record.faults_injected.as_slice(), [InjectedFault::StringOpen { panel_index: 0 }]);
+    assert_eq!(injected, [InjectedFault::StringOpen { panel_index: 0 }]);
+    // The MPPT converges again within the step
+    assert!((record.generated_power_w / healthy_w - 0.5).abs() < 0.02, "{} vs {healthy_w}", record.generated_power_w);
+    assert_eq!(eps.get_solar_panels()[0].get_open_strings(), 1);
+    assert!(eps.get_fault_injector().get_pending().is_empty());
+    assert_eq!(eps.get_fault_injector().get_log()[0].time_s, 600.0);
+
+    // A panel without an I-V model is a single string
+    let mut panel = SolarPanel::new("+Z", 0.03, 0.3, Vector3::Z);
+    panel.deploy();
+    panel.open_string();
+    panel.update_power_output(1361.0, &Vector3::Z);
+    assert_eq!(panel.get_power_output_w(), 0.0);
+}
+
+#[test]
+fn opening_every_string_leaves_the_panel_dead() {
+    let mut eps = sunlit_eps(battery(0.5));
+    for _ in 0..3 {
+        eps.schedule_fault(FaultTrigger::At(300.0), InjectedFault::StringOpen { panel_index: 0 }).unwrap();
+    }
+    let records = run_until(&mut eps, 600.0, MINUTE_H);
+    assert!(records[0].generated_power_w > 0.0);
+    assert_eq!(records.last().unwrap().generated_power_w, 0.0);
+    let panel = &eps.get_solar_panels()[0];
+    assert_eq!(panel.get_open_strings(), 2);
+    let string = panel.get_string().unwrap();
+    assert_eq!(string.get_parallel_strings(), 0);
+    assert_eq!(string.get_short_circuit_current_a(1361.0, 28.0), 0.0);
+    assert_eq!(string.get_maximum_power_point(1361.0, 28.0).1, 0.0);
+}
+
+#[test]
+fn cell_short_latches_a_fault_and_forces_safe_mode() {
+    let pack = CellPack::li_ion(2, 2, 0.6).unwrap();
+    let mut eps = sunlit_eps(Battery::from_pack("BAT0", pack, 20.0, 20.0));
+    eps.schedule_fault(FaultTrigger::At(300.0), InjectedFault::CellShort { group: 1, position: 0 }).unwrap();
+    run_until(&mut eps, 900.0, MINUTE_H);
+
+    let record = eps.get_fault_injector().get_record(InjectedFault::CellShort { group: 1, position: 0 }).unwrap();
+    assert_eq!((record.time_s, record.mode), (300.0, SatelliteOperationalMode::NominalSunlit));
+    assert!(record.responses.contains(&FaultResponse::BatteryFaultRaised { time_s: 300.0, fault: BatteryFault::CellShort }));
+    // The pack latches the fault at once, so the mode guards act within the same step
+    assert_eq!(record.get_time_to_mode_s(SatelliteOperationalMode::SafeMode), Some(0.0));
+    let safe = eps.get_mode_transitions().last().unwrap();
+    assert_eq!(safe.reason, TransitionReason::BatteryFault);
+}
+
+#[test]
+fn stuck_load_keeps_drawing_after_it_is_shed() {
+    let mut eps = sunlit_eps(battery(0.31));
+    eps.schedule_fault(FaultTrigger::InMode(SatelliteOperationalMode::NominalEclipse), InjectedFault::LoadStuckOn(LoadId::PAYLOAD_CAM))
+        .unwrap();
+    run_until(&mut eps, 300.0, MINUTE_H);
+    assert_eq!(eps.get_fault_injector().get_pending().len(), 1);
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse); // Switches the payload off; the fault is still to come
+    eps.get_pdu_mut().switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    run_until(&mut eps, 3600.0, MINUTE_H);
+
+    let record = &eps.get_fault_injector().get_log()[0];
+    assert_eq!(record.time_s, 300.0);
+    assert!(record.was_load_shed(LoadId::PAYLOAD_CAM));
+    let camera = eps.get_pdu().get_load(LoadId::PAYLOAD_CAM).unwrap();
+    assert!(camera.is_stuck_on() && camera.is_powered());
+    eps.get_pdu_mut().switch_load(LoadId::PAYLOAD_CAM, false).unwrap();
+    assert!(eps.get_pdu().get_load(LoadId::PAYLOAD_CAM).unwrap().is_powered());
+}
+
+// When and why the draining spacecraft enters SafeMode
+fn safe_mode_entry(fault: Option<InjectedFault>) -> (f64, TransitionReason) {
+    let mut eps = draining_eps();
+    if let Some(fault) = fault {
+        eps.inject_fault(fault).unwrap();
+    }
+    run_until(&mut eps, 2.0 * 3600.0, MINUTE_H);
+    let entry = eps.get_mode_transitions().iter().find(|transition| transition.to == SatelliteOperationalMode::SafeMode).unwrap();
+    (entry.time_s, entry.reason)
+}
+
+#[test]
+fn soc_sensor_faults_move_the_safe_mode_entry() {
+    let (reference_s, reason) = safe_mode_entry(None);
+    assert_eq!(reason, TransitionReason::LowSoc);
+    // Reading 10 points low: entered at once
+    assert_eq!(safe_mode_entry(Some(InjectedFault::SensorOffset(Sensor::BatterySoc, -10.0))), (0.0, TransitionReason::LowSoc));
+    // Frozen at 20 % or reading high, the SoC guard never fires and the empty-battery backstop acts instead
+    for fault in [InjectedFault::SensorFrozen(Sensor::BatterySoc), InjectedFault::SensorOffset(Sensor::BatterySoc, 4.0)] {
+        let (entry_s, reason) = safe_mode_entry(Some(fault));
+        assert!(entry_s > reference_s + 600.0, "{fault:?}");
+        assert_eq!(reason, TransitionReason::BatteryEmpty);
+    }
+}
+
+#[test]
+fn frozen_sensor_holds_its_reading_until_recalibrated() {
+    let mut eps = sunlit_eps(battery(0.5));
+    eps.inject_fault(InjectedFault::SensorFrozen(Sensor::BusVoltage)).unwrap();
+    let voltage_v = eps.get_battery().get_terminal_voltage_v();
+    eps.inject_fault(InjectedFault::SensorOffset(Sensor::BatterySoc, 5.0)).unwrap();
+    let injector = eps.get_fault_injector();
+    assert_eq!(injector.read(Sensor::BusVoltage, 3.0), voltage_v);
+    assert_eq!(injector.read(Sensor::BatterySoc, 50.0), 55.0);
+    assert!(injector.is_sensor_faulty(Sensor::BusVoltage));
+    eps.clear_sensor_fault(Sensor::BusVoltage);
+    assert_eq!(eps.get_fault_injector().read(Sensor::BusVoltage, 3.0), 3.0);
+    assert_eq!(eps.get_fault_injector().get_log().len(), 2);
+}
+
+#[test]
+fn regulator_failure_drains_the_battery_in_sunlight() {
+    let mut eps = sunlit_eps(battery(0.32));
+    eps.schedule_fault(FaultTrigger::SocBelow(0.0), InjectedFault::LoadStuckOn(LoadId::OBC)).unwrap(); // Never due
+    eps.schedule_fault(FaultTrigger::At(0.0), InjectedFault::RegulatorFailure).unwrap();
+    let record = eps.manage_power(MINUTE_H);
+    assert!(eps.get_regulator().is_failed());
+    assert_eq!(record.generated_power_w, 0.0);
+    assert!(record.battery_energy_out_wh > 0.0);
+    run_until(&mut eps, 3.0 * 3600.0, MINUTE_H);
+    // Nothing charges the battery, so the payload tier goes once the SoC reaches 30 %
+    let record = &eps.get_fault_injector().get_log()[0];
+    let shed = record.responses.iter().find(|response| matches!(response, FaultResponse::LoadShed { .. })).unwrap();
+    assert!(shed.get_time_s() > 0.0);
+    assert!(record.was_load_shed(LoadId::PAYLOAD_CAM));
+    assert_eq!(eps.get_fault_injector().get_pending().len(), 1);
+}
+
+#[test]
+fn faults_need_known_targets() {
+    let mut eps = sunlit_eps(battery(0.5));
+    let error = |eps: &mut EPS, fault| eps.schedule_fault(FaultTrigger::At(0.0), fault).err();
+    assert_eq!(error(&mut eps, InjectedFault::StringOpen { panel_index: 1 }), Some(FaultInjectionError::UnknownPanel));
+    assert_eq!(error(&mut eps, InjectedFault::LoadStuckOn(LoadId::ADCS)), Some(FaultInjectionError::UnknownLoad));
+    // No cell pack
+    assert_eq!(error(&mut eps, InjectedFault::CellShort { group: 0, position: 0 }), Some(FaultInjectionError::UnknownCell));
+    assert_eq!(eps.inject_fault(InjectedFault::LoadStuckOn(LoadId::ADCS)), Err(FaultInjectionError::UnknownLoad));
+
+    let mut injector = FaultInjector::new();
+    for _ in 0..16 {
+        injector.schedule(FaultTrigger::At(0.0), InjectedFault::RegulatorFailure).unwrap();
+    }
+    assert_eq!(injector.schedule(FaultTrigger::At(0.0), InjectedFault::RegulatorFailure), Err(FaultInjectionError::TooManyFaults));
+    let mut injector = FaultInjector::new();
+    injector.schedule(FaultTrigger::At(60.0), InjectedFault::StringOpen { panel_index: 3 }).unwrap();
+    assert_eq!(sunlit_eps(battery(0.5)).with_fault_injector(injector).err(), Some(FaultInjectionError::UnknownPanel));
+}
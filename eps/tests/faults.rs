@@ -0,0 +1,97 @@
+use eps::{Battery, BatteryAgeing, BatteryFault, BatteryState, BatteryThermal, EPS, Load, LoadId, PowerDistributionUnit};
+use heapless::Vec;
+
+fn battery_at(soc: f64) -> Battery {
+    // 2S Li-ion, 40 Wh
+    Battery::new("BAT0", 40.0, 40.0 * soc, 7.4, 40.0, 40.0)
+}
+
+#[test]
+fn faults_latch_with_a_timestamp_until_cleared() {
+    let mut battery = battery_at(0.5);
+    battery.set_time_s(120.0);
+    assert!(battery.raise_fault(BatteryFault::OverCurrent));
+    battery.set_time_s(180.0);
+    assert!(!battery.raise_fault(BatteryFault::OverCurrent));
+
+    let latched = battery.get_faults().get_active()[0];
+    assert_eq!(latched.fault, BatteryFault::OverCurrent);
+    assert_eq!(latched.raised_at_s, 120.0);
+    assert_eq!(latched.occurrences, 2);
+
+    // Still latched after operating with the condition gone
+    battery.rest(0.1);
+    assert!(battery.get_faults().is_active(BatteryFault::OverCurrent));
+    assert!(battery.clear_fault(BatteryFault::OverCurrent));
+    assert!(!battery.clear_fault(BatteryFault::OverCurrent));
+    assert!(battery.get_faults().get_active().is_empty());
+}
+
+#[test]
+fn active_faults_block_charge_and_discharge() {
+    let mut battery = battery_at(0.5);
+    battery.raise_fault(BatteryFault::OverVoltage);
+    assert_eq!(battery.charge_current(2.0, 0.1), 0.0);
+    assert_eq!(battery.get_status(), &BatteryState::Fault(BatteryFault::OverVoltage));
+    assert!(battery.discharge_current(2.0, 0.1) > 0.0);
+
+    battery.clear_all_faults();
+    battery.raise_fault(BatteryFault::CellShort);
+    assert_eq!(battery.charge_current(2.0, 0.1), 0.0);
+    assert_eq!(battery.discharge_current(2.0, 0.1), 0.0);
+
+    battery.clear_fault(BatteryFault::CellShort);
+    assert_eq!(battery.get_status(), &BatteryState::Idle);
+    assert_eq!(battery.charge_current(2.0, 0.1), 2.0);
+}
+
+#[test]
+fn limits_are_detected_during_operation() {
+    let mut hot = battery_at(0.5).with_thermal(BatteryThermal::new(70.0));
+    hot.discharge_current(1.0, 0.01);
+    assert!(hot.get_faults().is_active(BatteryFault::OverTemperature));
+    assert_eq!(hot.charge_current(1.0, 0.01), 0.0);
+
+    let mut frozen = battery_at(0.5).with_thermal(BatteryThermal::new(-30.0));
+    frozen.discharge_current(1.0, 0.01);
+    assert!(frozen.get_faults().is_active(BatteryFault::UnderTemperature));
+    assert_eq!(frozen.discharge_current(1.0, 0.01), 0.0);
+
+    let mut overloaded = battery_at(0.5);
+    overloaded.discharge_current(20.0, 0.001);
+    assert!(overloaded.get_faults().is_active(BatteryFault::OverCurrent));
+    assert_eq!(overloaded.discharge_current(1.0, 0.01), 0.0);
+}
+
+#[test]
+fn severe_degradation_is_latched_but_does_not_block_discharge() {
+    let mut battery = battery_at(0.9).with_ageing(BatteryAgeing::new().with_cycle_life(2.0, 1.0, 0.9));
+    while !battery.get_faults().is_active(BatteryFault::SevereDegradation) {
+        for _ in 0..10 {
+            battery.discharge(5.0, 0.1);
+        }
+        for _ in 0..10 {
+            battery.charge(5.0, 0.1);
+        }
+        assert!(battery.get_cycles() < 50.0);
+    }
+    assert!(battery.get_faults().is_active(BatteryFault::SevereDegradation));
+    assert!(battery.discharge_current(1.0, 0.01) > 0.0);
+}
+
+#[test]
+fn eps_timestamps_faults_and_clears_them_on_command() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 20.0, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    let mut eps = EPS::new(Vec::new(), battery_at(0.05), pdu);
+    let record = (0..100).map(|_| eps.manage_power(0.01)).find(|record| !record.faults_raised.is_empty()).unwrap();
+    assert_eq!(record.faults_raised.as_slice(), [BatteryFault::UnderVoltage]);
+    let latched = eps.get_battery().get_faults().get_active()[0];
+    assert!(record.time_s > 0.0);
+    assert_eq!(latched.raised_at_s, record.time_s);
+
+    assert!(eps.clear_battery_fault(BatteryFault::UnderVoltage));
+    assert!(eps.get_battery().get_faults().get_active().is_empty());
+    assert!(!eps.clear_battery_fault(BatteryFault::UnderVoltage));
+}
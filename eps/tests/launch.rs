@@ -0,0 +1,153 @@
+use eps::{
+    Battery, DeploymentSequencer, EPS, LaunchError, LaunchPhase, LaunchSequencer, Load, LoadId, PowerDistributionUnit,
+    SatelliteOperationalMode, SolarPanel, Vector3,
+};
+use heapless::Vec;
+
+mod common;
+use common::run_until;
+
+const BURN_WIRE: LoadId = LoadId(8);
+const STEP_H: f64 = 10.0 / 3600.0;
+
+// Sunlit +Z panel and a stowed wing, the standard radios and a payload, all switched on as on the bench
+fn bench_eps(launch: LaunchSequencer) -> EPS {
+    let mut panels = Vec::new();
+    let mut body = SolarPanel::new("+Z", 0.03, 0.3, Vector3::Z);
+    body.deploy();
+    panels.push(body).unwrap();
+    panels.push(SolarPanel::new("WING", 0.06, 0.3, Vector3::Z)).unwrap();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_RX, 0.3, false)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_TX, 4.0, false)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_TX, 6.0, false)).unwrap();
+    pdu.add_load(Load::new(BURN_WIRE, 3.0, false)).unwrap();
+    for load_id in [LoadId::OBC, LoadId::COM_RX, LoadId::COM_TX, LoadId::PAYLOAD_TX] {
+        pdu.switch_load(load_id, true).unwrap();
+    }
+    let mut deployment = DeploymentSequencer::default();
+    deployment.add_panel(1, BURN_WIRE).unwrap();
+    let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 30.0, 7.4, 20.0, 20.0), pdu)
+        .with_deployment(deployment)
+        .unwrap()
+        .with_launch(launch)
+        .unwrap();
+    eps.set_sun_vector_body(Vector3::Z);
+    eps
+}
+
+// First time a load is seen powered at the end of a step
+fn first_on_s(eps: &mut EPS, load_id: LoadId, until_s: f64) -> Option<f64> {
+    while eps.get_elapsed_time_s() < until_s - 1e-9 {
+        eps.manage_power(STEP_H);
+        if eps.get_pdu().get_load(load_id).unwrap().is_powered() {
+            return Some(eps.get_elapsed_time_s() - eps.get_pdu().get_load(load_id).unwrap().get_on_time_s());
+        }
+    }
+    None
+}
+
+#[test]
+fn stowed_spacecraft_stays_dark_with_the_battery_isolated() {
+    let mut eps = bench_eps(LaunchSequencer::default());
+    let soc = eps.get_battery().get_soc_percentage();
+    eps.get_pdu_mut().schedule_window(LoadId::COM_TX, 600.0, 900.0).unwrap();
+    let records = run_until(&mut eps, 3.0 * 3600.0, STEP_H);
+
+    assert!(records.iter().all(|record| record.generated_power_w == 0.0 && record.demanded_power_w == 0.0));
+    assert!(records.iter().all(|record| record.battery_energy_in_wh == 0.0 && record.battery_energy_out_wh == 0.0));
+    assert!((eps.get_battery().get_soc_percentage() - soc).abs() < 0.1);
+    assert!(eps.get_pdu().get_loads().iter().all(|load| !load.is_powered()));
+    assert!(eps.get_pdu().get_schedule().is_empty());
+    assert!(!eps.get_solar_panels()[1].is_deployed());
+    let launch = eps.get_launch().unwrap();
+    assert_eq!((launch.get_phase(), launch.get_separation_time_s()), (LaunchPhase::Stowed, None));
+    assert!(eps.get_mode_transitions().is_empty());
+}
+
+#[test]
+fn boot_stages_follow_separation_and_the_rf_silence() {
+    let launch = LaunchSequencer::default().with_separation_at(600.0);
+    let on_s = |load_id: LoadId| first_on_s(&mut bench_eps(launch.clone()), load_id, 3.0 * 3600.0).unwrap();
+    assert_eq!(on_s(LoadId::OBC), 600.0);
+    assert_eq!(on_s(LoadId::COM_RX), 660.0);
+    assert_eq!(on_s(LoadId::COM_TX), 2400.0); // Beacon
+
+    let mut eps = bench_eps(launch);
+    let booting = run_until(&mut eps, 2400.0, STEP_H);
+    assert!(booting.iter().filter(|record| record.time_s < 600.0).all(|record| record.demanded_power_w == 0.0));
+    assert!(booting.iter().any(|record| record.generated_power_w > 0.0));
+    let launch = eps.get_launch().unwrap();
+    assert_eq!(launch.get_phase(), LaunchPhase::Booting);
+    assert_eq!(launch.get_rf_silence_end_s(), Some(2400.0));
+    let powered: std::vec::Vec<bool> = launch.get_stages().iter().map(|stage| stage.get_powered_on_s().is_some()).collect();
+    assert_eq!(powered, [true, true, false]);
+    eps.manage_power(STEP_H);
+    assert!(eps.get_launch().unwrap().is_operational());
+    // PAYLOAD_TX is not a boot stage and was switched off with the rest of the PDU
+    assert!(!eps.get_pdu().get_load(LoadId::PAYLOAD_TX).unwrap().is_powered());
+}
+
+#[test]
+fn transmitters_ignore_commands_during_the_rf_silence() {
+    let mut eps = bench_eps(LaunchSequencer::default().with_separation_at(0.0));
+    run_until(&mut eps, 120.0, STEP_H);
+    eps.set_satellite_mode(SatelliteOperationalMode::PayloadOperation); // Switches PAYLOAD_TX on
+    eps.get_pdu_mut().schedule_window(LoadId::COM_TX, 600.0, 900.0).unwrap();
+    while eps.get_elapsed_time_s() < 1800.0 - 1e-9 {
+        let record = eps.manage_power(STEP_H);
+        assert!(record.peak_demand_w < 4.0, "{} W at {} s", record.peak_demand_w, record.time_s);
+        assert!(!eps.get_pdu().get_load(LoadId::PAYLOAD_TX).unwrap().is_powered());
+    }
+    // After the silence a ground command goes through
+    run_until(&mut eps, 1900.0, STEP_H);
+    eps.get_pdu_mut().switch_load(LoadId::PAYLOAD_TX, true).unwrap();
+    assert!(eps.manage_power(STEP_H).demanded_power_w > 6.0);
+}
+
+#[test]
+fn every_inhibit_has_to_release() {
+    let mut launch = LaunchSequencer::default();
+    launch.insert_rbf_pin();
+    let mut eps = bench_eps(launch);
+    run_until(&mut eps, 60.0, STEP_H);
+    assert_eq!(eps.get_launch().unwrap().get_phase(), LaunchPhase::Integration);
+    eps.get_launch_mut().unwrap().remove_rbf_pin();
+    for index in 0..3 {
+        run_until(&mut eps, 120.0 * (index + 1) as f64, STEP_H);
+        assert!(eps.get_launch().unwrap().is_inhibited(), "switch {index}");
+        assert_eq!(eps.get_launch().unwrap().get_phase(), LaunchPhase::Stowed);
+        eps.get_launch_mut().unwrap().set_separation_switch(index, false).unwrap();
+    }
+    // Separation is seen at the next step
+    let record = eps.manage_power(STEP_H);
+    assert_eq!(eps.get_launch().unwrap().get_separation_time_s(), Some(360.0));
+    assert!(record.demanded_power_w > 0.0);
+    // After separation the switches no longer matter
+    eps.get_launch_mut().unwrap().set_separation_switch(0, true).unwrap();
+    assert!(!eps.get_launch().unwrap().is_inhibited());
+}
+
+#[test]
+fn deployment_hold_off_starts_at_separation() {
+    let mut eps = bench_eps(LaunchSequencer::default().with_separation_at(3600.0));
+    let records = run_until(&mut eps, 3.0 * 3600.0, STEP_H);
+    let attempt = records.iter().flat_map(|record| record.burn_attempts.iter()).next().unwrap();
+    assert_eq!(attempt.start_s, 3600.0 + 1800.0);
+    assert!(eps.get_solar_panels()[1].is_deployed());
+}
+
+#[test]
+fn launch_needs_known_loads() {
+    let mut launch = LaunchSequencer::new(2, 1800.0);
+    assert_eq!(launch.get_separation_switches(), [true, true]);
+    assert_eq!(launch.set_separation_switch(2, false), Err(LaunchError::UnknownSwitch));
+    launch.add_stage(LoadId::ADCS, 0.0).unwrap();
+    for _ in 1..8 {
+        launch.add_stage(LoadId::OBC, 0.0).unwrap();
+    }
+    assert_eq!(launch.add_stage(LoadId::OBC, 0.0), Err(LaunchError::TooManyStages));
+    let eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), PowerDistributionUnit::new());
+    assert_eq!(eps.with_launch(launch).err(), Some(LaunchError::UnknownLoad));
+}
@@ -0,0 +1,117 @@
+use eps::{Battery, EPS, LatchingCurrentLimiter, LclRecovery, Load, LoadId, PowerDistributionUnit, SatelliteOperationalMode};
+use heapless::Vec;
+
+fn payload_pdu(recovery: LclRecovery) -> PowerDistributionUnit {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 2.0, false).with_lcl(LatchingCurrentLimiter::new(0.5, 0.01, recovery))).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    pdu
+}
+
+const AUTO_RETRY: LclRecovery = LclRecovery::AutoRetry { retry_delay_s: 1.0, max_retries: 3 };
+
+#[test]
+fn latch_up_trips_the_channel_and_auto_retry_restores_it() {
+    let mut pdu = payload_pdu(AUTO_RETRY);
+    pdu.inject_latch_up(LoadId::PAYLOAD_CAM, 1.0).unwrap();
+    assert!((pdu.get_total_demand_w(1.0).average_w - 0.5 - 0.5 * 7.4).abs() < 1e-9); // Held at the limit until it trips
+
+    assert_eq!(pdu.update_current_limiters(7.4, 0.02).as_slice(), [LoadId::PAYLOAD_CAM]);
+    let cam = pdu.get_load(LoadId::PAYLOAD_CAM).unwrap();
+    assert!(cam.get_lcl().is_tripped());
+    assert!(cam.is_on() && !cam.is_powered());
+    assert_eq!(cam.get_lcl().get_latch_up_current_a(), 0.0);
+    assert_eq!(pdu.get_total_demand_w(1.0).average_w, 0.5);
+    assert_eq!(pdu.get_tripped_loads().count(), 1);
+
+    pdu.update_current_limiters(7.4, 0.5);
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().get_lcl().is_tripped());
+    pdu.update_current_limiters(7.4, 0.5);
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_powered());
+    assert_eq!(pdu.get_total_trip_count(), 1);
+    assert_eq!(pdu.get_tripped_loads().count(), 0);
+}
+
+#[test]
+fn short_over_current_does_not_trip() {
+    let mut pdu = payload_pdu(AUTO_RETRY);
+    pdu.inject_latch_up(LoadId::PAYLOAD_CAM, 1.0).unwrap();
+    assert!(pdu.update_current_limiters(7.4, 0.005).is_empty());
+    // Current is held at the limit meanwhile
+    assert!((pdu.get_total_demand_w(1.0).average_w - 0.5 - 0.5 * 7.4).abs() < 1e-9);
+    assert_eq!(pdu.update_current_limiters(7.4, 0.005).len(), 1);
+}
+
+#[test]
+fn latch_off_channel_stays_open_until_commanded() {
+    let mut pdu = payload_pdu(LclRecovery::LatchOff);
+    pdu.inject_latch_up(LoadId::PAYLOAD_CAM, 1.0).unwrap();
+    pdu.update_current_limiters(7.4, 0.02);
+    for _ in 0..10 {
+        pdu.update_current_limiters(7.4, 60.0);
+    }
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().get_lcl().is_tripped());
+
+    pdu.switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_powered());
+    assert_eq!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().get_lcl().get_trip_count(), 1);
+}
+
+#[test]
+fn persistent_over_current_exhausts_the_retries() {
+    let mut pdu = PowerDistributionUnit::new();
+    // 10 W at 7.4 V is well above a 0.5 A limit
+    pdu.add_load(Load::new(LoadId::PAYLOAD_TX, 10.0, false).with_lcl(LatchingCurrentLimiter::new(0.5, 0.01, AUTO_RETRY))).unwrap();
+    pdu.switch_load(LoadId::PAYLOAD_TX, true).unwrap();
+    for _ in 0..20 {
+        pdu.update_current_limiters(7.4, 1.0);
+    }
+    let tx = pdu.get_load(LoadId::PAYLOAD_TX).unwrap();
+    assert!(tx.get_lcl().is_tripped());
+    assert_eq!(tx.get_lcl().get_trip_count(), 4);
+}
+
+#[test]
+fn eps_reports_tripped_channels() {
+    let battery = Battery::new("BAT0", 40.0, 30.0, 7.4, 20.0, 20.0);
+    let mut eps = EPS::new(Vec::new(), battery, payload_pdu(AUTO_RETRY));
+    eps.get_pdu_mut().inject_latch_up(LoadId::PAYLOAD_CAM, 2.0).unwrap();
+    eps.manage_power(1.0 / 60.0);
+    assert_eq!(eps.get_pdu().get_tripped_loads().next().unwrap().get_id(), LoadId::PAYLOAD_CAM);
+    eps.manage_power(1.0 / 60.0);
+    assert_eq!(eps.get_pdu().get_tripped_loads().count(), 0);
+    assert_eq!(eps.get_pdu().get_total_trip_count(), 1);
+}
+
+#[test]
+fn scheduled_windows_do_not_reclose_a_latched_off_channel() {
+    let mut pdu = payload_pdu(LclRecovery::LatchOff);
+    pdu.inject_latch_up(LoadId::PAYLOAD_CAM, 2.0).unwrap();
+    assert_eq!(pdu.update_current_limiters(7.4, 1.0).as_slice(), [LoadId::PAYLOAD_CAM]);
+    pdu.switch_load(LoadId::PAYLOAD_CAM, false).unwrap();
+    pdu.schedule_window(LoadId::PAYLOAD_CAM, 30.0, 510.0).unwrap();
+    assert_eq!(pdu.get_load_demand_w(LoadId::PAYLOAD_CAM, 60.0).unwrap().average_w, 0.0);
+    pdu.advance_to(60.0);
+    let cam = pdu.get_load(LoadId::PAYLOAD_CAM).unwrap();
+    assert!(cam.get_telemetry().is_tripped && !cam.is_powered());
+}
+
+#[test]
+fn mode_changes_do_not_reclose_a_latched_off_channel() {
+    let battery = Battery::new("BAT0", 40.0, 30.0, 7.4, 20.0, 20.0);
+    let mut eps = EPS::new(Vec::new(), battery, payload_pdu(LclRecovery::LatchOff));
+    eps.get_pdu_mut().inject_latch_up(LoadId::PAYLOAD_CAM, 2.0).unwrap();
+    eps.manage_power(1.0 / 60.0);
+    // Payload operations switch the camera back on, but its channel stays open
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    eps.set_satellite_mode(SatelliteOperationalMode::PayloadOperation);
+    eps.manage_power(1.0 / 60.0);
+    let cam = eps.get_pdu().get_load(LoadId::PAYLOAD_CAM).unwrap();
+    assert!(cam.is_on() && !cam.is_powered());
+    assert!(eps.manage_power(1.0 / 60.0).demanded_power_w < 1.0);
+
+    eps.get_pdu_mut().switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    assert!(eps.get_pdu().get_load(LoadId::PAYLOAD_CAM).unwrap().is_powered());
+}
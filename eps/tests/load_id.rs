@@ -0,0 +1,47 @@
+use eps::{Load, LoadId, LoadRegistry, LoadTelemetry, LatchingCurrentLimiter, LclRecovery, PduError, PowerDistributionUnit, RegistryError};
+
+#[test]
+fn standard_registry_names_every_standard_load() {
+    let registry = LoadRegistry::standard();
+    assert_eq!(registry.get_entries().len(), 7);
+    assert_eq!(registry.get_name(LoadId::PAYLOAD_CAM), Some("PayloadCam"));
+    assert_eq!(registry.get_id("Heaters"), Some(LoadId::HEATERS));
+    assert_eq!(registry.get_name(LoadId(42)), None);
+}
+
+#[test]
+fn registry_rejects_duplicates() {
+    let mut registry = LoadRegistry::standard();
+    assert_eq!(registry.register(LoadId::OBC, "Imager"), Err(RegistryError::DuplicateId));
+    assert_eq!(registry.register(LoadId(16), "OBC"), Err(RegistryError::DuplicateName));
+    registry.register(LoadId(16), "Imager").unwrap();
+    assert_eq!(registry.get_id("Imager"), Some(LoadId(16)));
+}
+
+#[test]
+fn pdu_addresses_loads_by_id() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    assert_eq!(pdu.add_load(Load::new(LoadId::OBC, 1.0, false)), Err(PduError::DuplicateLoad));
+    assert_eq!(pdu.switch_load(LoadId::COM_TX, true), Err(PduError::LoadNotFound));
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    assert_eq!(pdu.get_load(LoadId::OBC).unwrap().get_id(), LoadId::OBC);
+}
+
+#[test]
+fn load_telemetry_round_trips() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    let tx = Load::new(LoadId::PAYLOAD_TX, 4.0, false).with_lcl(LatchingCurrentLimiter::new(0.5, 0.01, LclRecovery::LatchOff));
+    pdu.add_load(tx).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.switch_load(LoadId::PAYLOAD_TX, true).unwrap();
+    pdu.update_current_limiters(7.4, 0.02);
+
+    let telemetry = pdu.get_telemetry();
+    assert_eq!(telemetry[1].encode(), [4, 0b0101, 1]); // On, tripped, not critical
+    for channel in telemetry.iter() {
+        assert_eq!(LoadTelemetry::decode(channel.encode()), *channel);
+    }
+    assert!(telemetry[0].is_powered && telemetry[0].is_critical);
+}
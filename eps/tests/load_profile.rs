@@ -0,0 +1,133 @@
+use eps::{Battery, EPS, Load, LoadId, LoadProfile, PduError, PowerDistributionUnit, ScheduledSwitch};
+use heapless::Vec;
+
+fn pdu_with(load: Load) -> PowerDistributionUnit {
+    let id = load.get_id();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(load).unwrap();
+    pdu.switch_load(id, true).unwrap();
+    pdu
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn duty_cycle_averages_over_the_step() {
+    let profile = LoadProfile::DutyCycle { period_s: 100.0, on_s: 25.0, offset_s: 0.0, standby_w: 0.2 };
+    let mut pdu = pdu_with(Load::new(LoadId::PAYLOAD_CAM, 4.0, false).with_profile(profile));
+    let demand = pdu.get_total_demand_w(400.0);
+    assert!(close(demand.average_w, 0.25 * 4.0 + 0.75 * 0.2));
+    assert_eq!(demand.peak_w, 4.0);
+
+    // A step that falls between two on-windows only sees the standby power
+    pdu.advance_to(30.0);
+    let demand = pdu.get_total_demand_w(60.0);
+    assert!(close(demand.average_w, 0.2));
+    assert_eq!(demand.peak_w, 0.2);
+}
+
+#[test]
+fn pulses_keep_their_phase_across_steps() {
+    let profile = LoadProfile::Pulsed { period_s: 10.0, pulse_s: 0.5, offset_s: 2.0, pulse_w: 8.0 };
+    let mut pdu = pdu_with(Load::new(LoadId::COM_TX, 0.5, false).with_profile(profile));
+    let whole = pdu.get_total_demand_w(60.0);
+    let mut energy_ws = 0.0;
+    for _ in 0..60 {
+        energy_ws += pdu.get_total_demand_w(1.0).average_w;
+        pdu.advance_to(pdu.get_time_s() + 1.0);
+    }
+    assert!(close(whole.average_w * 60.0, energy_ws));
+    assert!(close(whole.average_w, 0.5 + 7.5 * 0.05));
+    assert_eq!(whole.peak_w, 8.0);
+    assert_eq!(pdu.get_total_demand_w(1.0).peak_w, 0.5); // 60..61 s, between pulses at 52 and 62 s
+}
+
+#[test]
+fn inrush_follows_every_power_up() {
+    let mut pdu = pdu_with(Load::new(LoadId::PAYLOAD_CAM, 2.0, false).with_inrush(12.0, 0.5));
+    let demand = pdu.get_total_demand_w(10.0);
+    assert!(close(demand.average_w, (12.0 * 0.5 + 2.0 * 9.5) / 10.0));
+    assert_eq!(demand.peak_w, 12.0);
+
+    pdu.advance_to(10.0);
+    assert_eq!(pdu.get_total_demand_w(10.0).peak_w, 2.0);
+    pdu.switch_load(LoadId::PAYLOAD_CAM, false).unwrap();
+    pdu.switch_load(LoadId::PAYLOAD_CAM, true).unwrap();
+    assert_eq!(pdu.get_total_demand_w(10.0).peak_w, 12.0);
+}
+
+#[test]
+fn scheduled_window_switches_within_the_step() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::COM_TX, 6.0, false)).unwrap();
+    pdu.schedule_window(LoadId::COM_TX, 30.0, 510.0).unwrap();
+    assert_eq!(pdu.get_schedule().len(), 2);
+
+    let demand = pdu.get_total_demand_w(60.0);
+    assert!(close(demand.average_w, 3.0));
+    assert_eq!(demand.peak_w, 6.0);
+    pdu.advance_to(60.0);
+    let tx = pdu.get_load(LoadId::COM_TX).unwrap();
+    assert!(tx.is_on());
+    assert!(close(tx.get_on_time_s(), 30.0));
+    assert_eq!(pdu.get_schedule().len(), 1);
+
+    pdu.advance_to(480.0);
+    assert!(close(pdu.get_total_demand_w(60.0).average_w, 3.0));
+    pdu.advance_to(540.0);
+    assert!(!pdu.get_load(LoadId::COM_TX).unwrap().is_on());
+    assert!(pdu.get_schedule().is_empty());
+}
+
+#[test]
+fn stuck_on_duty_cycle_ignores_a_scheduled_switch_off() {
+    let profile = LoadProfile::DutyCycle { period_s: 100.0, on_s: 25.0, offset_s: 0.0, standby_w: 0.2 };
+    let mut pdu = pdu_with(Load::new(LoadId::PAYLOAD_CAM, 4.0, false).with_profile(profile));
+    pdu.inject_stuck_on(LoadId::PAYLOAD_CAM).unwrap();
+    pdu.schedule_switch(ScheduledSwitch { time_s: 200.0, load_id: LoadId::PAYLOAD_CAM, on: false }).unwrap();
+
+    // The welded channel keeps cycling through the off command
+    let demand = pdu.get_total_demand_w(400.0);
+    assert!(close(demand.average_w, 0.25 * 4.0 + 0.75 * 0.2));
+    pdu.advance_to(400.0);
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_on());
+    assert!(pdu.get_schedule().is_empty());
+    assert!(close(pdu.get_total_demand_w(400.0).average_w, 0.25 * 4.0 + 0.75 * 0.2));
+}
+
+#[test]
+fn schedule_rejects_unknown_loads_and_overflow() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::COM_TX, 6.0, false)).unwrap();
+    let switch = ScheduledSwitch { time_s: 10.0, load_id: LoadId::ADCS, on: true };
+    assert_eq!(pdu.schedule_switch(switch), Err(PduError::LoadNotFound));
+    for pass in 0..16 {
+        pdu.schedule_window(LoadId::COM_TX, 6000.0 * pass as f64, 6000.0 * pass as f64 + 600.0).unwrap();
+    }
+    assert_eq!(pdu.schedule_window(LoadId::COM_TX, 1e6, 1e6 + 600.0), Err(PduError::ScheduleFull));
+    assert_eq!(pdu.schedule_window(LoadId::COM_TX, 600.0, 300.0), Err(PduError::InvalidWindow));
+    // Queued out of order, executed in time order
+    assert!(pdu.get_schedule().windows(2).all(|pair| pair[0].time_s <= pair[1].time_s));
+    assert_eq!(pdu.cancel_schedule(LoadId::COM_TX), 32);
+}
+
+#[test]
+fn step_record_reports_average_and_peak_demand() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_TX, 6.0, false).with_inrush(10.0, 1.0)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.schedule_window(LoadId::COM_TX, 120.0, 150.0).unwrap();
+    let mut eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 30.0, 7.4, 40.0, 40.0), pdu);
+
+    let first = eps.manage_power(1.0 / 60.0);
+    assert!(close(first.demanded_power_w, 0.5));
+    assert_eq!(first.peak_demand_w, 0.5);
+    eps.manage_power(1.0 / 60.0);
+    let pass = eps.manage_power(1.0 / 60.0);
+    assert!(close(pass.demanded_power_w, 0.5 + (10.0 + 6.0 * 29.0) / 60.0));
+    assert_eq!(pass.peak_demand_w, 10.5);
+    assert!(!eps.get_pdu().get_load(LoadId::COM_TX).unwrap().is_on());
+}
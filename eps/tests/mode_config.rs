@@ -0,0 +1,96 @@
+use eps::{
+    Battery, EPS, Load, LoadId, LoadSetting, ModeConfigError, ModeLoadTable, PowerDistributionUnit, SatelliteOperationalMode,
+};
+use heapless::Vec;
+
+// Mission-specific loads, outside the standard ID space
+const IMAGER: LoadId = LoadId(16);
+const DOWNLINK: LoadId = LoadId(17);
+
+fn pdu() -> PowerDistributionUnit {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_RX, 0.3, true)).unwrap();
+    pdu.add_load(Load::new(IMAGER, 2.0, false)).unwrap();
+    pdu.add_load(Load::new(DOWNLINK, 4.0, false)).unwrap();
+    pdu.add_load(Load::new(LoadId::HEATERS, 1.0, false)).unwrap();
+    pdu
+}
+
+fn eps() -> EPS {
+    EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 30.0, 7.4, 20.0, 20.0), pdu())
+}
+
+fn is_on(eps: &EPS, load_id: LoadId) -> bool {
+    eps.get_pdu().get_load(load_id).unwrap().is_on()
+}
+
+#[test]
+fn rules_configure_each_mode() {
+    let rules = [
+        (SatelliteOperationalMode::PayloadOperation, IMAGER, LoadSetting::On),
+        (SatelliteOperationalMode::PayloadOperation, DOWNLINK, LoadSetting::On),
+        (SatelliteOperationalMode::SafeMode, LoadId::COM_RX, LoadSetting::Off),
+        (SatelliteOperationalMode::SafeMode, LoadId::HEATERS, LoadSetting::DontCare),
+    ];
+    let mut eps = eps().with_mode_load_rules(&rules).unwrap();
+
+    eps.set_satellite_mode(SatelliteOperationalMode::PayloadOperation);
+    assert!(is_on(&eps, LoadId::OBC) && is_on(&eps, IMAGER) && is_on(&eps, DOWNLINK));
+    assert!(!is_on(&eps, LoadId::HEATERS));
+
+    eps.get_pdu_mut().switch_load(LoadId::HEATERS, true).unwrap();
+    eps.set_satellite_mode(SatelliteOperationalMode::SafeMode);
+    assert!(is_on(&eps, LoadId::OBC));
+    assert!(!is_on(&eps, LoadId::COM_RX) && !is_on(&eps, IMAGER));
+    assert!(is_on(&eps, LoadId::HEATERS)); // Don't-care keeps the commanded state
+}
+
+#[test]
+fn unknown_and_conflicting_rules_are_rejected() {
+    let unknown = [
+        (SatelliteOperationalMode::PayloadOperation, IMAGER, LoadSetting::On),
+        (SatelliteOperationalMode::PayloadOperation, LoadId::PAYLOAD_CAM, LoadSetting::On),
+    ];
+    assert_eq!(ModeLoadTable::new(&unknown, &pdu()), Err(ModeConfigError::UnknownLoad { rule: 1 }));
+    assert_eq!(eps().with_mode_load_rules(&unknown).err(), Some(ModeConfigError::UnknownLoad { rule: 1 }));
+
+    let conflicting = [
+        (SatelliteOperationalMode::SafeMode, LoadId::HEATERS, LoadSetting::On),
+        (SatelliteOperationalMode::NominalSunlit, LoadId::HEATERS, LoadSetting::Off),
+        (SatelliteOperationalMode::SafeMode, LoadId::HEATERS, LoadSetting::Off),
+    ];
+    assert_eq!(ModeLoadTable::new(&conflicting, &pdu()), Err(ModeConfigError::ConflictingRule { rule: 2 }));
+}
+
+#[test]
+fn default_table_keeps_nominal_heaters_on() {
+    let mut eps = eps();
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    assert!(is_on(&eps, LoadId::HEATERS) && is_on(&eps, LoadId::COM_RX));
+    assert!(!is_on(&eps, IMAGER));
+    let table = eps.get_mode_load_table();
+    assert_eq!(table.get_setting(SatelliteOperationalMode::SafeMode, LoadId::HEATERS, false), LoadSetting::Off);
+    assert_eq!(table.get_setting(SatelliteOperationalMode::SafeMode, LoadId::OBC, true), LoadSetting::On);
+}
+
+#[test]
+fn loads_added_after_the_table_follow_their_rules() {
+    // The default table names the payload camera, which this PDU only gets later
+    let mut payload = eps();
+    payload.get_pdu_mut().add_load(Load::new(LoadId::PAYLOAD_CAM, 3.0, false)).unwrap();
+    payload.set_satellite_mode(SatelliteOperationalMode::PayloadOperation);
+    assert!(is_on(&payload, LoadId::PAYLOAD_CAM));
+
+    let rules = [
+        (SatelliteOperationalMode::SafeMode, IMAGER, LoadSetting::On),
+        (SatelliteOperationalMode::NominalSunlit, DOWNLINK, LoadSetting::DontCare),
+    ];
+    let mut eps = eps().with_mode_load_rules(&rules).unwrap();
+    eps.get_pdu_mut().add_load(Load::new(LoadId::ADCS, 0.8, true)).unwrap();
+    eps.set_satellite_mode(SatelliteOperationalMode::SafeMode);
+    assert!(is_on(&eps, IMAGER) && is_on(&eps, LoadId::ADCS)); // The new critical load gets the baseline
+    eps.get_pdu_mut().switch_load(DOWNLINK, true).unwrap();
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalSunlit);
+    assert!(is_on(&eps, DOWNLINK) && !is_on(&eps, IMAGER));
+}
@@ -0,0 +1,122 @@
+use eps::{
+    Battery, BatteryFault, EPS, Load, LoadId, ModeThresholds, Orbit, PowerDistributionUnit, SatelliteOperationalMode,
+    SolarPanel, TransitionReason, Vector3,
+};
+use heapless::Vec;
+
+mod common;
+use common::MINUTE_H;
+
+fn pdu() -> PowerDistributionUnit {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_RX, 0.3, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 3.0, false).with_shed_priority(3)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.switch_load(LoadId::COM_RX, true).unwrap();
+    pdu
+}
+
+// A +Z panel facing the sun; without an orbit the spacecraft stays sunlit
+fn sunlit_eps(soc: f64, panel_area_m2: f64) -> EPS {
+    let mut panels = Vec::new();
+    panels.push(SolarPanel::new("+Z", panel_area_m2, 0.3, Vector3::Z)).unwrap();
+    panels[0].deploy();
+    let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 40.0 * soc, 7.4, 20.0, 20.0), pdu());
+    eps.set_sun_vector_body(Vector3::Z);
+    eps
+}
+
+#[test]
+fn low_soc_enters_safe_mode_immediately() {
+    let mut eps = sunlit_eps(0.14, 0.0);
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::SafeMode);
+    let transition = eps.get_mode_transitions()[0];
+    assert_eq!(transition.time_s, 0.0);
+    assert_eq!(transition.from, SatelliteOperationalMode::NominalSunlit);
+    assert_eq!(transition.reason, TransitionReason::LowSoc);
+}
+
+#[test]
+fn blocking_battery_fault_enters_safe_mode() {
+    let mut battery = Battery::new("BAT0", 40.0, 30.0, 7.4, 20.0, 20.0);
+    battery.raise_fault(BatteryFault::CellShort);
+    let mut eps = EPS::new(Vec::new(), battery, pdu());
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_mode_transitions()[0].reason, TransitionReason::BatteryFault);
+
+    // A ground command takes it out, but the guards send it back while the fault is latched
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalSunlit);
+    assert_eq!(eps.get_mode_transitions()[1].reason, TransitionReason::GroundCommand);
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::SafeMode);
+
+    eps.clear_battery_fault(BatteryFault::CellShort);
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalSunlit);
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::NominalSunlit);
+    assert_eq!(eps.get_mode_transitions().len(), 4);
+}
+
+#[test]
+fn safe_mode_recovers_after_sustained_positive_balance() {
+    let thresholds = ModeThresholds { safe_recovery_time_s: 1800.0, ..ModeThresholds::default() };
+    let mut eps = sunlit_eps(0.14, 0.03).with_mode_thresholds(thresholds);
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::SafeMode);
+
+    let mut minutes = 0;
+    while *eps.get_current_mode() == SatelliteOperationalMode::SafeMode {
+        assert!(eps.get_mode_manager().get_safe_energy_balance_wh() >= 0.0);
+        eps.manage_power(MINUTE_H);
+        minutes += 1;
+        assert!(minutes < 24 * 60, "never left SafeMode");
+    }
+    let exit = eps.get_mode_transitions()[1];
+    assert_eq!(exit.reason, TransitionReason::SafeModeRecovered);
+    assert_eq!(exit.to, SatelliteOperationalMode::NominalSunlit);
+    assert!(exit.time_s >= 1800.0);
+    assert!(eps.get_battery().get_soc_percentage() >= 40.0);
+}
+
+#[test]
+fn safe_mode_is_kept_without_positive_balance() {
+    // Enough SoC to leave, but no generation: the balance stays negative
+    let thresholds = ModeThresholds { safe_recovery_time_s: 600.0, ..ModeThresholds::default() };
+    let mut eps = sunlit_eps(0.8, 0.0).with_mode_thresholds(thresholds);
+    eps.set_satellite_mode(SatelliteOperationalMode::SafeMode);
+    for _ in 0..120 {
+        eps.manage_power(MINUTE_H);
+    }
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::SafeMode);
+    assert_eq!(eps.get_mode_transitions().len(), 1);
+}
+
+#[test]
+fn payload_exit_waits_for_the_minimum_dwell() {
+    let mut eps = sunlit_eps(0.45, 0.0);
+    eps.set_satellite_mode(SatelliteOperationalMode::PayloadOperation);
+    for _ in 0..4 {
+        eps.manage_power(MINUTE_H);
+        assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::PayloadOperation);
+    }
+    eps.manage_power(MINUTE_H);
+    eps.manage_power(MINUTE_H);
+    assert_eq!(eps.get_current_mode(), &SatelliteOperationalMode::NominalSunlit);
+    let exit = eps.get_mode_transitions()[1];
+    assert_eq!(exit.reason, TransitionReason::PayloadLowSoc);
+    assert!(exit.time_s >= 300.0);
+}
+
+#[test]
+fn eclipse_transitions_are_logged() {
+    let orbit = Orbit::new(500.0, 97.4, 30.0, 0.0, 0.0);
+    let period_h = orbit.get_period_s() / 3600.0;
+    let mut eps = sunlit_eps(0.8, 0.03).with_orbit(orbit);
+    for _ in 0..200 {
+        eps.manage_power(period_h / 200.0);
+    }
+    let reasons: std::vec::Vec<TransitionReason> = eps.get_mode_transitions().iter().map(|t| t.reason).collect();
+    assert_eq!(reasons, [TransitionReason::EnteredEclipse, TransitionReason::LeftEclipse]);
+}
@@ -0,0 +1,65 @@
+use eps::{Battery, EPS, Load, LoadId, Orbit, PowerDistributionUnit, SatelliteOperationalMode, SolarPanel, Vector3};
+use heapless::Vec;
+
+fn leo_500km(beta_angle_deg: f64) -> Orbit {
+    Orbit::new(500.0, 97.4, 30.0, beta_angle_deg, 0.0)
+}
+
+#[test]
+fn leo_period_and_eclipse_duration() {
+    let orbit = leo_500km(0.0);
+    let period_min = orbit.get_period_s() / 60.0;
+    assert!((period_min - 94.6).abs() < 0.5, "period {period_min} min");
+
+    // Sample the geometric shadow test over one orbit and compare with the analytic fraction
+    let steps = 10_000;
+    let dt_s = orbit.get_period_s() / steps as f64;
+    let umbra_steps = (0..steps).filter(|i| !orbit.is_sunlit(*i as f64 * dt_s)).count();
+    let eclipse_min = umbra_steps as f64 * dt_s / 60.0;
+    let analytic_min = orbit.get_eclipse_fraction() * period_min;
+    assert!((eclipse_min - analytic_min).abs() < 0.1, "{eclipse_min} vs {analytic_min}");
+    assert!((eclipse_min - 35.5).abs() < 1.0, "eclipse {eclipse_min} min");
+}
+
+#[test]
+fn high_beta_angle_has_no_eclipse() {
+    let orbit = leo_500km(75.0);
+    assert_eq!(orbit.get_eclipse_fraction(), 0.0);
+    let dt_s = orbit.get_period_s() / 1000.0;
+    assert!((0..1000).all(|i| orbit.is_sunlit(i as f64 * dt_s)));
+}
+
+#[test]
+fn beta_angle_for_season_matches_orbit_plane() {
+    // Equatorial orbit at the equinox sees the sun in the orbit plane, at the solstice 23.44 deg above it
+    assert!(Orbit::beta_angle_for_season(0.0, 0.0, 0.0).abs() < 1e-9);
+    assert!((Orbit::beta_angle_for_season(0.0, 0.0, 90.0) - 23.44).abs() < 1e-9);
+}
+
+#[test]
+fn eps_switches_between_sunlit_and_eclipse_on_its_own() {
+    let mut panel = SolarPanel::new("+Z", 0.06, 0.28, Vector3::Z);
+    panel.deploy();
+    let mut panels = Vec::new();
+    panels.push(panel).unwrap();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    let orbit = leo_500km(0.0);
+    let period_h = orbit.get_period_s() / 3600.0;
+    let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 30.0, 8.2, 20.0, 20.0), pdu).with_orbit(orbit);
+
+    let steps = 200;
+    let mut eclipse_steps = 0;
+    for _ in 0..steps {
+        eps.manage_power(period_h / steps as f64);
+        if *eps.get_current_mode() == SatelliteOperationalMode::NominalEclipse {
+            eclipse_steps += 1;
+            assert_eq!(eps.get_total_generated_power_w(), 0.0);
+        } else {
+            assert!(eps.get_total_generated_power_w() > 0.0);
+        }
+    }
+    let eclipse_fraction = eclipse_steps as f64 / steps as f64;
+    assert!((eclipse_fraction - 0.375).abs() < 0.02, "eclipse fraction {eclipse_fraction}");
+}
@@ -0,0 +1,115 @@
+use eps::{
+    Battery, CellTechnology, EPS, Orbit, PowerDistributionUnit, PvString, RadiationDegradation, RemainingFactors, SolarCell,
+    SolarPanel, Vector3,
+};
+use eps::consts::SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2;
+use heapless::Vec;
+
+const YEAR_S: f64 = 8766.0 * 3600.0;
+
+#[test]
+fn fluence_rate_grows_with_altitude() {
+    let rate = RadiationDegradation::get_fluence_rate_for_altitude;
+    assert!((rate(500.0) / 10f64.powf(12.4) - 1.0).abs() < 1e-9);
+    assert!(rate(400.0) < rate(600.0) && rate(600.0) < rate(1400.0));
+    // Held flat outside the table
+    assert_eq!(rate(3000.0), rate(2000.0));
+    let orbit = Orbit::new(800.0, 98.6, 0.0, 0.0, 0.0);
+    let radiation = RadiationDegradation::for_orbit(CellTechnology::TripleJunctionGaAs, &orbit);
+    assert_eq!(radiation.get_fluence_rate_e_cm2_year(), rate(800.0));
+    assert!((radiation.get_fluence_e_cm2(2.0 * YEAR_S) - 2.0 * rate(800.0)).abs() < 1.0);
+}
+
+#[test]
+fn triple_junction_keeps_more_power_than_silicon() {
+    assert_eq!(RemainingFactors::for_fluence(CellTechnology::Silicon, 0.0), RemainingFactors::default());
+    let gaas = RemainingFactors::for_fluence(CellTechnology::TripleJunctionGaAs, 1e15);
+    let silicon = RemainingFactors::for_fluence(CellTechnology::Silicon, 1e15);
+    assert_eq!((gaas.isc, gaas.voc, gaas.pmax), (0.98, 0.91, 0.87));
+    assert!(silicon.pmax < gaas.pmax && silicon.isc < gaas.isc);
+    // Power falls faster than either current or voltage
+    for factors in [gaas, silicon] {
+        assert!(factors.pmax < factors.isc.min(factors.voc));
+    }
+    let later = RemainingFactors::for_fluence(CellTechnology::TripleJunctionGaAs, 3e15);
+    assert!(later.pmax < gaas.pmax && later.voc < gaas.voc);
+}
+
+#[test]
+fn panel_output_follows_mission_time() {
+    let radiation = RadiationDegradation::new(CellTechnology::Silicon, 1e14);
+    let mut panel = SolarPanel::new("+Z", 0.03, 0.3, Vector3::Z).with_radiation(radiation);
+    panel.deploy();
+    panel.update_power_output(1361.0, &Vector3::Z);
+    let bol_w = panel.get_power_output_w();
+    assert!((bol_w - 0.03 * 0.3 * 1361.0).abs() < 1e-9);
+
+    panel.update_radiation_damage(10.0 * YEAR_S);
+    panel.update_power_output(1361.0, &Vector3::Z);
+    let pmax = RemainingFactors::for_fluence(CellTechnology::Silicon, 1e15).pmax;
+    assert!((panel.get_remaining_factors().pmax - pmax).abs() < 1e-9);
+    assert!((panel.get_power_output_w() - pmax * bol_w).abs() < 1e-9);
+    assert!((panel.get_end_of_life_power_w(10.0) - pmax * panel.get_end_of_life_power_w(0.0)).abs() < 1e-9);
+}
+
+#[test]
+fn iv_string_maximum_power_tracks_the_pmax_curve() {
+    let string = PvString::new(SolarCell::for_technology(CellTechnology::TripleJunctionGaAs), 4, 1);
+    let radiation = RadiationDegradation::new(CellTechnology::TripleJunctionGaAs, 1e14);
+    let panel = SolarPanel::new("+Z", 4.0 * 0.003018, 0.3, Vector3::Z).with_string(string).with_radiation(radiation);
+    let bol_w = string.get_maximum_power_point(1367.0, 28.0).1;
+    let eol_w = panel.get_end_of_life_power_w(10.0);
+    let factors = radiation.get_end_of_life_factors(10.0);
+    assert!((eol_w / bol_w - factors.pmax).abs() < 1e-5, "{} vs {}", eol_w / bol_w, factors.pmax);
+    let degraded = panel.get_string().unwrap();
+    assert_eq!(degraded.get_cell().open_circuit_voltage_v, string.get_cell().open_circuit_voltage_v); // Mission time zero
+}
+
+#[test]
+fn ideal_and_iv_panels_lose_the_same_power_at_the_same_fluence() {
+    let radiation = RadiationDegradation::new(CellTechnology::TripleJunctionGaAs, 3e14);
+    let string = PvString::new(SolarCell::for_technology(CellTechnology::TripleJunctionGaAs), 8, 2);
+    let mut ideal = SolarPanel::new("+Z", 0.06, 0.3, Vector3::Z).with_radiation(radiation);
+    let mut iv = SolarPanel::new("-Z", 0.06, 0.3, Vector3::Z).with_string(string).with_radiation(radiation);
+    let mut output_w = |time_s: f64| {
+        [&mut ideal, &mut iv].map(|panel| {
+            panel.deploy();
+            panel.update_radiation_damage(time_s);
+            panel.update_power_output(SOLAR_CELL_REFERENCE_IRRADIANCE_W_M2, &Vector3::Z);
+            panel.get_power_output_w()
+        })
+    };
+    let [ideal_bol_w, iv_bol_w] = output_w(0.0);
+    let [ideal_eol_w, iv_eol_w] = output_w(5.0 * YEAR_S);
+    let pmax = RemainingFactors::for_fluence(CellTechnology::TripleJunctionGaAs, 1.5e15).pmax;
+    assert!((ideal_eol_w / ideal_bol_w - pmax).abs() < 1e-9);
+    assert!((iv_eol_w / iv_bol_w - pmax).abs() < 1e-5, "{} vs {}", iv_eol_w / iv_bol_w, pmax);
+    // Isc and Voc keep their own curves
+    let factors = iv.get_remaining_factors();
+    let cell = iv.get_string().unwrap().get_cell();
+    assert!((cell.short_circuit_current_a - factors.isc * string.get_cell().short_circuit_current_a).abs() < 1e-12);
+    assert!((cell.open_circuit_voltage_v - factors.voc * string.get_cell().open_circuit_voltage_v).abs() < 1e-12);
+}
+
+#[test]
+fn eps_predicts_end_of_life_generation() {
+    let orbit = Orbit::new(800.0, 98.6, 0.0, 90.0, 0.0); // Dawn-dusk, always sunlit
+    let eps_at = |technology: CellTechnology, age_years: f64| {
+        let radiation = RadiationDegradation::for_orbit(technology, &orbit).with_mission_age_years(age_years);
+        let mut panel = SolarPanel::new("+Z", 0.06, 0.3, Vector3::Z).with_radiation(radiation);
+        panel.deploy();
+        let mut panels = Vec::new();
+        panels.push(panel).unwrap();
+        let mut eps = EPS::new(panels, Battery::new("BAT0", 40.0, 20.0, 7.4, 20.0, 20.0), PowerDistributionUnit::new())
+            .with_orbit(orbit.clone());
+        eps.set_sun_vector_body(Vector3::Z);
+        eps.manage_power(1.0 / 60.0);
+        eps.get_total_generated_power_w()
+    };
+    let bol_w = eps_at(CellTechnology::TripleJunctionGaAs, 0.0);
+    let gaas_eol_w = eps_at(CellTechnology::TripleJunctionGaAs, 5.0);
+    let silicon_eol_w = eps_at(CellTechnology::Silicon, 5.0);
+    assert!(gaas_eol_w < bol_w && silicon_eol_w < gaas_eol_w);
+    // Five years at 800 km: about 2e14 e/cm^2
+    assert!(gaas_eol_w / bol_w > 0.9 && gaas_eol_w / bol_w < 0.95);
+}
@@ -0,0 +1,105 @@
+use eps::{
+    Battery, DcDcConverter, EPS, LatchingCurrentLimiter, LclRecovery, Load, LoadId, PowerDistributionUnit, PowerRail, Rail,
+    RailTelemetry,
+};
+use heapless::Vec;
+
+fn rail_pdu() -> PowerDistributionUnit {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::ADCS, 1.65, true).with_rail(PowerRail::V3_3)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 6.0, false).with_rail(PowerRail::V5)).unwrap();
+    for id in [LoadId::OBC, LoadId::ADCS, LoadId::PAYLOAD_CAM] {
+        pdu.switch_load(id, true).unwrap();
+    }
+    pdu
+}
+
+#[test]
+fn converter_efficiency_depends_on_load() {
+    let buck = DcDcConverter::buck(20.0);
+    assert!(buck.get_efficiency(0.5) < buck.get_efficiency(6.0));
+    assert!(buck.get_efficiency(20.0) < buck.get_efficiency(12.0));
+    assert!((buck.get_efficiency(6.0) - 0.93).abs() < 1e-9);
+    assert_eq!(buck.get_input_power_w(0.0), 0.0);
+    // Light loads waste a larger share
+    let light_loss = buck.get_input_power_w(0.5) / 0.5;
+    let heavy_loss = buck.get_input_power_w(6.0) / 6.0;
+    assert!(light_loss > heavy_loss);
+}
+
+#[test]
+fn demand_includes_losses_broken_down_per_rail() {
+    let pdu = rail_pdu();
+    let rails = pdu.get_rail_demand(60.0);
+    let kinds: std::vec::Vec<PowerRail> = rails.iter().map(|rail| rail.rail).collect();
+    assert_eq!(kinds, [PowerRail::Raw, PowerRail::V3_3, PowerRail::V5, PowerRail::V12]);
+    assert_eq!((rails[0].output.average_w, rails[0].loss_w), (0.5, 0.0));
+    assert!((rails[1].output.average_w - 1.65).abs() < 1e-9);
+    assert!((rails[1].input.average_w - 1.65 / 0.87).abs() < 1e-9); // 10 % of 16.5 W
+    assert!((rails[2].loss_w - (6.0 / 0.93 - 6.0)).abs() < 1e-9);
+    assert_eq!(rails[3].input.average_w, 0.0);
+
+    let total = pdu.get_total_demand_w(60.0);
+    let input_w: f64 = rails.iter().map(|rail| rail.input.average_w).sum();
+    assert!((total.average_w - input_w).abs() < 1e-12);
+    assert!(total.average_w > 0.5 + 1.65 + 6.0);
+}
+
+#[test]
+fn regulated_channels_are_limited_at_the_rail_voltage() {
+    let load = Load::new(LoadId::ADCS, 1.65, true).with_rail(PowerRail::V3_3);
+    assert!((load.get_lcl().get_current_limit_a() - 2.0 * 1.65 / 3.3).abs() < 1e-9);
+    let mut pdu = rail_pdu();
+    assert!(pdu.update_current_limiters(7.4, 60.0).is_empty());
+    assert_eq!(Load::new(LoadId::ADCS, 1.65, true).get_rail(), PowerRail::Raw);
+}
+
+#[test]
+fn moving_to_a_rail_keeps_a_custom_limiter() {
+    let lcl = LatchingCurrentLimiter::new(1.0, 0.02, LclRecovery::LatchOff);
+    let load = Load::new(LoadId::PAYLOAD_CAM, 3.0, false).with_lcl(lcl.clone()).with_rail(PowerRail::V5);
+    assert!((load.get_lcl().get_current_limit_a() - 1.0 * 7.4 / 5.0).abs() < 1e-9);
+    assert_eq!(load.get_lcl().get_recovery(), LclRecovery::LatchOff);
+    // Given after the rail, the limiter is already sized for it
+    let later = Load::new(LoadId::PAYLOAD_CAM, 3.0, false).with_rail(PowerRail::V5).with_lcl(lcl);
+    assert_eq!(later.get_lcl().get_current_limit_a(), 1.0);
+}
+
+#[test]
+fn custom_rails_replace_the_standard_ones() {
+    let mut pdu = rail_pdu();
+    pdu.set_rail(Rail::new(PowerRail::V5, Some(DcDcConverter::new(10.0, &[(0.0, 0.5), (1.0, 0.5)]))));
+    assert_eq!(pdu.get_rails().len(), 4);
+    let rails = pdu.get_rail_demand(60.0);
+    assert!((rails[2].input.average_w - 12.0).abs() < 1e-9);
+    pdu.set_rail(Rail::new(PowerRail::V5, None));
+    assert_eq!(pdu.get_rail_demand(60.0)[2].loss_w, 0.0);
+}
+
+#[test]
+fn rail_telemetry_uses_p31u_units() {
+    let mut pdu = rail_pdu();
+    pdu.update_current_limiters(8.0, 1.0);
+    let telemetry = pdu.get_rail_telemetry();
+    assert_eq!(telemetry.len(), 4);
+    assert_eq!((telemetry[0].voltage_mv, telemetry[0].current_ma), (8000, 63)); // 0.5 W at 8 V
+    assert_eq!((telemetry[1].voltage_mv, telemetry[1].current_ma), (3300, 500));
+    assert_eq!(telemetry[2].current_ma, 1200);
+    assert_eq!(telemetry[2].loss_mw, 452);
+    assert_eq!(telemetry[2].input_current_ma, 806);
+
+    let bytes = telemetry[1].encode();
+    assert_eq!(bytes[..3], [1, 0x0C, 0xE4]); // 3300 mV, big-endian
+    assert_eq!(RailTelemetry::decode(bytes), Some(telemetry[1]));
+    assert_eq!(RailTelemetry::decode([9; RailTelemetry::SIZE]), None);
+}
+
+#[test]
+fn step_record_reports_conversion_losses() {
+    let mut eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 30.0, 7.4, 40.0, 40.0), rail_pdu());
+    let record = eps.manage_power(1.0 / 60.0);
+    assert!(record.conversion_loss_w() > 0.4);
+    let output_w: f64 = record.rail_demand.iter().map(|rail| rail.output.average_w).sum();
+    assert!((record.demanded_power_w - output_w - record.conversion_loss_w()).abs() < 1e-9);
+}
@@ -0,0 +1,95 @@
+use eps::regulator::{direct_energy_transfer_power_w, mppt_gain_w};
+use eps::{
+    Battery, ChargeRegulator, EPS, MpptAlgorithm, MpptTracker, PowerDistributionUnit, PvString, RegulatorKind,
+    SolarCell, SolarPanel, Vector3,
+};
+use heapless::Vec;
+
+const AM0_W_M2: f64 = 1367.0;
+
+// Four triple-junction cells in series, as on a 2S battery bus
+fn gaas_string() -> PvString {
+    PvString::new(SolarCell::triple_junction_gaas(), 4, 1)
+}
+
+#[test]
+fn iv_curve_matches_the_datasheet_at_reference_conditions() {
+    let cell = PvString::new(SolarCell::triple_junction_gaas(), 1, 1);
+    assert!((cell.get_current_a(0.0, AM0_W_M2, 28.0) - 0.52).abs() < 1e-3);
+    assert!((cell.get_open_circuit_voltage_v(AM0_W_M2, 28.0) - 2.69).abs() < 1e-3);
+    assert!(cell.get_current_a(2.69, AM0_W_M2, 28.0) < 1e-3);
+
+    let (mpp_v, mpp_w) = cell.get_maximum_power_point(AM0_W_M2, 28.0);
+    assert!(mpp_v > 2.3 && mpp_v < 2.5, "Vmp {mpp_v}");
+    assert!(mpp_w > 1.1 && mpp_w < 1.25, "Pmp {mpp_w}");
+
+    let string = PvString::new(SolarCell::triple_junction_gaas(), 4, 2);
+    assert!((string.get_open_circuit_voltage_v(AM0_W_M2, 28.0) - 4.0 * 2.69).abs() < 1e-3);
+    assert!((string.get_maximum_power_point(AM0_W_M2, 28.0).1 - 8.0 * mpp_w).abs() < 1e-3);
+}
+
+#[test]
+fn hot_cells_lose_voltage_and_power() {
+    let string = gaas_string();
+    let cold_voc_v = string.get_open_circuit_voltage_v(AM0_W_M2, 0.0);
+    let hot_voc_v = string.get_open_circuit_voltage_v(AM0_W_M2, 80.0);
+    assert!((cold_voc_v - hot_voc_v - 4.0 * 0.0062 * 80.0).abs() < 1e-3);
+    assert!(string.get_short_circuit_current_a(AM0_W_M2, 80.0) > string.get_short_circuit_current_a(AM0_W_M2, 0.0));
+    assert!(string.get_maximum_power_point(AM0_W_M2, 80.0).1 < string.get_maximum_power_point(AM0_W_M2, 0.0).1);
+    // Voc drops logarithmically with irradiance, current linearly
+    assert!(string.get_open_circuit_voltage_v(AM0_W_M2 / 2.0, 28.0) > 0.95 * string.get_open_circuit_voltage_v(AM0_W_M2, 28.0));
+}
+
+#[test]
+fn trackers_converge_to_the_maximum_power_point() {
+    let string = gaas_string();
+    let (_, mpp_w) = string.get_maximum_power_point(AM0_W_M2, 28.0);
+    for algorithm in [MpptAlgorithm::PerturbAndObserve, MpptAlgorithm::IncrementalConductance] {
+        let mut tracker = MpptTracker::default();
+        let voltage_v = tracker.track(algorithm, &string, AM0_W_M2, 28.0, 200);
+        let power_w = string.get_power_w(voltage_v, AM0_W_M2, 28.0);
+        assert!(power_w > 0.99 * mpp_w, "{algorithm:?}: {power_w} W of {mpp_w} W");
+
+        // Follows a change in operating conditions
+        let (_, hot_mpp_w) = string.get_maximum_power_point(AM0_W_M2, 80.0);
+        let voltage_v = tracker.track(algorithm, &string, AM0_W_M2, 80.0, 200);
+        assert!(string.get_power_w(voltage_v, AM0_W_M2, 80.0) > 0.99 * hot_mpp_w);
+    }
+}
+
+#[test]
+fn mppt_gain_over_det_depends_on_battery_voltage() {
+    let string = gaas_string();
+    let (mpp_v, mpp_w) = string.get_maximum_power_point(AM0_W_M2, 28.0);
+
+    // Low battery: DET drags the array far below its maximum power voltage
+    let low_gain_w = mppt_gain_w(&string, AM0_W_M2, 28.0, 6.0);
+    assert!(low_gain_w > 0.2 * mpp_w, "gain {low_gain_w} W");
+
+    // Battery just below the maximum power voltage: DET is as good or better than a lossy converter
+    let matched_gain_w = mppt_gain_w(&string, AM0_W_M2, 28.0, mpp_v - 0.3);
+    assert!(matched_gain_w < low_gain_w);
+    assert!(matched_gain_w.abs() < 0.1 * mpp_w);
+
+    // Battery above the array's open-circuit voltage: DET delivers nothing
+    assert_eq!(direct_energy_transfer_power_w(&string, AM0_W_M2, 28.0, 11.0), 0.0);
+    assert!(mppt_gain_w(&string, AM0_W_M2, 28.0, 11.0) > 0.9 * mpp_w);
+}
+
+#[test]
+fn eps_extracts_more_power_with_mppt_on_a_low_battery() {
+    let generation = |kind: RegulatorKind| {
+        let mut panels = Vec::new();
+        panels.push(SolarPanel::new("+Z", 0.012, 0.3, Vector3::Z).with_string(gaas_string())).unwrap();
+        panels[0].deploy();
+        let battery = Battery::new("BAT0", 40.0, 4.0, 7.4, 40.0, 40.0);
+        let mut eps = EPS::new(panels, battery, PowerDistributionUnit::new()).with_regulator(ChargeRegulator::new(kind));
+        eps.set_sun_vector_body(Vector3::Z);
+        eps.manage_power(0.01);
+        eps.get_total_generated_power_w()
+    };
+    let det_w = generation(RegulatorKind::DirectEnergyTransfer);
+    let mppt_w = generation(RegulatorKind::Mppt(MpptAlgorithm::IncrementalConductance));
+    assert!(det_w > 0.0);
+    assert!(mppt_w > det_w * 1.1, "MPPT {mppt_w} W, DET {det_w} W");
+}
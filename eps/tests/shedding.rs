@@ -0,0 +1,144 @@
+use eps::{
+    Battery, EPS, Load, LoadId, LoadSheddingPolicy, PowerDistributionUnit, SatelliteOperationalMode, SheddingAction,
+    SheddingError, SheddingReason,
+};
+use heapless::Vec;
+
+fn tiered_pdu() -> PowerDistributionUnit {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_CAM, 2.0, false)).unwrap();
+    pdu.add_load(Load::new(LoadId::PAYLOAD_TX, 4.0, false)).unwrap();
+    pdu.add_load(Load::new(LoadId::ADCS, 1.0, false).with_shed_priority(1)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_TX, 3.0, false).with_shed_priority(2)).unwrap();
+    for load in [LoadId::OBC, LoadId::PAYLOAD_CAM, LoadId::PAYLOAD_TX, LoadId::ADCS, LoadId::COM_TX] {
+        pdu.switch_load(load, true).unwrap();
+    }
+    pdu
+}
+
+#[test]
+fn tiers_are_shed_one_at_a_time_as_soc_falls() {
+    let mut pdu = tiered_pdu();
+    let mut policy = LoadSheddingPolicy::default();
+    assert!(policy.update(&mut pdu, 45.0).is_empty());
+
+    let decisions = policy.update(&mut pdu, 29.0);
+    assert_eq!(decisions.len(), 1);
+    let decision = &decisions[0];
+    assert_eq!(decision.action, SheddingAction::Shed);
+    assert_eq!(decision.priority, 0);
+    assert_eq!(decision.reason, SheddingReason::SocBelowShedThreshold);
+    assert_eq!(decision.threshold_soc, 30.0);
+    assert_eq!(decision.power_w, 6.0);
+    assert_eq!(decision.loads.as_slice(), [LoadId::PAYLOAD_CAM, LoadId::PAYLOAD_TX]);
+    assert!(pdu.get_load(LoadId::ADCS).unwrap().is_on());
+
+    let decisions = policy.update(&mut pdu, 19.0);
+    assert_eq!(decisions[0].priority, 1);
+    assert!(!pdu.get_load(LoadId::ADCS).unwrap().is_on());
+    assert!(pdu.get_load(LoadId::COM_TX).unwrap().is_on());
+
+    policy.update(&mut pdu, 5.0);
+    assert_eq!(pdu.get_total_demand_w(1.0).average_w, 0.5); // Critical loads are never shed
+}
+
+#[test]
+fn restore_needs_the_hysteresis_margin() {
+    let mut pdu = tiered_pdu();
+    let mut policy = LoadSheddingPolicy::default();
+    policy.update(&mut pdu, 29.0);
+    // Hovering around the shed threshold does not flap the tier
+    for soc in [31.0, 29.5, 35.0, 28.0, 39.9] {
+        assert!(policy.update(&mut pdu, soc).is_empty());
+    }
+    assert!(!pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_on());
+
+    let decisions = policy.update(&mut pdu, 40.5);
+    assert_eq!(decisions[0].action, SheddingAction::Restore);
+    assert_eq!(decisions[0].reason, SheddingReason::SocAboveRestoreThreshold);
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_on());
+    assert!(pdu.get_load(LoadId::PAYLOAD_TX).unwrap().is_on());
+}
+
+#[test]
+fn tiers_come_back_in_reverse_order() {
+    let mut pdu = tiered_pdu();
+    // Loads switched off before shedding are not switched back on
+    pdu.switch_load(LoadId::PAYLOAD_TX, false).unwrap();
+    let mut policy = LoadSheddingPolicy::default();
+    policy.update(&mut pdu, 5.0);
+    assert!(pdu.get_load(LoadId::PAYLOAD_TX).unwrap().is_held_off());
+
+    let decisions = policy.update(&mut pdu, 25.0);
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].priority, 2);
+
+    let decisions = policy.update(&mut pdu, 50.0);
+    let restored: std::vec::Vec<u8> = decisions.iter().map(|decision| decision.priority).collect();
+    assert_eq!(restored, [1, 0]);
+    assert!(pdu.get_load(LoadId::PAYLOAD_CAM).unwrap().is_on());
+    assert!(!pdu.get_load(LoadId::PAYLOAD_TX).unwrap().is_on());
+    assert!(!pdu.get_load(LoadId::PAYLOAD_TX).unwrap().is_held_off());
+}
+
+#[test]
+fn tiers_are_validated() {
+    let mut policy = LoadSheddingPolicy::new();
+    assert_eq!(policy.add_tier(0, 30.0, 30.0), Err(SheddingError::NoHysteresis));
+    for priority in 0..4 {
+        policy.add_tier(priority, 10.0 * priority as f64, 10.0 * priority as f64 + 5.0).unwrap();
+    }
+    assert_eq!(policy.add_tier(4, 50.0, 60.0), Err(SheddingError::TooManyTiers));
+    // Ordered by decreasing shed threshold
+    assert_eq!(policy.get_tiers()[0].get_priority(), 3);
+}
+
+#[test]
+fn eps_reports_shedding_decisions() {
+    let battery = Battery::new("BAT0", 40.0, 12.4, 7.4, 40.0, 40.0);
+    let mut eps = EPS::new(Vec::new(), battery, tiered_pdu());
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    for load in [LoadId::PAYLOAD_CAM, LoadId::PAYLOAD_TX, LoadId::ADCS] {
+        eps.get_pdu_mut().switch_load(load, true).unwrap();
+    }
+    eps.manage_power(0.01);
+    assert!(eps.get_shedding_decisions().is_empty());
+    while eps.get_shedding_decisions().is_empty() {
+        eps.manage_power(0.01);
+    }
+    let decision = &eps.get_shedding_decisions()[0];
+    assert_eq!(decision.priority, 0);
+    assert!(decision.soc_percentage < 30.0);
+    assert!(!eps.get_pdu().get_load(LoadId::PAYLOAD_TX).unwrap().is_on());
+}
+
+#[test]
+fn scheduled_windows_keep_a_shed_tier_off() {
+    let mut pdu = tiered_pdu();
+    let mut policy = LoadSheddingPolicy::default();
+    policy.update(&mut pdu, 29.0);
+    pdu.schedule_window(LoadId::PAYLOAD_TX, 30.0, 510.0).unwrap();
+    assert_eq!(pdu.get_load_demand_w(LoadId::PAYLOAD_TX, 60.0).unwrap().average_w, 0.0);
+    pdu.advance_to(60.0);
+    assert!(!pdu.get_load(LoadId::PAYLOAD_TX).unwrap().is_on());
+}
+
+#[test]
+fn mode_changes_keep_a_shed_tier_off() {
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.add_load(Load::new(LoadId::COM_RX, 0.3, false)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    pdu.switch_load(LoadId::COM_RX, true).unwrap();
+    let mut eps = EPS::new(Vec::new(), Battery::new("BAT0", 40.0, 10.8, 7.4, 40.0, 40.0), pdu);
+    eps.manage_power(0.01);
+    assert_eq!(eps.get_shedding_decisions()[0].loads.as_slice(), [LoadId::COM_RX]);
+    // Eclipse switches COM_RX on, but tier 0 is still shed at 27 %
+    eps.set_satellite_mode(SatelliteOperationalMode::NominalEclipse);
+    for _ in 0..10 {
+        eps.manage_power(0.01);
+        assert!(!eps.get_pdu().get_load(LoadId::COM_RX).unwrap().is_on());
+    }
+    assert!(eps.get_pdu().get_load(LoadId::COM_RX).unwrap().is_held_off());
+}
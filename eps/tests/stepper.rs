@@ -0,0 +1,144 @@
+use eps::{
+    AdaptiveStepper, Battery, CrossingKind, EPS, EpsEvent, EventSink, Load, LoadId, Orbit, PowerDistributionUnit,
+    SatelliteOperationalMode, SolarPanel, Vector3,
+};
+use eps::consts::ADAPTIVE_MIN_STEP_S;
+use heapless::Vec;
+
+mod common;
+use common::draining_eps;
+
+fn orbiting_eps() -> EPS {
+    let mut panel = SolarPanel::new("+Z", 0.06, 0.28, Vector3::Z);
+    panel.deploy();
+    let mut panels = Vec::new();
+    panels.push(panel).unwrap();
+    let mut pdu = PowerDistributionUnit::new();
+    pdu.add_load(Load::new(LoadId::OBC, 0.5, true)).unwrap();
+    pdu.switch_load(LoadId::OBC, true).unwrap();
+    EPS::new(panels, Battery::new("BAT0", 40.0, 30.0, 8.2, 20.0, 20.0), pdu).with_orbit(Orbit::new(500.0, 97.4, 30.0, 0.0, 0.0))
+}
+
+// Time the SoC first reaches `threshold`, stepping one second at a time
+fn reference_crossing_s(mut eps: EPS, threshold: f64) -> f64 {
+    while eps.get_battery().get_soc_percentage() > threshold {
+        eps.manage_power(1.0 / 3600.0);
+    }
+    eps.get_elapsed_time_s()
+}
+
+#[test]
+fn soc_crossing_is_located_within_the_resolution() {
+    let reference_s = reference_crossing_s(draining_eps(), 15.0);
+    let mut eps = draining_eps();
+    let stepper = AdaptiveStepper::new(600.0, 1.0).with_soc_threshold(15.0);
+    let step = stepper.advance(&mut eps, 3600.0);
+
+    assert_eq!(step.crossings.len(), 1);
+    assert_eq!(step.crossings[0].kind, CrossingKind::SocBelow(15.0));
+    // Beyond the resolution, the longer sub-steps integrate the polarisation differently
+    assert!((step.crossings[0].time_s - reference_s).abs() <= 5.0, "{} vs {reference_s}", step.crossings[0].time_s);
+    assert!(step.substeps > 6);
+    assert!((eps.get_elapsed_time_s() - 3600.0).abs() < 1e-9);
+    assert!((step.record.duration_h - 1.0).abs() < 1e-9);
+    assert_eq!(step.record.time_s, 0.0);
+    assert_eq!(step.record.soc_percentage, eps.get_battery().get_soc_percentage());
+}
+
+#[test]
+fn step_limits_are_kept_positive() {
+    for (max_step_s, resolution_s) in [(-1.0, 1.0), (0.0, 0.0), (60.0, -1.0), (f64::NAN, f64::NAN)] {
+        let stepper = AdaptiveStepper::new(max_step_s, resolution_s);
+        assert!(stepper.get_max_step_s() >= ADAPTIVE_MIN_STEP_S && stepper.get_resolution_s() >= ADAPTIVE_MIN_STEP_S);
+        assert!(stepper.get_resolution_s() <= stepper.get_max_step_s());
+    }
+    // The smallest step still reaches the end
+    let mut eps = draining_eps();
+    let step = AdaptiveStepper::new(0.0, 0.0).advance(&mut eps, 0.01);
+    assert_eq!(step.substeps, 10);
+    assert!((eps.get_elapsed_time_s() - 0.01).abs() < 1e-9);
+}
+
+#[test]
+fn safe_mode_entry_does_not_depend_on_the_outer_step() {
+    let reference_s = reference_crossing_s(draining_eps(), 15.0);
+    let entry_s = |outer_step_s: f64| {
+        let mut eps = draining_eps();
+        let stepper = AdaptiveStepper::for_eps(&eps);
+        let mut transitions = std::vec::Vec::new();
+        while eps.get_elapsed_time_s() < 3600.0 {
+            transitions.extend(stepper.advance(&mut eps, outer_step_s).record.mode_transitions);
+        }
+        let entry = transitions.iter().find(|transition| transition.to == SatelliteOperationalMode::SafeMode).unwrap();
+        entry.time_s
+    };
+    for outer_step_s in [60.0, 600.0, 1800.0] {
+        assert!((entry_s(outer_step_s) - reference_s).abs() <= 1.0, "outer step {outer_step_s} s");
+    }
+
+    // A fixed 600 s step only notices at its next start
+    let mut eps = draining_eps();
+    while *eps.get_current_mode() != SatelliteOperationalMode::SafeMode {
+        eps.manage_power(600.0 / 3600.0);
+    }
+    assert!(eps.get_mode_transitions().last().unwrap().time_s - reference_s > 60.0);
+}
+
+#[test]
+fn eclipse_boundaries_are_located_from_the_orbit() {
+    let mut eps = orbiting_eps();
+    let orbit = eps.get_orbit().unwrap().clone();
+    let stepper = AdaptiveStepper::new(60.0, 0.5).without_eclipse();
+    assert!(stepper.advance(&mut eps.clone(), orbit.get_period_s()).crossings.is_empty());
+
+    let stepper = AdaptiveStepper::new(60.0, 0.5);
+    let step = stepper.advance(&mut eps, orbit.get_period_s());
+    let kinds: std::vec::Vec<CrossingKind> = step.crossings.iter().map(|crossing| crossing.kind).collect();
+    assert_eq!(kinds, [CrossingKind::EnteredEclipse, CrossingKind::LeftEclipse]);
+    for crossing in step.crossings.iter() {
+        // Within the resolution after the boundary
+        assert_ne!(orbit.is_sunlit(crossing.time_s), orbit.is_sunlit(crossing.time_s - 0.5));
+    }
+    let eclipse_s = step.crossings[1].time_s - step.crossings[0].time_s;
+    assert!((eclipse_s - orbit.get_eclipse_fraction() * orbit.get_period_s()).abs() < 1.0);
+}
+
+#[derive(Default)]
+struct Recorder(std::vec::Vec<String>);
+
+impl EventSink for Recorder {
+    fn on_event(&mut self, event: &EpsEvent) {
+        match event {
+            EpsEvent::ModeChanged(transition) => self.0.push(format!("mode {:?}", transition.to)),
+            EpsEvent::ThresholdCrossed(crossing) => self.0.push(format!("crossing {:?}", crossing.kind)),
+            EpsEvent::StepCompleted(_) => self.0.push("step".into()),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn sink_sees_crossings_before_the_reaction() {
+    let mut eps = draining_eps();
+    let mut sink = Recorder::default();
+    let step = AdaptiveStepper::new(600.0, 1.0).with_soc_threshold(15.0).advance_with(&mut eps, 3600.0, &mut sink);
+
+    assert_eq!(sink.0.iter().filter(|event| *event == "step").count(), step.substeps as usize);
+    let crossing = sink.0.iter().position(|event| event == "crossing SocBelow(15.0)").unwrap();
+    let safe = sink.0.iter().position(|event| event == "mode SafeMode").unwrap();
+    assert_eq!(sink.0[crossing - 1], "step");
+    assert!(crossing < safe);
+}
+
+#[test]
+fn default_thresholds_follow_the_eps_configuration() {
+    let eps = draining_eps();
+    let stepper = AdaptiveStepper::for_eps(&eps);
+    for soc in [15.0, 40.0, 50.0] {
+        assert!(stepper.get_soc_thresholds().contains(&soc));
+    }
+    let tier = &eps.get_shedding_policy().get_tiers()[0];
+    assert!(stepper.get_soc_thresholds().contains(&tier.get_shed_below_soc()));
+    assert_eq!(stepper.get_voltage_thresholds().len(), 3);
+    assert_eq!(stepper.get_max_step_s(), 60.0);
+}
@@ -8,8 +8,8 @@ fn main() -> Result<()> {
 
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <serial|usb>", args[0]);
+    if args.len() < 2 || (args[1] == "decode") != (args.len() == 3) {
+        eprintln!("Usage: {} <serial|usb|decode <hex>>", args[0]);
         return Err(color_eyre::eyre::eyre!("Invalid arguments"));
     }
 
@@ -22,9 +22,10 @@ fn main() -> Result<()> {
             println!("Running USB list...");
             tasks::send_command_test_loop()
         }
+        "decode" => tasks::decode_load_telemetry(&args[2]),
         _ => {
             eprintln!("Unknown command: {}", args[1]);
-            eprintln!("Available commands: serial, usb");
+            eprintln!("Available commands: serial, usb, decode");
             Err(color_eyre::eyre::eyre!("Unknown command"))
         }
     }
@@ -5,6 +5,7 @@ use std::{
 };
 
 use color_eyre::eyre::{anyhow, bail};
+use eps::{LoadRegistry, LoadTelemetry};
 use hidapi::HidApi;
 use serialport::SerialPortType;
 use std::thread; // If needed for delays
@@ -194,4 +195,37 @@ pub fn usb_descriptors() -> color_eyre::Result<()> {
     }
 
     bail!("nRF52840 USB device not found")
-}
\ No newline at end of file
+}
+
+/// Decodes a hex dump of EPS load telemetry, three bytes per channel, into named load states.
+pub fn decode_load_telemetry(hex: &str) -> color_eyre::Result<()> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("telemetry must be hexadecimal digits")
+    }
+    if hex.len() % (2 * LoadTelemetry::SIZE) != 0 {
+        bail!("telemetry length must be a multiple of {} bytes", LoadTelemetry::SIZE)
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let registry = LoadRegistry::standard();
+    for chunk in bytes.chunks_exact(LoadTelemetry::SIZE) {
+        let telemetry = LoadTelemetry::decode([chunk[0], chunk[1], chunk[2]]);
+        let name = registry.get_name(telemetry.id).unwrap_or("<unknown>");
+        println!(
+            "{:>3} {:<10} on={} powered={} tripped={} critical={} trips={}",
+            telemetry.id.0,
+            name,
+            telemetry.is_on,
+            telemetry.is_powered,
+            telemetry.is_tripped,
+            telemetry.is_critical,
+            telemetry.trip_count
+        );
+    }
+
+    Ok(())
+}